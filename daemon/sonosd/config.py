@@ -0,0 +1,122 @@
+"""Reading config.yaml: every known setting with the type it takes, so a typo
+or a wrong value is reported (with its line) instead of silently ignored."""
+
+import difflib
+from pathlib import Path
+
+import yaml
+
+CONFIG_PATH = Path(__file__).parent.parent / "config.yaml"
+
+# Setting -> the Python type its value must have. None (an empty value) is
+# always allowed and means "use the default": parse_config leaves it out.
+SCHEMA: dict[str, type] = {
+    "playlists": dict,
+    "speakers": dict,
+    "podcasts": dict,
+    "default_speaker": str,
+    "default_volume": int,
+    "host": str,
+    "port": int,
+    "playlist_sort": str,
+    "wrap_navigation": bool,
+    "speaker_sort": str,
+    "room_order": list,
+    "rooms_layout": str,
+    "terminal_title": bool,
+    "volume_osd": bool,
+    "playlist_volumes": dict,
+    "resume_playlists": list,
+    "next_up_seconds": int,
+    "change_notifications": list,
+    "confirm_quit_when_playing": bool,
+    "volume_balance_threshold": int,
+    "status_linger": float,
+    "volume_color_levels": list,
+    "event_log_file": str,
+    "keybindings": dict,
+    "show_clock": bool,
+    "clock_format": str,
+    "screensaver_minutes": int,
+    "screensaver_style": str,
+    "on_startup": str,
+    "do_not_disturb": list,
+    "party": dict,
+    "zones": dict,
+    "group_all_speakers": list,
+    "play_group": list,
+    "podcast_skip_forward": int,
+    "podcast_skip_back": int,
+    "podcast_refresh_minutes": int,
+}
+
+CHOICES: dict[str, tuple[str, ...]] = {
+    "playlist_sort": ("alphabetical", "popularity", "manual"),
+    "speaker_sort": ("name", "group", "daemon"),
+    "rooms_layout": ("groups", "flat"),
+    "clock_format": ("24h", "12h"),
+    "screensaver_style": ("clock", "dim"),
+}
+
+_KIND = {dict: "a mapping", list: "a list", str: "text", int: "a whole number", float: "a number", bool: "true or false"}
+
+
+def _fits(value, expected: type) -> bool:
+    if expected is str:
+        # Names and commands written bare (`default_speaker: 5`) are still text
+        return isinstance(value, (str, int, float)) and not isinstance(value, bool)
+    if expected is int:
+        return isinstance(value, int) and not isinstance(value, bool)
+    if expected is float:
+        return isinstance(value, (int, float)) and not isinstance(value, bool)
+    return isinstance(value, expected)
+
+
+def parse_config(text: str) -> tuple[dict | None, list[str]]:
+    """Validate config.yaml's contents. Returns the usable settings (bad ones
+    dropped, so their defaults apply) and a problem per dropped setting, each
+    led by its line number. Empty settings are left out, as if unset. The
+    settings are None when the file can't be read as YAML at all."""
+    try:
+        root = yaml.compose(text)
+        data = yaml.safe_load(text)
+    except yaml.YAMLError as e:
+        mark = getattr(e, "problem_mark", None)
+        where = f"line {mark.line + 1}: " if mark else ""
+        return None, [f"{where}{getattr(e, 'problem', None) or e}"]
+    if data is None:
+        return {}, []
+    if not isinstance(data, dict):
+        return None, ["the file must be a list of `setting: value` lines"]
+
+    lines = {}
+    if isinstance(root, yaml.MappingNode):
+        lines = {str(k.value): k.start_mark.line + 1 for k, _ in root.value}
+    config, problems = {}, []
+    for key, value in data.items():
+        at = f"line {lines[str(key)]}: " if str(key) in lines else ""
+        expected = SCHEMA.get(key)
+        if expected is None:
+            close = difflib.get_close_matches(str(key), SCHEMA, n=1)
+            hint = f" (did you mean {close[0]}?)" if close else ""
+            problems.append(f"{at}unknown setting {key}{hint}")
+            continue
+        if value is None:
+            continue
+        if not _fits(value, expected):
+            problems.append(f"{at}{key} should be {_KIND[expected]}, not {value!r}")
+            continue
+        if key in CHOICES and str(value) not in CHOICES[key]:
+            problems.append(f"{at}{key} should be one of {', '.join(CHOICES[key])}, not {value!r}")
+            continue
+        config[key] = value
+    return config, problems
+
+
+def load_config(path: Path = CONFIG_PATH) -> tuple[dict | None, list[str]]:
+    """`parse_config` on the file at `path`; an unreadable file counts as unparseable."""
+    try:
+        text = path.read_text()
+    except OSError as e:
+        return None, [f"can't read {path.name}: {e.strerror}"]
+    return parse_config(text)
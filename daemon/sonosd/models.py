@@ -9,9 +9,12 @@ class TrackInfo(BaseModel):
     duration: int
     position: int
     art_uri: str
+    rating: Optional[str] = None
 
 
 class SpeakerInfo(BaseModel):
+    uid: str
+    coordinator_uid: Optional[str]
     name: str
     alias: Optional[str]
     ip: str
@@ -20,3 +23,11 @@ class SpeakerInfo(BaseModel):
     state: str
     group_coordinator: Optional[str]
     track: Optional[TrackInfo]
+    bass: Optional[int] = None
+    treble: Optional[int] = None
+    loudness: Optional[bool] = None
+    play_mode: Optional[str] = None
+    cross_fade: Optional[bool] = None
+    model: Optional[str] = None
+    bonded_to: Optional[str] = None
+    bond: Optional[str] = None
@@ -1,9 +1,8 @@
 from fastapi import FastAPI, HTTPException
 from pydantic import BaseModel
 import uvicorn
-import yaml
-from pathlib import Path
 from soco.exceptions import SoCoUPnPException
+from .config import load_config
 from .sonos import SonosManager
 
 app = FastAPI(title="sonosd")
@@ -15,6 +14,11 @@ class PlayRequest(BaseModel):
     playlist: str
 
 
+class RateRequest(BaseModel):
+    speaker: str
+    rating: str
+
+
 class SpeakerRequest(BaseModel):
     speaker: str = "all"
 
@@ -24,6 +28,11 @@ class VolumeRequest(BaseModel):
     volume: int
 
 
+class MuteRequest(BaseModel):
+    speaker: str
+    muted: bool
+
+
 class GroupRequest(BaseModel):
     speakers: list[str]
 
@@ -56,10 +65,9 @@ podcast_manager = None  # PodcastManager, set on startup
 @app.on_event("startup")
 async def startup():
     global manager, podcast_manager
-    config_path = Path(__file__).parent.parent / "config.yaml"
-    with open(config_path) as f:
-        config = yaml.safe_load(f)
-    manager = SonosManager(config)
+    config, problems = load_config()
+    config = config or {}
+    manager = SonosManager(config, problems)
 
     from .podcast import PodcastManager
     podcasts = config.get("podcasts", {})
@@ -76,13 +84,22 @@ async def startup():
 
 
 @app.get("/speakers")
-def get_speakers():
+def get_speakers(speaker: str | None = None):
+    """Every speaker's status, or just `speaker`'s (UID, alias, or name)."""
+    if speaker is None:
+        targets = manager.get_all_speakers()
+    else:
+        try:
+            sp = manager.get_speaker(speaker)
+        except KeyError as e:
+            raise HTTPException(404, str(e))
+        targets = {sp.uid: sp}
     speakers = []
-    for name, sp in manager.get_all_speakers().items():
+    for uid, sp in targets.items():
         try:
             speakers.append(manager.get_speaker_info(sp))
         except Exception:
-            speakers.append({"name": name, "error": "unreachable"})
+            speakers.append({"name": sp.player_name, "uid": uid, "error": "unreachable"})
     return {"speakers": speakers}
 
 
@@ -110,19 +127,131 @@ async def reload_config():
     return {"status": "reloaded"}
 
 
+@app.post("/resync")
+def resync():
+    """Re-run speaker discovery now rather than at the next background sweep."""
+    manager.refresh()
+    return {"status": "resynced", "speakers": len(manager.get_all_speakers())}
+
+
+def _playlist_volumes(cfg: dict) -> dict[str, int]:
+    """Per-playlist preferred volumes, dropping entries that aren't 0-100 integers."""
+    raw = cfg.get("playlist_volumes") or {}
+    return {
+        str(alias): vol for alias, vol in raw.items()
+        if isinstance(vol, int) and not isinstance(vol, bool) and 0 <= vol <= 100
+    }
+
+
+def _party(cfg: dict) -> dict:
+    raw = cfg.get("party") or {}
+    vol = raw.get("volume")
+    valid_vol = isinstance(vol, int) and not isinstance(vol, bool) and 0 <= vol <= 100
+    playlist = raw.get("playlist")
+    return {
+        "speakers": [str(s) for s in raw.get("speakers") or []],
+        "volume": vol if valid_vol else None,
+        "playlist": str(playlist) if playlist else None,
+    }
+
+
+def _keybindings(cfg: dict) -> dict[str, list[str]]:
+    """Action name -> keys; a lone key may be given as a plain string."""
+    raw = cfg.get("keybindings") or {}
+    if not isinstance(raw, dict):
+        return {}
+    return {
+        str(action): [str(keys)] if isinstance(keys, str) else [str(k) for k in keys or []]
+        for action, keys in raw.items()
+    }
+
+
+def _zones(cfg: dict) -> dict[str, list[str]]:
+    """Zone name -> rooms; a zone given as one room name is a list of one."""
+    raw = cfg.get("zones") or {}
+    return {
+        str(zone): [str(rooms)] if isinstance(rooms, str) else [str(r) for r in rooms or []]
+        for zone, rooms in raw.items()
+    }
+
+
+CHANGE_NOTIFICATIONS = ("playback", "volume", "grouping")
+
+
+def _change_notifications(cfg: dict) -> list[str]:
+    raw = cfg.get("change_notifications")
+    if raw is None:
+        return list(CHANGE_NOTIFICATIONS)
+    return [c for c in raw if c in CHANGE_NOTIFICATIONS]
+
+
+def _volume_color_levels(cfg: dict) -> list[int]:
+    """Two volumes: at or below the first reads quiet, at or above the second
+    loud. An empty list turns the colours off; anything else is the default."""
+    raw = cfg.get("volume_color_levels")
+    if raw is None:
+        return [25, 80]
+    try:
+        levels = sorted(max(0, min(100, int(v))) for v in raw)
+    except (TypeError, ValueError):
+        return [25, 80]
+    return levels if len(levels) in (0, 2) else [25, 80]
+
+
 @app.get("/config")
 def get_config():
     raw = manager.config.get("playlist_sort", "alphabetical")
-    sort = raw if raw in ("alphabetical", "popularity") else "alphabetical"
+    sort = raw if raw in ("alphabetical", "popularity", "manual") else "alphabetical"
     skip_fwd = 30
     skip_back = 10
     if podcast_manager is not None:
         skip_fwd = podcast_manager.skip_forward
         skip_back = podcast_manager.skip_back
+    cfg = manager.config
+    clock_format = cfg.get("clock_format", "24h")
+    if clock_format not in ("12h", "24h"):
+        clock_format = "24h"
+    screensaver_style = cfg.get("screensaver_style", "clock")
+    if screensaver_style not in ("clock", "dim"):
+        screensaver_style = "clock"
+    rooms_layout = cfg.get("rooms_layout", "groups")
+    if rooms_layout not in ("groups", "flat"):
+        rooms_layout = "groups"
+    speaker_sort = cfg.get("speaker_sort", "name")
+    if speaker_sort not in ("name", "group", "daemon"):
+        speaker_sort = "name"
     return {
         "playlist_sort": sort,
         "podcast_skip_forward": skip_fwd,
         "podcast_skip_back": skip_back,
+        "wrap_navigation": bool(cfg.get("wrap_navigation", True)),
+        "speaker_sort": speaker_sort,
+        "room_order": [str(s) for s in cfg.get("room_order") or []],
+        "terminal_title": bool(cfg.get("terminal_title", False)),
+        "volume_osd": bool(cfg.get("volume_osd", True)),
+        "group_all_speakers": [str(s) for s in cfg.get("group_all_speakers") or []],
+        "play_group": [str(s) for s in cfg.get("play_group") or []],
+        "playlist_volumes": _playlist_volumes(cfg),
+        "resume_playlists": [str(a) for a in cfg.get("resume_playlists") or []],
+        "next_up_seconds": max(0, int(cfg.get("next_up_seconds", 10))),
+        "change_notifications": _change_notifications(cfg),
+        "do_not_disturb": [str(s) for s in cfg.get("do_not_disturb") or []],
+        "on_startup": str(cfg.get("on_startup") or ""),
+        "default_speaker": str(cfg.get("default_speaker") or ""),
+        "show_clock": bool(cfg.get("show_clock", False)),
+        "clock_format": clock_format,
+        "party": _party(cfg),
+        "zones": _zones(cfg),
+        "confirm_quit_when_playing": bool(cfg.get("confirm_quit_when_playing", False)),
+        "volume_balance_threshold": max(0, min(100, int(cfg.get("volume_balance_threshold", 15)))),
+        "volume_color_levels": _volume_color_levels(cfg),
+        "status_linger": max(0.5, min(10.0, float(cfg.get("status_linger") or 1.0))),
+        "event_log_file": str(cfg.get("event_log_file") or ""),
+        "keybindings": _keybindings(cfg),
+        "screensaver_minutes": max(0, int(cfg.get("screensaver_minutes", 0))),
+        "screensaver_style": screensaver_style,
+        "rooms_layout": rooms_layout,
+        "config_errors": [str(p) for p in manager.config_problems],
     }
 
 
@@ -139,6 +268,33 @@ def play(req: PlayRequest):
         raise HTTPException(404, str(e))
 
 
+@app.post("/playlist/add")
+def add_to_playlist(req: PlayRequest):
+    """Save the speaker's current track into a Sonos playlist."""
+    try:
+        speaker = manager.get_speaker(req.speaker)
+        title = manager.add_current_track_to_playlist(speaker, req.playlist)
+        return {"status": "added", "title": title, "playlist": req.playlist}
+    except KeyError as e:
+        raise HTTPException(404, str(e))
+    except ValueError as e:
+        raise HTTPException(422, str(e))
+
+
+@app.post("/rate")
+def rate(req: RateRequest):
+    """Thumb the playing track up or down; 422 when its service can't be rated."""
+    if req.rating not in ("up", "down"):
+        raise HTTPException(400, "rating must be up or down")
+    try:
+        manager.rate_current_track(manager.get_speaker(req.speaker), req.rating)
+        return {"status": "rated", "rating": req.rating}
+    except KeyError as e:
+        raise HTTPException(404, str(e))
+    except ValueError as e:
+        raise HTTPException(422, str(e))
+
+
 @app.post("/pause")
 def pause(req: SpeakerRequest):
     try:
@@ -165,6 +321,12 @@ def resume(req: SpeakerRequest):
         raise HTTPException(404, str(e))
 
 
+@app.get("/capabilities")
+def get_capabilities():
+    """Optional endpoints this daemon has, so clients can hide what it can't do."""
+    return {"capabilities": ["stop", "mute", "seek", "queue", "group_volume", "playlist_add", "rate", "resync"]}
+
+
 @app.post("/stop")
 def stop(req: SpeakerRequest):
     try:
@@ -192,6 +354,30 @@ def set_volume(req: VolumeRequest):
         raise HTTPException(404, str(e))
 
 
+@app.post("/group_volume")
+def set_group_volume(req: VolumeRequest):
+    """Set a whole zone's volume; Sonos scales each member proportionally."""
+    try:
+        vol = max(0, min(100, req.volume))
+        manager.get_speaker(req.speaker).group.volume = vol
+        return {"status": "ok", "volume": vol}
+    except KeyError as e:
+        raise HTTPException(404, str(e))
+
+
+@app.post("/mute")
+def set_mute(req: MuteRequest):
+    try:
+        if req.speaker == "all":
+            for sp in manager.get_all_speakers().values():
+                sp.mute = req.muted
+        else:
+            manager.get_speaker(req.speaker).mute = req.muted
+        return {"status": "ok", "muted": req.muted}
+    except KeyError as e:
+        raise HTTPException(404, str(e))
+
+
 @app.post("/group")
 def group(req: GroupRequest):
     try:
@@ -245,6 +431,14 @@ async def get_podcast_episodes(alias: str):
     return {"episodes": episodes}
 
 
+@app.get("/queue/{speaker}")
+def get_queue(speaker: str, limit: int = 5):
+    try:
+        return {"tracks": manager.get_upcoming(speaker, limit)}
+    except KeyError as e:
+        raise HTTPException(404, str(e))
+
+
 @app.post("/play_uri")
 def play_uri(req: PlayUriRequest):
     try:
@@ -306,9 +500,10 @@ async def refresh_podcasts():
 
 
 def main():
-    config_path = Path(__file__).parent.parent / "config.yaml"
-    with open(config_path) as f:
-        config = yaml.safe_load(f)
+    config, problems = load_config()
+    for problem in problems:
+        print(f"config.yaml: {problem}")
+    config = config or {}
     host = config.get("host", "127.0.0.1")
     port = config.get("port", 9271)
     uvicorn.run(app, host=host, port=port)
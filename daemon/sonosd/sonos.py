@@ -1,24 +1,41 @@
 import soco
+from soco.data_structures_entry import from_didl_string
+from soco.music_services import MusicService
 import threading
 import time
-import yaml
-from pathlib import Path
 from typing import Optional
+from urllib.parse import parse_qs, unquote, urlparse
+
+from .config import CONFIG_PATH, load_config
 
 _REDISCOVER_INTERVAL = 30  # seconds between background UPnP sweeps
+_EQ_REFRESH_INTERVAL = 60  # seconds a speaker's EQ reading is reused across polls
+_RATINGS_KEPT = 500  # most recent track ratings remembered for status
+# SMAPI rateItem values for a thumbs-up / thumbs-down service
+_SMAPI_RATINGS = {"up": 1, "down": 0}
 
 
 class SonosManager:
     """Manages speaker discovery and provides control methods."""
 
-    def __init__(self, config: dict):
+    def __init__(self, config: dict, problems: list[str] | None = None):
         self.config = config
+        # What was wrong with config.yaml when last read, served to the TUI
+        self.config_problems: list[str] = problems or []
+        # Keyed by UID: room names are not unique, and two rooms sharing one
+        # must not collapse into a single entry
         self._speakers: dict[str, soco.SoCo] = {}
+        # UID -> hardware model, once a speaker has told us
+        self._models: dict[str, str] = {}
+        # UID -> (when read, bass/treble/loudness)
+        self._eq: dict[str, tuple[float, dict]] = {}
+        # Track URI -> "up"/"down", for ratings sent through this daemon
+        self._ratings: dict[str, str] = {}
         self._lock = threading.Lock()
         self._alias_map: dict[str, str] = config.get("speakers", {})
         self._reverse_alias: dict[str, str] = {v: k for k, v in self._alias_map.items()}
         self._playlist_map: dict[str, str] = config.get("playlists", {})
-        self._config_path = Path(__file__).parent.parent / "config.yaml"
+        self._config_path = CONFIG_PATH
         self._config_mtime: float = self._config_path.stat().st_mtime
         self._last_config_check: float = 0.0
         self._discover()
@@ -31,7 +48,7 @@ class SonosManager:
             discovered = soco.discover(timeout=5)
             if discovered:
                 with self._lock:
-                    self._speakers = {sp.player_name: sp for sp in discovered}
+                    self._speakers = {sp.uid: sp for sp in discovered}
         except Exception:
             pass  # transient network error; retain existing speaker cache
 
@@ -55,14 +72,21 @@ class SonosManager:
         self._discover()
 
     def get_speaker(self, name_or_alias: str) -> soco.SoCo:
-        """Resolve alias or name to a SoCo instance."""
-        real_name = self._alias_map.get(name_or_alias, name_or_alias)
+        """Resolve UID, alias, or name to a SoCo instance.
+
+        The UID is tried first: room names are not unique, UIDs are.
+        """
         with self._lock:
-            if real_name in self._speakers:
-                return self._speakers[real_name]
+            if name_or_alias in self._speakers:
+                return self._speakers[name_or_alias]
+            real_name = self._alias_map.get(name_or_alias, name_or_alias)
+            for sp in self._speakers.values():
+                if sp.player_name == real_name:
+                    return sp
         raise KeyError(f"Speaker not found: {name_or_alias}")
 
     def get_all_speakers(self) -> dict[str, soco.SoCo]:
+        """Every discovered speaker, keyed by UID."""
         with self._lock:
             return dict(self._speakers)
 
@@ -73,6 +97,32 @@ class SonosManager:
             return speaker.group.coordinator
         return speaker
 
+    def get_upcoming(self, name_or_alias: str, limit: int = 5) -> list[dict]:
+        """Tracks queued after the current one, read from the group coordinator.
+
+        Empty when the group isn't playing from its queue (radio, line-in, TV).
+        """
+        coordinator = self.get_coordinator(name_or_alias)
+        media_uri = coordinator.get_current_media_info().get("uri", "")
+        if not media_uri.startswith("x-rincon-queue:"):
+            return []
+        try:
+            position = int(coordinator.get_current_track_info().get("playlist_position", 0))
+        except (TypeError, ValueError):
+            return []
+        if position < 1:
+            return []
+        # get_queue is 0-indexed, playlist_position 1-indexed: start=position is the next track
+        items = coordinator.get_queue(start=position, max_items=limit)
+        return [
+            {
+                "title": item.title,
+                "artist": getattr(item, "creator", "") or "",
+                "album": getattr(item, "album", "") or "",
+            }
+            for item in items
+        ]
+
     def get_speaker_info(self, speaker: soco.SoCo) -> dict:
         """Build the full status dict for a speaker."""
         info = speaker.get_current_transport_info()
@@ -90,6 +140,7 @@ class SonosManager:
                 "art_uri": track_info.get("album_art", ""),
                 "source": _detect_source(uri),
                 "quality": "",
+                "rating": self._ratings.get(uri),
             }
 
         coordinator_sp = speaker.group.coordinator if speaker.group else None
@@ -109,9 +160,16 @@ class SonosManager:
                     "art_uri": coord_track.get("album_art", ""),
                     "source": _detect_source(coord_uri),
                     "quality": "",
+                    "rating": self._ratings.get(coord_uri),
                 }
 
+        # Play mode belongs to the group — read it from the coordinator
+        mode_sp = coordinator_sp or speaker
+        bonded_to, bond = _bonding(speaker)
+
         return {
+            "uid": speaker.uid,
+            "coordinator_uid": coordinator_sp.uid if coordinator_sp else None,
             "name": speaker.player_name,
             "alias": self._reverse_alias.get(speaker.player_name),
             "ip": speaker.ip_address,
@@ -120,8 +178,82 @@ class SonosManager:
             "state": info.get("current_transport_state", "UNKNOWN"),
             "group_coordinator": coordinator_name,
             "track": track,
+            **self._speaker_eq(speaker),
+            "play_mode": mode_sp.play_mode,
+            "cross_fade": mode_sp.cross_fade,
+            "model": self._speaker_model(speaker),
+            "bonded_to": bonded_to,
+            "bond": bond,
         }
 
+    def _speaker_eq(self, speaker: soco.SoCo) -> dict:
+        """Bass, treble and loudness, re-read from the speaker at most every
+        _EQ_REFRESH_INTERVAL seconds rather than on every poll."""
+        now = time.monotonic()
+        cached = self._eq.get(speaker.uid)
+        if cached and now - cached[0] < _EQ_REFRESH_INTERVAL:
+            return cached[1]
+        eq = {"bass": speaker.bass, "treble": speaker.treble, "loudness": speaker.loudness}
+        self._eq[speaker.uid] = (now, eq)
+        return eq
+
+    def _speaker_model(self, speaker: soco.SoCo) -> str | None:
+        """The hardware model, asked for once per speaker; a failed ask is
+        retried on the next poll."""
+        model = self._models.get(speaker.uid) or _model_name(speaker)
+        if model:
+            self._models[speaker.uid] = model
+        return model
+
+    def add_current_track_to_playlist(self, speaker: soco.SoCo, playlist_name: str) -> str:
+        """Append the playing track to the Sonos playlist `playlist_name`
+        (created if missing) and return its title. Raises ValueError when
+        what's playing is a stream with no single track to save."""
+        if speaker.group:
+            speaker = speaker.group.coordinator
+        info = speaker.get_current_track_info()
+        uri = info.get("uri", "")
+        if not info.get("title") or _is_stream(uri) or not info.get("metadata"):
+            raise ValueError("Nothing addressable is playing")
+        try:
+            item = from_didl_string(info["metadata"])[0]
+        except Exception as e:
+            raise ValueError(f"Track can't be saved: {e}")
+
+        target = next(
+            (p for p in speaker.get_sonos_playlists() if p.title.lower() == playlist_name.lower()),
+            None,
+        )
+        if target is None:
+            target = speaker.create_sonos_playlist(playlist_name)
+        speaker.add_item_to_sonos_playlist(item, target)
+        return info["title"]
+
+    def rate_current_track(self, speaker: soco.SoCo, rating: str) -> None:
+        """Thumb the playing track "up" or "down" through its music service's
+        SMAPI rateItem call, and remember the rating for the track's status.
+        Raises ValueError when nothing is playing or the source can't be rated
+        (radio, line-in, a service without ratings or one not signed in)."""
+        if speaker.group:
+            speaker = speaker.group.coordinator
+        info = speaker.get_current_track_info()
+        if not info.get("title"):
+            raise ValueError("Nothing is playing")
+        uri = info.get("uri", "")
+        unrateable = ValueError(f"{_detect_source(uri) or 'This source'} doesn't take ratings")
+        item_id, service_id = _service_item(uri)
+        if item_id is None:
+            raise unrateable
+        try:
+            service = _music_service(service_id, speaker)
+            service.soap_client.call("rateItem", [("id", item_id), ("rating", _SMAPI_RATINGS[rating])])
+        except Exception as e:
+            raise unrateable from e
+        self._ratings.pop(uri, None)
+        self._ratings[uri] = rating
+        if len(self._ratings) > _RATINGS_KEPT:
+            self._ratings.pop(next(iter(self._ratings)))
+
     def play_favorite(self, speaker: soco.SoCo, favorite_name: str) -> None:
         """Play a Sonos Favorite by exact name or alias."""
         # Always operate on the group coordinator — playing on a follower raises SoCoSlaveException
@@ -187,10 +319,14 @@ class SonosManager:
             pass
 
     def reload_config(self) -> None:
-        """Re-read config.yaml and update alias/playlist maps."""
-        with open(self._config_path) as f:
-            config = yaml.safe_load(f)
+        """Re-read config.yaml and update alias/playlist maps. A file that no
+        longer parses keeps the current settings; its error is still reported."""
+        config, problems = load_config(self._config_path)
         with self._lock:
+            self.config_problems = problems
+            if config is None:
+                self._config_mtime = self._config_path.stat().st_mtime
+                return
             self.config = config
             self._alias_map = config.get("speakers", {})
             self._reverse_alias = {v: k for k, v in self._alias_map.items()}
@@ -205,6 +341,17 @@ class SonosManager:
 _podcast_uris: set[str] = set()
 
 
+_STREAM_PREFIXES = (
+    "x-rincon-mp3radio:", "x-sonosapi-stream:", "x-sonosapi-radio:",
+    "x-sonosapi-hls:", "aac:", "x-rincon-stream:", "x-sonos-htastream:",
+)
+
+
+def _is_stream(uri: str) -> bool:
+    """Radio, line-in and TV audio: nothing a playlist could hold."""
+    return not uri or uri.lower().startswith(_STREAM_PREFIXES)
+
+
 def _detect_source(uri: str) -> str:
     """Best-effort source detection from track URI."""
     if not uri:
@@ -233,6 +380,56 @@ def _detect_source(uri: str) -> str:
     return ""
 
 
+def _service_item(uri: str) -> tuple[str | None, str | None]:
+    """(SMAPI item id, service id) of a track streamed from a music service,
+    e.g. ("spotify:track:4u7E", "9") for x-sonos-spotify:spotify%3atrack%3a4u7E?sid=9;
+    (None, None) for anything without a service id."""
+    parsed = urlparse(uri)
+    sid = parse_qs(parsed.query).get("sid")
+    if not sid or not parsed.path:
+        return None, None
+    item_id = unquote(parsed.path)
+    if parsed.scheme.startswith("x-sonos-http"):
+        # x-sonos-http:track%3a123.mp3 — the id is what precedes the extension
+        item_id = item_id.rsplit(".", 1)[0]
+    return item_id, sid[0]
+
+
+def _music_service(service_id: str, speaker: soco.SoCo) -> MusicService:
+    """The music service `speaker` knows by `service_id`."""
+    for name in MusicService.get_all_music_services_names():
+        if str(MusicService.get_data_for_name(name).get("ServiceID")) == service_id:
+            return MusicService(name, device=speaker)
+    raise ValueError(f"no music service {service_id}")
+
+
+def _model_name(speaker: soco.SoCo) -> str | None:
+    """Hardware model, e.g. "Sonos One", or None when the speaker can't say."""
+    try:
+        model = speaker.get_speaker_info().get("model_name")
+    except Exception:
+        return None
+    return model if isinstance(model, str) else None
+
+
+def _bonding(speaker: soco.SoCo) -> tuple[str | None, str | None]:
+    """(bonded_to, bond): for a hidden stereo-pair half, surround or sub, the
+    UID of the visible speaker it belongs to; for that primary, "stereo" or
+    "surround". Bonded members share the room name within one zone group."""
+    members = speaker.group.members if speaker.group else []
+    partners = [m for m in members if m.uid != speaker.uid and m.player_name == speaker.player_name]
+    if not partners:
+        return None, None
+    if not speaker.is_visible:
+        primary = next((m for m in partners if m.is_visible), None)
+        return (primary.uid if primary else None), None
+    if speaker.has_satellites:
+        return None, "surround"
+    if any(not m.is_subwoofer for m in partners):
+        return None, "stereo"
+    return None, None
+
+
 def _parse_duration(time_str: str) -> int:
     """Parse 'H:MM:SS' to total seconds."""
     parts = time_str.split(":")
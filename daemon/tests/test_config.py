@@ -0,0 +1,55 @@
+from sonosd.config import load_config, parse_config
+
+
+def test_valid_config_loads_without_problems():
+    config, problems = parse_config(
+        "playlists:\n  altwave: Alt Wave\nport: 9271\nshow_clock: true\nclock_format: 12h\n"
+    )
+    assert problems == []
+    assert config == {
+        "playlists": {"altwave": "Alt Wave"},
+        "port": 9271,
+        "show_clock": True,
+        "clock_format": "12h",
+    }
+
+
+def test_bad_settings_are_dropped_and_reported_by_line():
+    config, problems = parse_config(
+        "port: 9271\n"
+        "screensaver_minuts: 5\n"
+        "show_clock: yes please\n"
+        "clock_format: 13h\n"
+        "default_speaker: 5\n"
+        "on_startup:\n"
+    )
+    assert config == {"port": 9271, "default_speaker": 5}
+    assert problems == [
+        "line 2: unknown setting screensaver_minuts (did you mean screensaver_minutes?)",
+        "line 3: show_clock should be true or false, not 'yes please'",
+        "line 4: clock_format should be one of 24h, 12h, not '13h'",
+    ]
+
+
+def test_empty_file_is_all_defaults():
+    assert parse_config("") == ({}, [])
+    assert parse_config("# nothing but comments\n") == ({}, [])
+
+
+def test_unparseable_file_reports_where():
+    config, problems = parse_config("port: 9271\nplaylists: [altwave\n")
+    assert config is None
+    assert len(problems) == 1 and problems[0].startswith("line 3:")
+    assert parse_config("- just\n- a list\n")[0] is None
+
+
+def test_missing_file_counts_as_unparseable(tmp_path):
+    config, problems = load_config(tmp_path / "config.yaml")
+    assert config is None
+    assert problems[0].startswith("can't read config.yaml")
+
+
+def test_numbers_take_whole_or_fractional_values():
+    assert parse_config("status_linger: 2\n") == ({"status_linger": 2}, [])
+    assert parse_config("status_linger: 1.5\n") == ({"status_linger": 1.5}, [])
+    assert parse_config("status_linger: slow\n")[1] == ["line 1: status_linger should be a number, not 'slow'"]
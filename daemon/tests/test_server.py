@@ -1,12 +1,14 @@
 import asyncio
 from unittest.mock import MagicMock, patch
 from fastapi.testclient import TestClient
+from sonosd.config import parse_config
 
 
 def _make_client():
     """Build a TestClient with a fully mocked SonosManager."""
     mock_speaker = MagicMock()
     mock_speaker.player_name = "cthulhu"
+    mock_speaker.uid = "RINCON_000E58CTHULHU01400"
     mock_speaker.ip_address = "192.168.1.99"
     mock_speaker.volume = 25
     mock_speaker.mute = False
@@ -25,7 +27,8 @@ def _make_client():
     mock_speaker.get_current_track_info.return_value = track
 
     mock_manager = MagicMock()
-    mock_manager.get_all_speakers.return_value = {"cthulhu": mock_speaker}
+    mock_manager.config = {}
+    mock_manager.get_all_speakers.return_value = {"RINCON_000E58CTHULHU01400": mock_speaker}
     mock_manager.get_speaker_info.return_value = {
         "name": "cthulhu",
         "alias": "cthulhu",
@@ -92,6 +95,29 @@ def test_volume_clamps_to_100():
     assert resp.json()["volume"] == 100
 
 
+def test_group_volume_sets_zone_volume():
+    client, _, mock_speaker = _make_client()
+    resp = client.post("/group_volume", json={"speaker": "cthulhu", "volume": 120})
+    assert resp.status_code == 200
+    assert resp.json()["volume"] == 100
+    assert mock_speaker.group.volume == 100
+
+
+def test_mute_sets_speaker_mute():
+    client, _, mock_speaker = _make_client()
+    resp = client.post("/mute", json={"speaker": "cthulhu", "muted": True})
+    assert resp.status_code == 200
+    assert resp.json()["muted"] is True
+    assert mock_speaker.mute is True
+
+
+def test_mute_unknown_speaker_returns_404():
+    client, mock_manager, _ = _make_client()
+    mock_manager.get_speaker.side_effect = KeyError("Speaker not found: ghost")
+    resp = client.post("/mute", json={"speaker": "ghost", "muted": True})
+    assert resp.status_code == 404
+
+
 def test_pause_returns_200():
     client, _, _ = _make_client()
     resp = client.post("/pause", json={"speaker": "cthulhu"})
@@ -138,6 +164,111 @@ def test_get_config_returns_playlist_sort():
     assert "playlist_sort" in resp.json()
 
 
+def test_get_config_playlist_sort_accepts_manual():
+    client, mock_manager, _ = _make_client()
+    mock_manager.config = {"playlist_sort": "manual"}
+    assert client.get("/config").json()["playlist_sort"] == "manual"
+    mock_manager.config = {"playlist_sort": "random"}
+    assert client.get("/config").json()["playlist_sort"] == "alphabetical"
+
+
+def test_get_config_wrap_navigation_defaults_true():
+    client, _, _ = _make_client()
+    assert client.get("/config").json()["wrap_navigation"] is True
+
+
+def test_get_config_wrap_navigation_from_config():
+    client, mock_manager, _ = _make_client()
+    mock_manager.config = {"wrap_navigation": False}
+    assert client.get("/config").json()["wrap_navigation"] is False
+
+
+def test_get_config_speaker_sort_rejects_unknown_value():
+    client, mock_manager, _ = _make_client()
+    mock_manager.config = {"speaker_sort": "shuffle"}
+    assert client.get("/config").json()["speaker_sort"] == "name"
+    mock_manager.config = {"speaker_sort": "group"}
+    assert client.get("/config").json()["speaker_sort"] == "group"
+
+
+def test_get_config_terminal_title_is_opt_in():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["terminal_title"] is False
+    mock_manager.config = {"terminal_title": True}
+    assert client.get("/config").json()["terminal_title"] is True
+
+
+def test_get_config_volume_osd_defaults_true():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["volume_osd"] is True
+    mock_manager.config = {"volume_osd": False}
+    assert client.get("/config").json()["volume_osd"] is False
+
+
+def test_get_config_empty_values_use_the_defaults():
+    client, mock_manager, _ = _make_client()
+    typed = [
+        "wrap_navigation", "terminal_title", "volume_osd", "show_clock",
+        "confirm_quit_when_playing", "next_up_seconds", "volume_balance_threshold",
+        "screensaver_minutes", "status_linger", "clock_format", "screensaver_style",
+        "rooms_layout", "speaker_sort", "playlist_sort",
+    ]
+    mock_manager.config, problems = parse_config("".join(f"{key}:\n" for key in typed))
+    assert problems == []
+    resp = client.get("/config")
+    assert resp.status_code == 200
+    body = resp.json()
+    assert body["wrap_navigation"] is True
+    assert body["volume_osd"] is True
+    assert body["terminal_title"] is False
+    assert body["show_clock"] is False
+    assert body["confirm_quit_when_playing"] is False
+    assert body["next_up_seconds"] == 10
+    assert body["volume_balance_threshold"] == 15
+    assert body["screensaver_minutes"] == 0
+    assert body["status_linger"] == 1.0
+    assert body["clock_format"] == "24h"
+    assert body["screensaver_style"] == "clock"
+    assert body["rooms_layout"] == "groups"
+    assert body["speaker_sort"] == "name"
+    assert body["playlist_sort"] == "alphabetical"
+
+
+def test_get_config_group_all_speakers():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["group_all_speakers"] == []
+    mock_manager.config = {"group_all_speakers": ["family", "cthulhu"]}
+    assert client.get("/config").json()["group_all_speakers"] == ["family", "cthulhu"]
+
+
+def test_get_config_play_group():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["play_group"] == []
+    mock_manager.config = {"play_group": ["family", "cthulhu"]}
+    assert client.get("/config").json()["play_group"] == ["family", "cthulhu"]
+
+
+def test_get_config_playlist_volumes_drops_invalid():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["playlist_volumes"] == {}
+    mock_manager.config = {"playlist_volumes": {"ambient": 15, "party": 150, "odd": "loud"}}
+    assert client.get("/config").json()["playlist_volumes"] == {"ambient": 15}
+
+
+def test_get_queue_returns_upcoming_tracks():
+    client, mock_manager, _ = _make_client()
+    mock_manager.get_upcoming.return_value = [{"title": "T", "artist": "A", "album": "B"}]
+    resp = client.get("/queue/cthulhu?limit=1")
+    assert resp.status_code == 200
+    assert resp.json() == {"tracks": [{"title": "T", "artist": "A", "album": "B"}]}
+    mock_manager.get_upcoming.assert_called_with("cthulhu", 1)
+
+
+def test_get_config_next_up_seconds_default():
+    client, _, _ = _make_client()
+    assert client.get("/config").json()["next_up_seconds"] == 10
+
+
 def test_reload_endpoint_returns_200():
     client, mock_manager, _ = _make_client()
     resp = client.post("/reload")
@@ -145,6 +276,14 @@ def test_reload_endpoint_returns_200():
     assert resp.json()["status"] == "reloaded"
 
 
+def test_resync_rediscovers_speakers():
+    client, mock_manager, _ = _make_client()
+    resp = client.post("/resync")
+    assert resp.status_code == 200
+    assert resp.json() == {"status": "resynced", "speakers": 1}
+    mock_manager.refresh.assert_called_once()
+
+
 def _make_podcast_client():
     """Build a TestClient with mocked SonosManager + real PodcastManager (temp DB)."""
     import tempfile, os
@@ -264,3 +403,209 @@ def test_get_config_returns_podcast_skip():
     data = resp.json()
     assert data["podcast_skip_forward"] == 30
     assert data["podcast_skip_back"] == 10
+
+
+def test_get_config_change_notifications():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["change_notifications"] == ["playback", "volume", "grouping"]
+    mock_manager.config = {"change_notifications": ["playback", "gossip"]}
+    assert client.get("/config").json()["change_notifications"] == ["playback"]
+    mock_manager.config = {"change_notifications": []}
+    assert client.get("/config").json()["change_notifications"] == []
+
+
+def test_get_config_do_not_disturb():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["do_not_disturb"] == []
+    mock_manager.config = {"do_not_disturb": ["nursery"]}
+    assert client.get("/config").json()["do_not_disturb"] == ["nursery"]
+
+
+def test_get_config_on_startup():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["on_startup"] == ""
+    mock_manager.config = {"on_startup": "play morning; vol 20"}
+    assert client.get("/config").json()["on_startup"] == "play morning; vol 20"
+
+
+def test_get_config_clock():
+    client, mock_manager, _ = _make_client()
+    body = client.get("/config").json()
+    assert body["show_clock"] is False
+    assert body["clock_format"] == "24h"
+    mock_manager.config = {"show_clock": True, "clock_format": "12h"}
+    body = client.get("/config").json()
+    assert body["show_clock"] is True
+    assert body["clock_format"] == "12h"
+    mock_manager.config = {"clock_format": "sundial"}
+    assert client.get("/config").json()["clock_format"] == "24h"
+
+
+def test_get_config_party():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["party"] == {"speakers": [], "volume": None, "playlist": None}
+    mock_manager.config = {"party": {"speakers": ["family", "cthulhu"], "volume": 40, "playlist": "altwave"}}
+    assert client.get("/config").json()["party"] == {
+        "speakers": ["family", "cthulhu"], "volume": 40, "playlist": "altwave",
+    }
+    mock_manager.config = {"party": {"volume": 400}}
+    assert client.get("/config").json()["party"]["volume"] is None
+
+
+def test_get_config_confirm_quit_when_playing():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["confirm_quit_when_playing"] is False
+    mock_manager.config = {"confirm_quit_when_playing": True}
+    assert client.get("/config").json()["confirm_quit_when_playing"] is True
+
+
+def test_capabilities_lists_optional_endpoints():
+    client, _, _ = _make_client()
+    caps = client.get("/capabilities").json()["capabilities"]
+    assert set(caps) == {"stop", "mute", "seek", "queue", "group_volume", "playlist_add", "rate", "resync"}
+
+
+def test_get_speakers_filtered_to_one():
+    client, mock_manager, _ = _make_client()
+    resp = client.get("/speakers", params={"speaker": "cthulhu"})
+    assert resp.status_code == 200
+    assert [s["name"] for s in resp.json()["speakers"]] == ["cthulhu"]
+    mock_manager.get_speaker.assert_called_with("cthulhu")
+    mock_manager.get_speaker.side_effect = KeyError("Speaker not found: nope")
+    assert client.get("/speakers", params={"speaker": "nope"}).status_code == 404
+
+
+def test_get_config_room_order():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["room_order"] == []
+    mock_manager.config = {"room_order": ["Living Room", "Kitchen"]}
+    assert client.get("/config").json()["room_order"] == ["Living Room", "Kitchen"]
+
+
+def test_get_config_volume_balance_threshold():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["volume_balance_threshold"] == 15
+    mock_manager.config = {"volume_balance_threshold": 500}
+    assert client.get("/config").json()["volume_balance_threshold"] == 100
+
+
+def test_get_config_event_log_file():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["event_log_file"] == ""
+    mock_manager.config = {"event_log_file": "/tmp/palantir.log"}
+    assert client.get("/config").json()["event_log_file"] == "/tmp/palantir.log"
+
+
+def test_get_config_keybindings():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["keybindings"] == {}
+    mock_manager.config = {"keybindings": {"down": ["J", "ctrl+n"], "quit": "Q"}}
+    assert client.get("/config").json()["keybindings"] == {"down": ["J", "ctrl+n"], "quit": ["Q"]}
+
+
+def test_get_config_resume_playlists():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["resume_playlists"] == []
+    mock_manager.config = {"resume_playlists": ["essential-mix"]}
+    assert client.get("/config").json()["resume_playlists"] == ["essential-mix"]
+
+
+def test_get_config_screensaver():
+    client, mock_manager, _ = _make_client()
+    body = client.get("/config").json()
+    assert body["screensaver_minutes"] == 0
+    assert body["screensaver_style"] == "clock"
+    mock_manager.config = {"screensaver_minutes": 10, "screensaver_style": "dim"}
+    body = client.get("/config").json()
+    assert body["screensaver_minutes"] == 10
+    assert body["screensaver_style"] == "dim"
+    mock_manager.config = {"screensaver_minutes": -5, "screensaver_style": "starfield"}
+    body = client.get("/config").json()
+    assert body["screensaver_minutes"] == 0
+    assert body["screensaver_style"] == "clock"
+
+
+def test_playlist_add_returns_saved_title():
+    client, mock_manager, mock_speaker = _make_client()
+    mock_manager.add_current_track_to_playlist.return_value = "Alt Wave Track"
+    resp = client.post("/playlist/add", json={"speaker": "cthulhu", "playlist": "Keepers"})
+    assert resp.status_code == 200
+    assert resp.json()["title"] == "Alt Wave Track"
+    mock_manager.add_current_track_to_playlist.assert_called_once_with(mock_speaker, "Keepers")
+
+
+def test_playlist_add_stream_returns_422():
+    client, mock_manager, _ = _make_client()
+    mock_manager.add_current_track_to_playlist.side_effect = ValueError("Nothing addressable is playing")
+    resp = client.post("/playlist/add", json={"speaker": "cthulhu", "playlist": "Keepers"})
+    assert resp.status_code == 422
+
+
+def test_get_config_default_speaker():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["default_speaker"] == ""
+    mock_manager.config = {"default_speaker": "cthulhu"}
+    assert client.get("/config").json()["default_speaker"] == "cthulhu"
+
+
+def test_rate_unrateable_service_returns_422():
+    client, mock_manager, mock_speaker = _make_client()
+    mock_manager.rate_current_track.side_effect = ValueError("Spotify doesn't take ratings")
+    resp = client.post("/rate", json={"speaker": "cthulhu", "rating": "up"})
+    assert resp.status_code == 422
+    mock_manager.rate_current_track.assert_called_once_with(mock_speaker, "up")
+    assert client.post("/rate", json={"speaker": "cthulhu", "rating": "meh"}).status_code == 400
+
+
+def test_rate_passes_the_thumb_to_the_manager():
+    client, mock_manager, mock_speaker = _make_client()
+    resp = client.post("/rate", json={"speaker": "cthulhu", "rating": "down"})
+    assert resp.status_code == 200
+    assert resp.json() == {"status": "rated", "rating": "down"}
+    mock_manager.rate_current_track.assert_called_once_with(mock_speaker, "down")
+
+
+def test_get_config_rooms_layout():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["rooms_layout"] == "groups"
+    mock_manager.config = {"rooms_layout": "flat"}
+    assert client.get("/config").json()["rooms_layout"] == "flat"
+    mock_manager.config = {"rooms_layout": "boxes"}
+    assert client.get("/config").json()["rooms_layout"] == "groups"
+
+
+def test_get_config_reports_config_problems():
+    client, mock_manager, _ = _make_client()
+    mock_manager.config_problems = []
+    assert client.get("/config").json()["config_errors"] == []
+    mock_manager.config_problems = ["line 2: unknown setting prot (did you mean port?)"]
+    assert client.get("/config").json()["config_errors"] == ["line 2: unknown setting prot (did you mean port?)"]
+
+
+def test_get_config_zones():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["zones"] == {}
+    mock_manager.config = {"zones": {"downstairs": ["family", "Kitchen"], "attic": "Loft"}}
+    assert client.get("/config").json()["zones"] == {"downstairs": ["family", "Kitchen"], "attic": ["Loft"]}
+
+
+def test_get_config_status_linger():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["status_linger"] == 1.0
+    mock_manager.config = {"status_linger": 2}
+    assert client.get("/config").json()["status_linger"] == 2.0
+    mock_manager.config = {"status_linger": 0}
+    assert client.get("/config").json()["status_linger"] == 1.0
+    mock_manager.config = {"status_linger": 99}
+    assert client.get("/config").json()["status_linger"] == 10.0
+
+
+def test_get_config_volume_color_levels():
+    client, mock_manager, _ = _make_client()
+    assert client.get("/config").json()["volume_color_levels"] == [25, 80]
+    mock_manager.config = {"volume_color_levels": [90, 10]}
+    assert client.get("/config").json()["volume_color_levels"] == [10, 90]
+    mock_manager.config = {"volume_color_levels": []}
+    assert client.get("/config").json()["volume_color_levels"] == []
+    mock_manager.config = {"volume_color_levels": [50]}
+    assert client.get("/config").json()["volume_color_levels"] == [25, 80]
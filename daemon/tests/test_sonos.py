@@ -1,3 +1,4 @@
+import pytest
 from sonosd.sonos import _parse_duration
 from unittest.mock import MagicMock, patch
 
@@ -28,6 +29,7 @@ def _make_manager(config=None):
 
     mock_speaker = MagicMock()
     mock_speaker.player_name = "cthulhu"
+    mock_speaker.uid = "RINCON_000E58CTHULHU01400"
     mock_speaker.ip_address = "192.168.1.99"
     mock_speaker.volume = 25
     mock_speaker.mute = False
@@ -45,6 +47,12 @@ def test_get_speaker_by_alias():
     assert result is mock_speaker
 
 
+def test_get_speaker_by_uid():
+    manager, mock_speaker = _make_manager()
+    result = manager.get_speaker("RINCON_000E58CTHULHU01400")
+    assert result is mock_speaker
+
+
 def test_get_speaker_unknown_raises():
     manager, _ = _make_manager()
     import pytest
@@ -55,7 +63,19 @@ def test_get_speaker_unknown_raises():
 def test_get_all_speakers_returns_dict():
     manager, mock_speaker = _make_manager()
     all_speakers = manager.get_all_speakers()
-    assert "cthulhu" in all_speakers
+    assert all_speakers == {"RINCON_000E58CTHULHU01400": mock_speaker}
+
+
+def test_rooms_sharing_a_name_stay_apart():
+    manager, mock_speaker = _make_manager()
+    twin = MagicMock()
+    twin.player_name = "cthulhu"
+    twin.uid = "RINCON_000E58CTHULHU02400"
+    with patch("soco.discover", return_value={mock_speaker, twin}):
+        manager.refresh()
+    assert len(manager.get_all_speakers()) == 2
+    assert manager.get_speaker("RINCON_000E58CTHULHU02400") is twin
+    assert manager.get_speaker("cthulhu") in (mock_speaker, twin)
 
 
 def test_get_coordinator_returns_self_when_not_grouped():
@@ -136,6 +156,8 @@ def test_get_speaker_info_follower_uses_coordinator_track():
     assert info["track"]["title"] == "Alt Wave Track"
     assert info["track"]["artist"] == "Some Artist"
     assert info["group_coordinator"] == "Family Room"
+    assert info["coordinator_uid"] == mock_coordinator.uid
+    assert info["uid"] == "RINCON_000E58CTHULHU01400"
     assert info["name"] == "cthulhu"
 
 
@@ -172,3 +194,158 @@ def test_get_playlists_map_returns_copy():
     # modifying the returned dict should not affect internal state
     result["injected"] = "value"
     assert "injected" not in manager.get_playlists_map()
+
+
+def test_get_speaker_info_reports_eq_and_play_mode_from_coordinator():
+    """EQ is per-speaker; play mode and crossfade come from the group coordinator."""
+    manager, mock_follower = _make_manager()
+
+    mock_coordinator = MagicMock()
+    mock_coordinator.player_name = "Family Room"
+    mock_coordinator.play_mode = "SHUFFLE"
+    mock_coordinator.cross_fade = True
+    mock_follower.group = MagicMock()
+    mock_follower.group.coordinator = mock_coordinator
+    mock_follower.bass = 3
+    mock_follower.treble = -2
+    mock_follower.loudness = True
+    mock_follower.play_mode = "NORMAL"
+
+    mock_follower.get_current_transport_info.return_value = {
+        "current_transport_state": "PLAYING"
+    }
+    mock_follower.get_current_track_info.return_value = {"title": ""}
+    mock_coordinator.get_current_track_info.return_value = {"title": ""}
+
+    info = manager.get_speaker_info(mock_follower)
+
+    assert info["bass"] == 3
+    assert info["treble"] == -2
+    assert info["loudness"] is True
+    assert info["play_mode"] == "SHUFFLE"
+    assert info["cross_fade"] is True
+
+
+def test_get_speaker_info_reports_model_when_known():
+    manager, mock_speaker = _make_manager()
+    mock_speaker.group = None
+    mock_speaker.get_current_transport_info.return_value = {"current_transport_state": "STOPPED"}
+    mock_speaker.get_current_track_info.return_value = {"title": ""}
+    mock_speaker.get_speaker_info.side_effect = OSError("unreachable")
+    assert manager.get_speaker_info(mock_speaker)["model"] is None
+    mock_speaker.get_speaker_info.side_effect = None
+    mock_speaker.get_speaker_info.return_value = {"model_name": "Sonos One"}
+    assert manager.get_speaker_info(mock_speaker)["model"] == "Sonos One"
+    mock_speaker.get_speaker_info.side_effect = OSError("unreachable")
+    assert manager.get_speaker_info(mock_speaker)["model"] == "Sonos One", "known once, kept"
+    assert mock_speaker.get_speaker_info.call_count == 2
+
+
+def test_get_speaker_info_rereads_eq_only_after_the_refresh_interval():
+    manager, mock_speaker = _make_manager()
+    mock_speaker.group = None
+    mock_speaker.get_current_transport_info.return_value = {"current_transport_state": "PLAYING"}
+    mock_speaker.get_current_track_info.return_value = {"title": ""}
+    mock_speaker.bass = 3
+    with patch("sonosd.sonos.time.monotonic", return_value=1000.0):
+        assert manager.get_speaker_info(mock_speaker)["bass"] == 3
+    mock_speaker.bass = -4
+    with patch("sonosd.sonos.time.monotonic", return_value=1030.0):
+        assert manager.get_speaker_info(mock_speaker)["bass"] == 3
+    with patch("sonosd.sonos.time.monotonic", return_value=1061.0):
+        assert manager.get_speaker_info(mock_speaker)["bass"] == -4
+
+
+def test_get_speaker_info_reports_stereo_pair_bonding():
+    manager, mock_speaker = _make_manager()
+    mock_speaker.player_name = "Living Room"
+    mock_speaker.uid = "RINCON_L"
+    mock_speaker.is_visible = True
+    mock_speaker.has_satellites = False
+    right = MagicMock(player_name="Living Room", uid="RINCON_R", is_visible=False, is_subwoofer=False)
+    kitchen = MagicMock(player_name="Kitchen", uid="RINCON_K", is_visible=True)
+    mock_speaker.group = MagicMock()
+    mock_speaker.group.coordinator = mock_speaker
+    mock_speaker.group.members = {mock_speaker, right, kitchen}
+    right.group = mock_speaker.group
+    mock_speaker.get_current_transport_info.return_value = {"current_transport_state": "STOPPED"}
+    mock_speaker.get_current_track_info.return_value = {"title": ""}
+    right.get_current_transport_info.return_value = {"current_transport_state": "STOPPED"}
+    right.get_current_track_info.return_value = {"title": ""}
+
+    info = manager.get_speaker_info(mock_speaker)
+    assert info["bond"] == "stereo"
+    assert info["bonded_to"] is None
+    info = manager.get_speaker_info(right)
+    assert info["bonded_to"] == "RINCON_L"
+    assert info["bond"] is None
+
+
+def test_add_current_track_refuses_streams():
+    manager, mock_speaker = _make_manager()
+    mock_speaker.group = None
+    mock_speaker.get_current_track_info.return_value = {
+        "title": "KEXP", "uri": "x-sonosapi-stream:s12345?sid=254", "metadata": "<DIDL-Lite/>",
+    }
+    with pytest.raises(ValueError):
+        manager.add_current_track_to_playlist(mock_speaker, "Keepers")
+    mock_speaker.add_item_to_sonos_playlist.assert_not_called()
+
+
+def test_rate_current_track_names_the_unrateable_source():
+    manager, mock_speaker = _make_manager()
+    mock_speaker.group = None
+    mock_speaker.get_current_track_info.return_value = {"title": "Go Outside", "uri": "x-sonos-spotify:track"}
+    with pytest.raises(ValueError, match="Spotify"):
+        manager.rate_current_track(mock_speaker, "down")
+
+
+def test_rate_current_track_sends_rate_item_and_reports_the_rating():
+    manager, mock_speaker = _make_manager()
+    mock_speaker.group = None
+    uri = "x-sonos-spotify:spotify%3atrack%3a4u7E?sid=9&flags=8224&sn=1"
+    mock_speaker.get_current_transport_info.return_value = {"current_transport_state": "PLAYING"}
+    mock_speaker.get_current_track_info.return_value = {"title": "Go Outside", "uri": uri}
+    service = MagicMock()
+    with patch("sonosd.sonos._music_service", return_value=service) as lookup:
+        manager.rate_current_track(mock_speaker, "up")
+    lookup.assert_called_once_with("9", mock_speaker)
+    service.soap_client.call.assert_called_once_with("rateItem", [("id", "spotify:track:4u7E"), ("rating", 1)])
+    assert manager.get_speaker_info(mock_speaker)["track"]["rating"] == "up"
+
+    service.soap_client.call.side_effect = Exception("SOAP fault")
+    with patch("sonosd.sonos._music_service", return_value=service):
+        with pytest.raises(ValueError, match="doesn't take ratings"):
+            manager.rate_current_track(mock_speaker, "down")
+    assert manager.get_speaker_info(mock_speaker)["track"]["rating"] == "up"
+
+
+def test_service_item_reads_id_and_service_from_the_uri():
+    from sonosd.sonos import _service_item
+    assert _service_item("x-sonos-http:track%3a123.mp3?sid=236&flags=8224") == ("track:123", "236")
+    assert _service_item("x-rincon-mp3radio://stream.example.com/live") == (None, None)
+    assert _service_item("x-file-cifs://nas/music/song.flac") == (None, None)
+
+
+def test_get_upcoming_returns_tracks_after_current():
+    manager, mock_speaker = _make_manager()
+    mock_speaker.group = None
+    mock_speaker.get_current_media_info.return_value = {"uri": "x-rincon-queue:RINCON_1#0"}
+    mock_speaker.get_current_track_info.return_value = {"playlist_position": "3"}
+    item = MagicMock()
+    item.title = "Black Country"
+    item.creator = "Ratboys"
+    item.album = "GN"
+    mock_speaker.get_queue.return_value = [item]
+    assert manager.get_upcoming("cthulhu", 1) == [
+        {"title": "Black Country", "artist": "Ratboys", "album": "GN"}
+    ]
+    mock_speaker.get_queue.assert_called_with(start=3, max_items=1)
+
+
+def test_get_upcoming_empty_for_streams():
+    manager, mock_speaker = _make_manager()
+    mock_speaker.group = None
+    mock_speaker.get_current_media_info.return_value = {"uri": "x-sonosapi-stream:s1234"}
+    assert manager.get_upcoming("cthulhu") == []
+    mock_speaker.get_queue.assert_not_called()
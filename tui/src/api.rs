@@ -4,6 +4,13 @@ const BASE_URL: &str = "http://127.0.0.1:9271";
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Speaker {
+    /// Sonos UID (`RINCON_…`) — unique even when room names collide.
+    /// Empty when talking to an older daemon that doesn't report it.
+    #[serde(default)]
+    pub uid: String,
+    /// UID of this speaker's group coordinator, if grouped.
+    #[serde(default)]
+    pub coordinator_uid: Option<String>,
     pub name: String,
     pub alias: Option<String>,
     pub ip: String,
@@ -12,6 +19,70 @@ pub struct Speaker {
     pub state: String,
     pub group_coordinator: Option<String>,
     pub track: Option<Track>,
+    #[serde(default)]
+    pub bass: Option<i8>,
+    #[serde(default)]
+    pub treble: Option<i8>,
+    #[serde(default)]
+    pub loudness: Option<bool>,
+    /// Sonos play mode of the group, e.g. "NORMAL", "SHUFFLE", "REPEAT_ONE".
+    #[serde(default)]
+    pub play_mode: Option<String>,
+    #[serde(default)]
+    pub cross_fade: Option<bool>,
+    /// Hardware model ("Sonos One"), from daemons that report it.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// UID of the speaker this one is bonded to (the other half of a stereo
+    /// pair, a surround or sub), from daemons that report bonding.
+    #[serde(default)]
+    pub bonded_to: Option<String>,
+    /// On the primary of a bonded set: "stereo" or "surround".
+    #[serde(default)]
+    pub bond: Option<String>,
+}
+
+impl Speaker {
+    /// Identifier for API calls and internal matching: the UID when known,
+    /// otherwise the alias or name.
+    pub fn id(&self) -> String {
+        if self.uid.is_empty() {
+            self.display_name().to_string()
+        } else {
+            self.uid.clone()
+        }
+    }
+
+    /// Alias if configured, otherwise the Sonos room name. For display only.
+    pub fn display_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Name as shown in Rooms: the display name, plus "(stereo)" and the like for a bonded set.
+    pub fn label(&self) -> String {
+        match &self.bond {
+            Some(bond) => format!("{} ({})", self.display_name(), bond),
+            None => self.display_name().to_string(),
+        }
+    }
+
+    /// Key this speaker is known by as a group coordinator (UID, or name on older daemons).
+    pub fn group_key(&self) -> &str {
+        if self.uid.is_empty() { &self.name } else { &self.uid }
+    }
+
+    /// The `group_key` of this speaker's coordinator, if it belongs to a group.
+    pub fn coordinator_key(&self) -> Option<&str> {
+        if self.uid.is_empty() {
+            self.group_coordinator.as_deref()
+        } else {
+            self.coordinator_uid.as_deref()
+        }
+    }
+
+    pub fn is_coordinator(&self) -> bool {
+        self.coordinator_key() == Some(self.group_key())
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -25,18 +96,34 @@ pub struct Track {
     pub source: String,
     #[serde(default)]
     pub quality: String,
+    /// "up" or "down" when the service reports a rating for this track.
+    #[serde(default)]
+    pub rating: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Playlist {
     pub alias: String,
     pub favorite_name: String,
+    /// Where the entry came from; set client-side, never sent by the daemon.
+    #[serde(skip)]
+    pub source: PlaylistSource,
+}
+
+/// `Configured` entries are aliases from config.yaml; `Favorite` entries are
+/// Sonos Favorites merged in automatically because no alias covers them.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PlaylistSource {
+    #[default]
+    Configured,
+    Favorite,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Podcast {
     pub alias: String,
     pub name: String,
+    #[allow(dead_code)]
     #[serde(default)]
     pub url: String,
     #[serde(default)]
@@ -58,6 +145,17 @@ pub struct Episode {
     pub played: u8,
 }
 
+/// An upcoming entry in a speaker's queue (`GET /queue/{speaker}`).
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct QueueItem {
+    pub title: String,
+    #[serde(default)]
+    pub artist: String,
+    #[allow(dead_code)]
+    #[serde(default)]
+    pub album: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct PlayRequest {
     pub speaker: String,
@@ -69,12 +167,24 @@ pub struct SpeakerRequest {
     pub speaker: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupRequest {
+    /// First entry becomes the coordinator.
+    pub speakers: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct VolumeRequest {
     pub speaker: String,
     pub volume: u8,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct MuteRequest {
+    pub speaker: String,
+    pub muted: bool,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct PlayUriRequest {
     pub speaker: String,
@@ -101,44 +211,420 @@ pub struct EpisodeProgressRequest {
     pub played: bool,
 }
 
+/// TUI-facing settings served by the daemon's `GET /config`.
+/// Missing keys fall back to the defaults below, so older daemons still work.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub playlist_sort: String,
+    pub podcast_skip_forward: i32,
+    pub podcast_skip_back: i32,
+    pub wrap_navigation: bool,
+    /// Speaker list order: "name", "group", or "daemon" (as received).
+    pub speaker_sort: String,
+    /// Explicit room order (names or aliases); when set it replaces `speaker_sort`.
+    pub room_order: Vec<String>,
+    /// Mirror the focused track in the terminal title (opt-in; some terminals misbehave).
+    pub terminal_title: bool,
+    /// Show a brief centered gauge when volume changes via keys.
+    pub volume_osd: bool,
+    /// Speakers `group all` / `g` gather (names or aliases); empty means every speaker.
+    pub group_all_speakers: Vec<String>,
+    /// Rooms (names or aliases, first leads) grouped before any playlist
+    /// starts from Enter or `play`; empty plays where you are.
+    pub play_group: Vec<String>,
+    /// Volume to switch to after starting a playlist, keyed by alias. Opt-in per playlist.
+    pub playlist_volumes: std::collections::HashMap<String, u8>,
+    /// Playlist aliases that resume where they were paused or stopped.
+    pub resume_playlists: Vec<String>,
+    /// Show the upcoming track this many seconds before the current one ends; 0 disables.
+    pub next_up_seconds: u64,
+    /// External changes to announce in the status line: "playback", "volume", "grouping".
+    pub change_notifications: Vec<String>,
+    /// Speakers (names or aliases) that "all" actions skip.
+    pub do_not_disturb: Vec<String>,
+    /// Command line (`;`-chained) run once after launch, e.g. "play morning".
+    pub on_startup: String,
+    /// Room (name or alias) selected at launch; empty keeps the first in the list.
+    pub default_speaker: String,
+    /// Show the local time in the status line.
+    pub show_clock: bool,
+    /// "24h" or "12h".
+    pub clock_format: String,
+    /// The `party` macro's setup.
+    pub party: PartyConfig,
+    /// Require a second `q` to quit while anything is playing.
+    pub confirm_quit_when_playing: bool,
+    /// How far (in volume points) a group member may stray from the group
+    /// average before its volume is colored; 0 disables.
+    pub volume_balance_threshold: u8,
+    /// Also write the event log to this file (truncated at launch); empty = off.
+    pub event_log_file: String,
+    /// Action name → keys, replacing that action's stock keys (see `keymap`).
+    pub keybindings: std::collections::HashMap<String, Vec<String>>,
+    /// Idle minutes (no input, nothing playing) before the screensaver; 0 = never.
+    pub screensaver_minutes: u32,
+    /// "clock" (a dim, drifting clock) or "dim" (the UI, darkened).
+    pub screensaver_style: String,
+    /// "groups" (Rooms switches to a per-group view once anything is grouped)
+    /// or "flat" (one list, followers indented under a group volume hint).
+    pub rooms_layout: String,
+    /// What the daemon found wrong in config.yaml (each led by its line);
+    /// those settings were dropped for their defaults.
+    pub config_errors: Vec<String>,
+    /// Zone name → rooms (names or aliases), for `play <zone> <playlist>`.
+    pub zones: std::collections::HashMap<String, Vec<String>>,
+    /// Multiplier on how long status messages stay up (2 = twice as long).
+    pub status_linger: f64,
+    /// [quiet, loud]: volume numbers at or below / at or above these are
+    /// coloured; empty keeps them uniform.
+    pub volume_color_levels: Vec<u8>,
+}
+
+/// `party:` in config.yaml — who to gather, how loud, and what to start.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PartyConfig {
+    /// Names or aliases, first leads; empty falls back to `group_all_speakers` / everyone.
+    pub speakers: Vec<String>,
+    pub volume: Option<u8>,
+    /// Playlist alias to start on the party group.
+    pub playlist: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            playlist_sort: "alphabetical".to_string(),
+            podcast_skip_forward: 30,
+            podcast_skip_back: 10,
+            wrap_navigation: true,
+            speaker_sort: "name".to_string(),
+            room_order: vec![],
+            terminal_title: false,
+            volume_osd: true,
+            group_all_speakers: vec![],
+            play_group: vec![],
+            playlist_volumes: std::collections::HashMap::new(),
+            resume_playlists: vec![],
+            next_up_seconds: 10,
+            change_notifications: vec!["playback".into(), "volume".into(), "grouping".into()],
+            do_not_disturb: vec![],
+            on_startup: String::new(),
+            default_speaker: String::new(),
+            show_clock: false,
+            clock_format: "24h".to_string(),
+            party: PartyConfig::default(),
+            confirm_quit_when_playing: false,
+            volume_balance_threshold: 15,
+            event_log_file: String::new(),
+            keybindings: std::collections::HashMap::new(),
+            screensaver_minutes: 0,
+            screensaver_style: "clock".to_string(),
+            rooms_layout: "groups".to_string(),
+            config_errors: vec![],
+            zones: std::collections::HashMap::new(),
+            status_linger: 1.0,
+            volume_color_levels: vec![25, 80],
+        }
+    }
+}
+
+/// The daemon answered 401: the configured credentials were missing or wrong.
+#[derive(Debug)]
+pub struct Unauthorized;
+
+impl std::fmt::Display for Unauthorized {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sonosd rejected our credentials (401)")
+    }
+}
+
+impl std::error::Error for Unauthorized {}
+
+pub fn is_unauthorized(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<Unauthorized>().is_some()
+}
+
+/// The daemon has no such route: it predates the feature.
+#[derive(Debug)]
+pub struct Unsupported;
+
+impl std::fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sonosd has no such endpoint")
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+pub fn is_unsupported(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<Unsupported>().is_some()
+}
+
+/// The daemon answered 422 to `/playlist/add`: what's playing is a stream
+/// (radio, line-in, TV) with no single track to keep.
+#[derive(Debug)]
+pub struct NoTrack;
+
+impl std::fmt::Display for NoTrack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "nothing addressable is playing")
+    }
+}
+
+impl std::error::Error for NoTrack {}
+
+pub fn is_no_track(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<NoTrack>().is_some()
+}
+
+/// The daemon answered 422 to `/rate`: the playing service takes no ratings.
+#[derive(Debug)]
+pub struct NoRating;
+
+impl std::fmt::Display for NoRating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "this service takes no ratings")
+    }
+}
+
+impl std::error::Error for NoRating {}
+
+pub fn is_no_rating(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<NoRating>().is_some()
+}
+
+/// A like or dislike for `SonosApi::rate`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Thumb {
+    Up,
+    Down,
+}
+
+impl Thumb {
+    /// How `/rate` and `Track::rating` spell it.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Thumb::Up => "up",
+            Thumb::Down => "down",
+        }
+    }
+}
+
+/// Optional daemon features the TUI can do without, as named by `/capabilities`.
+pub const OPTIONAL_FEATURES: &[&str] = &["stop", "mute", "seek", "queue", "group_volume", "playlist_add", "rate", "resync"];
+
+/// `send()` that turns a 401 into `Unauthorized` instead of letting it pass
+/// as an empty success or a confusing JSON decode error.
+trait SendChecked {
+    async fn send_checked(self) -> anyhow::Result<reqwest::Response>;
+    /// For endpoints newer daemons added: also turns FastAPI's bare route 404
+    /// into `Unsupported`. (A 404 with any other detail is a missing speaker.)
+    async fn send_optional(self) -> anyhow::Result<reqwest::Response>;
+}
+
+impl SendChecked for reqwest::RequestBuilder {
+    async fn send_checked(self) -> anyhow::Result<reqwest::Response> {
+        let resp = self.send().await?;
+        if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(Unauthorized.into());
+        }
+        Ok(resp)
+    }
+
+    async fn send_optional(self) -> anyhow::Result<reqwest::Response> {
+        let resp = self.send_checked().await?;
+        if resp.status() != reqwest::StatusCode::NOT_FOUND {
+            return Ok(resp);
+        }
+        let body: serde_json::Value = resp.json().await.unwrap_or_default();
+        match body["detail"].as_str() {
+            Some("Not Found") | None => Err(Unsupported.into()),
+            Some(detail) => anyhow::bail!("{}", detail.trim_matches(|c| c == '\'' || c == '"')),
+        }
+    }
+}
+
+/// Turn a non-2xx response into an error carrying the daemon's `detail` message
+/// (FastAPI's `HTTPException` body), falling back to the HTTP status.
+async fn daemon_error(resp: reqwest::Response) -> anyhow::Result<()> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body: serde_json::Value = resp.json().await.unwrap_or_default();
+    match body["detail"].as_str() {
+        // KeyError messages arrive repr-quoted, e.g. "'Favorite ... not found'"
+        Some(detail) => anyhow::bail!("{}", detail.trim_matches(|c| c == '\'' || c == '"')),
+        None => anyhow::bail!("{}", status),
+    }
+}
+
+/// Everything the TUI asks of the daemon. `ApiClient` speaks HTTP; tests swap in
+/// `MockApi`, which just records the calls.
+pub trait SonosApi {
+    async fn get_speakers(&self) -> anyhow::Result<Vec<Speaker>>;
+    /// Just the speaker with uid `speaker`, for polling a single room.
+    async fn get_speaker(&self, speaker: &str) -> anyhow::Result<Vec<Speaker>>;
+    async fn get_playlists(&self) -> anyhow::Result<Vec<Playlist>>;
+    async fn get_favorites(&self) -> anyhow::Result<Vec<String>>;
+    async fn get_config(&self) -> anyhow::Result<Config>;
+    /// Ask the daemon to start `playlist` on `speaker`. Whether it actually
+    /// began playing shows up in a later poll.
+    async fn play(&self, speaker: &str, playlist: &str) -> anyhow::Result<()>;
+    /// Append the track `speaker` is playing to the Sonos playlist `playlist`
+    /// (created if missing), returning the saved track's title.
+    async fn add_track_to_playlist(&self, speaker: &str, playlist: &str) -> anyhow::Result<String>;
+    /// Like or dislike the track `speaker` is playing, on services that allow it.
+    async fn rate(&self, speaker: &str, thumb: Thumb) -> anyhow::Result<()>;
+    async fn pause(&self, speaker: &str) -> anyhow::Result<()>;
+    async fn resume(&self, speaker: &str) -> anyhow::Result<()>;
+    async fn stop(&self, speaker: &str) -> anyhow::Result<()>;
+    /// Which of `OPTIONAL_FEATURES` the daemon has; errs on daemons that predate it.
+    async fn capabilities(&self) -> anyhow::Result<Vec<String>>;
+    async fn set_volume(&self, speaker: &str, volume: u8) -> anyhow::Result<()>;
+    /// Set a group's overall volume via its coordinator; Sonos scales the members.
+    async fn set_group_volume(&self, coordinator: &str, volume: u8) -> anyhow::Result<()>;
+    async fn set_mute(&self, speaker: &str, muted: bool) -> anyhow::Result<()>;
+    async fn next(&self, speaker: &str) -> anyhow::Result<()>;
+    async fn previous(&self, speaker: &str) -> anyhow::Result<()>;
+    async fn group_all(&self) -> anyhow::Result<()>;
+    async fn group(&self, speakers: &[String]) -> anyhow::Result<()>;
+    async fn ungroup_all(&self) -> anyhow::Result<()>;
+    /// Take one speaker out of its group, leaving the rest playing.
+    async fn ungroup(&self, speaker: &str) -> anyhow::Result<()>;
+    async fn reload(&self) -> anyhow::Result<()>;
+    /// Have the daemon look for speakers again now.
+    async fn resync(&self) -> anyhow::Result<()>;
+    async fn get_podcasts(&self) -> anyhow::Result<Vec<Podcast>>;
+    async fn get_episodes(&self, alias: &str) -> anyhow::Result<Vec<Episode>>;
+    async fn get_queue(&self, speaker: &str, limit: usize) -> anyhow::Result<Vec<QueueItem>>;
+    async fn play_uri(&self, speaker: &str, uri: &str, title: &str) -> anyhow::Result<()>;
+    async fn skip(&self, speaker: &str, seconds: i32) -> anyhow::Result<()>;
+    async fn seek(&self, speaker: &str, position: u64) -> anyhow::Result<()>;
+    async fn save_episode_progress(&self, episode_id: &str, position: u64, played: bool) -> anyhow::Result<()>;
+    async fn refresh_podcasts(&self) -> anyhow::Result<()>;
+}
+
+/// Credentials for a daemon behind an authenticating proxy.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Auth {
+    Basic { user: String, password: String },
+    Bearer(String),
+}
+
+impl Auth {
+    /// From `SONOSD_TOKEN`, else `SONOSD_USER` (+ optional `SONOSD_PASSWORD`).
+    pub fn from_env() -> Option<Self> {
+        let var = |k| std::env::var(k).ok().filter(|v: &String| !v.is_empty());
+        if let Some(token) = var("SONOSD_TOKEN") {
+            return Some(Auth::Bearer(token));
+        }
+        let user = var("SONOSD_USER")?;
+        Some(Auth::Basic { user, password: var("SONOSD_PASSWORD").unwrap_or_default() })
+    }
+
+    fn apply(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self {
+            Auth::Basic { user, password } => request.basic_auth(user, Some(password)),
+            Auth::Bearer(token) => request.bearer_auth(token),
+        }
+    }
+}
+
 pub struct ApiClient {
     client: reqwest::Client,
     base_url: String,
+    auth: Option<Auth>,
 }
 
 impl ApiClient {
-    pub fn new() -> Self {
-        Self {
-            client: reqwest::Client::new(),
-            base_url: BASE_URL.to_string(),
+    /// Talk to `base_url` (`http://` or `https://` — reqwest's default TLS
+    /// handles the latter), sending `auth` with every request.
+    pub fn with_auth(base_url: &str, auth: Option<Auth>) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder().build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            auth,
+        })
+    }
+
+    /// `SONOSD_URL` (default `http://127.0.0.1:9271`) plus any credentials from `Auth::from_env`.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let url = std::env::var("SONOSD_URL").unwrap_or_else(|_| BASE_URL.to_string());
+        Self::with_auth(&url, Auth::from_env())
+    }
+
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        self.authed(self.client.get(format!("{}{}", self.base_url, path)))
+    }
+
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        self.authed(self.client.post(format!("{}{}", self.base_url, path)))
+    }
+
+    fn authed(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.auth {
+            Some(auth) => auth.apply(request),
+            None => request,
         }
     }
 
-    pub async fn get_speakers(&self) -> anyhow::Result<Vec<Speaker>> {
-        let resp: serde_json::Value = self.client
-            .get(format!("{}/speakers", self.base_url))
-            .send().await?
+    /// Where sonosd should be listening, when that's this machine.
+    pub fn local_addr(&self) -> Option<String> {
+        local_addr(&self.base_url)
+    }
+}
+
+/// `host:port` of `base_url` when it names this machine (localhost or a
+/// loopback address); None for a remote daemon or an unreadable URL.
+pub fn local_addr(base_url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(base_url).ok()?;
+    let host = url.host_str()?;
+    let loopback = host.trim_start_matches('[').trim_end_matches(']')
+        .parse::<std::net::IpAddr>()
+        .is_ok_and(|ip| ip.is_loopback());
+    if !loopback && !host.eq_ignore_ascii_case("localhost") {
+        return None;
+    }
+    Some(format!("{}:{}", host, url.port_or_known_default()?))
+}
+
+impl SonosApi for ApiClient {
+    async fn get_speakers(&self) -> anyhow::Result<Vec<Speaker>> {
+        let resp: serde_json::Value = self.get("/speakers")
+            .send_checked().await?
             .json().await?;
         let speakers: Vec<Speaker> = serde_json::from_value(resp["speakers"].clone())?;
         Ok(speakers)
     }
 
-    pub async fn get_playlists(&self) -> anyhow::Result<Vec<Playlist>> {
-        let resp: serde_json::Value = self.client
-            .get(format!("{}/playlists", self.base_url))
-            .send().await?
+    async fn get_speaker(&self, speaker: &str) -> anyhow::Result<Vec<Speaker>> {
+        let resp: serde_json::Value = self.get("/speakers")
+            .query(&[("speaker", speaker)])
+            .send_checked().await?
+            .json().await?;
+        let mut speakers: Vec<Speaker> = serde_json::from_value(resp["speakers"].clone())?;
+        // Older daemons ignore the filter and answer with every room
+        speakers.retain(|s| s.id() == speaker);
+        Ok(speakers)
+    }
+
+    async fn get_playlists(&self) -> anyhow::Result<Vec<Playlist>> {
+        let resp: serde_json::Value = self.get("/playlists")
+            .send_checked().await?
             .json().await?;
         let map: std::collections::HashMap<String, String> =
             serde_json::from_value(resp["playlists"].clone())?;
         Ok(map.into_iter().map(|(alias, favorite_name)| {
-            Playlist { alias, favorite_name }
+            Playlist { alias, favorite_name, source: PlaylistSource::Configured }
         }).collect())
     }
 
-    pub async fn get_favorites(&self) -> anyhow::Result<Vec<String>> {
-        let resp: serde_json::Value = self.client
-            .get(format!("{}/favorites", self.base_url))
-            .send().await?
+    async fn get_favorites(&self) -> anyhow::Result<Vec<String>> {
+        let resp: serde_json::Value = self.get("/favorites")
+            .send_checked().await?
             .json().await?;
         let favs = resp["favorites"]
             .as_array()
@@ -149,158 +635,473 @@ impl ApiClient {
             .collect())
     }
 
-    pub async fn get_playlist_sort(&self) -> anyhow::Result<String> {
-        let resp: serde_json::Value = self.client
-            .get(format!("{}/config", self.base_url))
-            .send().await?
+    async fn get_config(&self) -> anyhow::Result<Config> {
+        let config: Config = self.get("/config")
+            .send_checked().await?
             .json().await?;
-        Ok(resp["playlist_sort"].as_str().unwrap_or("alphabetical").to_string())
+        Ok(config)
     }
 
-    pub async fn play(&self, speaker: &str, playlist: &str) -> anyhow::Result<()> {
-        self.client.post(format!("{}/play", self.base_url))
+    async fn play(&self, speaker: &str, playlist: &str) -> anyhow::Result<()> {
+        let resp = self.post("/play")
             .json(&PlayRequest {
                 speaker: speaker.to_string(),
                 playlist: playlist.to_string(),
             })
-            .send().await?;
+            .send_checked().await?;
+        daemon_error(resp).await
+    }
+
+    async fn add_track_to_playlist(&self, speaker: &str, playlist: &str) -> anyhow::Result<String> {
+        let resp = self.post("/playlist/add")
+            .json(&PlayRequest {
+                speaker: speaker.to_string(),
+                playlist: playlist.to_string(),
+            })
+            .send_optional().await?;
+        if resp.status() == reqwest::StatusCode::UNPROCESSABLE_ENTITY {
+            return Err(NoTrack.into());
+        }
+        if !resp.status().is_success() {
+            return daemon_error(resp).await.map(|()| String::new());
+        }
+        let body: serde_json::Value = resp.json().await?;
+        Ok(body["title"].as_str().unwrap_or_default().to_string())
+    }
+
+    async fn rate(&self, speaker: &str, thumb: Thumb) -> anyhow::Result<()> {
+        let resp = self.post("/rate")
+            .json(&serde_json::json!({"speaker": speaker, "rating": thumb.as_str()}))
+            .send_optional().await?;
+        if resp.status() == reqwest::StatusCode::UNPROCESSABLE_ENTITY {
+            return Err(NoRating.into());
+        }
+        daemon_error(resp).await
+    }
+
+    async fn pause(&self, speaker: &str) -> anyhow::Result<()> {
+        self.post("/pause")
+            .json(&SpeakerRequest { speaker: speaker.to_string() })
+            .send_checked().await?;
         Ok(())
     }
 
-    pub async fn pause(&self, speaker: &str) -> anyhow::Result<()> {
-        self.client.post(format!("{}/pause", self.base_url))
+    async fn resume(&self, speaker: &str) -> anyhow::Result<()> {
+        self.post("/resume")
             .json(&SpeakerRequest { speaker: speaker.to_string() })
-            .send().await?;
+            .send_checked().await?;
         Ok(())
     }
 
-    pub async fn resume(&self, speaker: &str) -> anyhow::Result<()> {
-        self.client.post(format!("{}/resume", self.base_url))
+    /// Unlike pause, releases the stream — radio and line-in sources stop for real.
+    async fn stop(&self, speaker: &str) -> anyhow::Result<()> {
+        self.post("/stop")
             .json(&SpeakerRequest { speaker: speaker.to_string() })
-            .send().await?;
+            .send_optional().await?;
         Ok(())
     }
 
-    pub async fn set_volume(&self, speaker: &str, volume: u8) -> anyhow::Result<()> {
-        self.client.post(format!("{}/volume", self.base_url))
+    async fn capabilities(&self) -> anyhow::Result<Vec<String>> {
+        let resp: serde_json::Value = self.get("/capabilities")
+            .send_optional().await?
+            .json().await?;
+        Ok(serde_json::from_value(resp["capabilities"].clone())?)
+    }
+
+    async fn set_volume(&self, speaker: &str, volume: u8) -> anyhow::Result<()> {
+        self.post("/volume")
             .json(&VolumeRequest {
                 speaker: speaker.to_string(),
                 volume,
             })
-            .send().await?;
+            .send_checked().await?;
+        Ok(())
+    }
+
+    async fn set_group_volume(&self, coordinator: &str, volume: u8) -> anyhow::Result<()> {
+        self.post("/group_volume")
+            .json(&VolumeRequest {
+                speaker: coordinator.to_string(),
+                volume,
+            })
+            .send_optional().await?;
         Ok(())
     }
 
-    pub async fn next(&self, speaker: &str) -> anyhow::Result<()> {
-        self.client.post(format!("{}/next", self.base_url))
+    async fn set_mute(&self, speaker: &str, muted: bool) -> anyhow::Result<()> {
+        self.post("/mute")
+            .json(&MuteRequest {
+                speaker: speaker.to_string(),
+                muted,
+            })
+            .send_optional().await?;
+        Ok(())
+    }
+
+    async fn next(&self, speaker: &str) -> anyhow::Result<()> {
+        self.post("/next")
             .json(&SpeakerRequest { speaker: speaker.to_string() })
-            .send().await?
+            .send_checked().await?
             .error_for_status()?;
         Ok(())
     }
 
-    pub async fn previous(&self, speaker: &str) -> anyhow::Result<()> {
-        self.client.post(format!("{}/previous", self.base_url))
+    async fn previous(&self, speaker: &str) -> anyhow::Result<()> {
+        self.post("/previous")
             .json(&SpeakerRequest { speaker: speaker.to_string() })
-            .send().await?
+            .send_checked().await?
             .error_for_status()?;
         Ok(())
     }
 
-    pub async fn group_all(&self) -> anyhow::Result<()> {
-        self.client.post(format!("{}/group", self.base_url))
+    async fn group_all(&self) -> anyhow::Result<()> {
+        self.post("/group")
             .json(&serde_json::json!({"speakers": ["all"]}))
-            .send().await?;
+            .send_checked().await?;
         Ok(())
     }
 
-    pub async fn ungroup_all(&self) -> anyhow::Result<()> {
-        self.client.post(format!("{}/ungroup", self.base_url))
-            .json(&SpeakerRequest { speaker: "all".to_string() })
-            .send().await?;
+    async fn group(&self, speakers: &[String]) -> anyhow::Result<()> {
+        self.post("/group")
+            .json(&GroupRequest { speakers: speakers.to_vec() })
+            .send_checked().await?;
         Ok(())
     }
 
-    pub async fn reload(&self) -> anyhow::Result<()> {
-        self.client
-            .post(format!("{}/reload", self.base_url))
-            .send().await?;
+    async fn ungroup_all(&self) -> anyhow::Result<()> {
+        self.ungroup("all").await
+    }
+
+    async fn ungroup(&self, speaker: &str) -> anyhow::Result<()> {
+        self.post("/ungroup")
+            .json(&SpeakerRequest { speaker: speaker.to_string() })
+            .send_checked().await?;
+        Ok(())
+    }
+
+    async fn reload(&self) -> anyhow::Result<()> {
+        self.post("/reload")
+            .send_checked().await?;
         Ok(())
     }
 
-    pub async fn get_podcasts(&self) -> anyhow::Result<Vec<Podcast>> {
-        let resp: serde_json::Value = self.client
-            .get(format!("{}/podcasts", self.base_url))
-            .send().await?
+    async fn resync(&self) -> anyhow::Result<()> {
+        self.post("/resync")
+            .send_optional().await?;
+        Ok(())
+    }
+
+    async fn get_podcasts(&self) -> anyhow::Result<Vec<Podcast>> {
+        let resp: serde_json::Value = self.get("/podcasts")
+            .send_checked().await?
             .json().await?;
         let podcasts: Vec<Podcast> = serde_json::from_value(resp["podcasts"].clone())?;
         Ok(podcasts)
     }
 
-    pub async fn get_episodes(&self, alias: &str) -> anyhow::Result<Vec<Episode>> {
-        let resp: serde_json::Value = self.client
-            .get(format!("{}/podcasts/{}/episodes", self.base_url, alias))
-            .send().await?
+    async fn get_episodes(&self, alias: &str) -> anyhow::Result<Vec<Episode>> {
+        let resp: serde_json::Value = self.get(&format!("/podcasts/{}/episodes", alias))
+            .send_checked().await?
             .json().await?;
         let episodes: Vec<Episode> = serde_json::from_value(resp["episodes"].clone())?;
         Ok(episodes)
     }
 
-    pub async fn play_uri(&self, speaker: &str, uri: &str, title: &str) -> anyhow::Result<()> {
-        self.client.post(format!("{}/play_uri", self.base_url))
+    async fn get_queue(&self, speaker: &str, limit: usize) -> anyhow::Result<Vec<QueueItem>> {
+        let resp: serde_json::Value = self.get(&format!("/queue/{}", speaker))
+            .query(&[("limit", limit)])
+            .send_optional().await?
+            .json().await?;
+        let tracks: Vec<QueueItem> = serde_json::from_value(resp["tracks"].clone())?;
+        Ok(tracks)
+    }
+
+    async fn play_uri(&self, speaker: &str, uri: &str, title: &str) -> anyhow::Result<()> {
+        self.post("/play_uri")
             .json(&PlayUriRequest {
                 speaker: speaker.to_string(),
                 uri: uri.to_string(),
                 title: title.to_string(),
             })
-            .send().await?;
+            .send_checked().await?;
         Ok(())
     }
 
-    pub async fn skip(&self, speaker: &str, seconds: i32) -> anyhow::Result<()> {
-        self.client.post(format!("{}/skip", self.base_url))
+    async fn skip(&self, speaker: &str, seconds: i32) -> anyhow::Result<()> {
+        self.post("/skip")
             .json(&SkipRequest {
                 speaker: speaker.to_string(),
                 seconds,
             })
-            .send().await?;
+            .send_checked().await?;
         Ok(())
     }
 
-    pub async fn seek(&self, speaker: &str, position: u64) -> anyhow::Result<()> {
-        self.client.post(format!("{}/seek", self.base_url))
+    async fn seek(&self, speaker: &str, position: u64) -> anyhow::Result<()> {
+        self.post("/seek")
             .json(&SeekRequest {
                 speaker: speaker.to_string(),
                 position,
             })
-            .send().await?;
+            .send_optional().await?;
         Ok(())
     }
 
-    pub async fn save_episode_progress(&self, episode_id: &str, position: u64, played: bool) -> anyhow::Result<()> {
-        self.client.post(format!("{}/podcasts/episode/progress", self.base_url))
+    async fn save_episode_progress(&self, episode_id: &str, position: u64, played: bool) -> anyhow::Result<()> {
+        self.post("/podcasts/episode/progress")
             .json(&EpisodeProgressRequest {
                 episode_id: episode_id.to_string(),
                 position,
                 played,
             })
-            .send().await?;
+            .send_checked().await?;
         Ok(())
     }
 
-    pub async fn refresh_podcasts(&self) -> anyhow::Result<()> {
-        self.client.post(format!("{}/podcasts/refresh", self.base_url))
-            .send().await?;
+    async fn refresh_podcasts(&self) -> anyhow::Result<()> {
+        self.post("/podcasts/refresh")
+            .send_checked().await?;
         Ok(())
     }
+}
 
-    pub async fn get_skip_config(&self) -> anyhow::Result<(i32, i32)> {
-        let resp: serde_json::Value = self.client
-            .get(format!("{}/config", self.base_url))
-            .send().await?
-            .json().await?;
-        let fwd = resp["podcast_skip_forward"].as_i64().unwrap_or(30) as i32;
-        let back = resp["podcast_skip_back"].as_i64().unwrap_or(10) as i32;
-        Ok((fwd, back))
+/// In-memory stand-in for the daemon: getters serve the canned data below and
+/// every call is logged as e.g. `"set_volume RINCON_a 30"` for assertions.
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockApi {
+    pub speakers: Vec<Speaker>,
+    pub playlists: Vec<Playlist>,
+    pub favorites: Vec<String>,
+    pub config: Config,
+    pub podcasts: Vec<Podcast>,
+    pub episodes: Vec<Episode>,
+    pub queue: Vec<QueueItem>,
+    /// When set, `play` fails with this message, as the daemon would on a 404.
+    pub play_error: Option<String>,
+    /// Optional features this mock daemon lacks; calling one fails with `Unsupported`.
+    pub missing: Vec<&'static str>,
+    pub calls: std::cell::RefCell<Vec<String>>,
+}
+
+#[cfg(test)]
+impl MockApi {
+    fn record(&self, call: String) {
+        self.calls.borrow_mut().push(call);
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.borrow().clone()
+    }
+
+    /// Answer like a daemon without `feature`'s endpoint: a bare 404.
+    fn require(&self, feature: &str) -> anyhow::Result<()> {
+        if self.missing.contains(&feature) {
+            return Err(Unsupported.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl SonosApi for MockApi {
+    async fn get_speakers(&self) -> anyhow::Result<Vec<Speaker>> {
+        Ok(self.speakers.clone())
+    }
+
+    async fn get_speaker(&self, speaker: &str) -> anyhow::Result<Vec<Speaker>> {
+        self.record(format!("get_speaker {}", speaker));
+        Ok(self.speakers.iter().filter(|s| s.id() == speaker).cloned().collect())
+    }
+
+    async fn get_playlists(&self) -> anyhow::Result<Vec<Playlist>> {
+        Ok(self.playlists.clone())
+    }
+
+    async fn get_favorites(&self) -> anyhow::Result<Vec<String>> {
+        Ok(self.favorites.clone())
+    }
+
+    async fn get_config(&self) -> anyhow::Result<Config> {
+        Ok(self.config.clone())
+    }
+
+    async fn play(&self, speaker: &str, playlist: &str) -> anyhow::Result<()> {
+        self.record(format!("play {} {}", speaker, playlist));
+        match &self.play_error {
+            Some(err) => anyhow::bail!("{}", err),
+            None => Ok(()),
+        }
+    }
+
+    async fn add_track_to_playlist(&self, speaker: &str, playlist: &str) -> anyhow::Result<String> {
+        self.record(format!("add_track_to_playlist {} {}", speaker, playlist));
+        self.require("playlist_add")?;
+        self.speakers.iter()
+            .find(|s| s.id() == speaker)
+            .and_then(|s| s.track.as_ref())
+            .filter(|t| t.duration > 0)
+            .map(|t| t.title.clone())
+            .ok_or_else(|| NoTrack.into())
+    }
+
+    async fn rate(&self, speaker: &str, thumb: Thumb) -> anyhow::Result<()> {
+        self.record(format!("rate {} {}", speaker, thumb.as_str()));
+        self.require("rate")?;
+        // Streams stand in for services without ratings
+        let rateable = self.speakers.iter()
+            .find(|s| s.id() == speaker)
+            .and_then(|s| s.track.as_ref())
+            .is_some_and(|t| t.duration > 0);
+        if rateable { Ok(()) } else { Err(NoRating.into()) }
+    }
+
+    async fn pause(&self, speaker: &str) -> anyhow::Result<()> {
+        self.record(format!("pause {}", speaker));
+        Ok(())
+    }
+
+    async fn resume(&self, speaker: &str) -> anyhow::Result<()> {
+        self.record(format!("resume {}", speaker));
+        Ok(())
+    }
+
+    async fn stop(&self, speaker: &str) -> anyhow::Result<()> {
+        self.record(format!("stop {}", speaker));
+        self.require("stop")?;
+        Ok(())
+    }
+
+    async fn capabilities(&self) -> anyhow::Result<Vec<String>> {
+        Ok(OPTIONAL_FEATURES.iter()
+            .filter(|f| !self.missing.contains(f))
+            .map(|f| f.to_string())
+            .collect())
+    }
+
+    async fn set_volume(&self, speaker: &str, volume: u8) -> anyhow::Result<()> {
+        self.record(format!("set_volume {} {}", speaker, volume));
+        Ok(())
+    }
+
+    async fn set_group_volume(&self, coordinator: &str, volume: u8) -> anyhow::Result<()> {
+        self.record(format!("set_group_volume {} {}", coordinator, volume));
+        self.require("group_volume")?;
+        Ok(())
+    }
+
+    async fn set_mute(&self, speaker: &str, muted: bool) -> anyhow::Result<()> {
+        self.record(format!("set_mute {} {}", speaker, muted));
+        self.require("mute")?;
+        Ok(())
+    }
+
+    async fn next(&self, speaker: &str) -> anyhow::Result<()> {
+        self.record(format!("next {}", speaker));
+        Ok(())
+    }
+
+    async fn previous(&self, speaker: &str) -> anyhow::Result<()> {
+        self.record(format!("previous {}", speaker));
+        Ok(())
+    }
+
+    async fn group_all(&self) -> anyhow::Result<()> {
+        self.record("group_all".to_string());
+        Ok(())
+    }
+
+    async fn group(&self, speakers: &[String]) -> anyhow::Result<()> {
+        self.record(format!("group {}", speakers.join(" ")));
+        Ok(())
+    }
+
+    async fn ungroup_all(&self) -> anyhow::Result<()> {
+        self.record("ungroup_all".to_string());
+        Ok(())
+    }
+
+    async fn ungroup(&self, speaker: &str) -> anyhow::Result<()> {
+        self.record(format!("ungroup {}", speaker));
+        Ok(())
+    }
+
+    async fn reload(&self) -> anyhow::Result<()> {
+        self.record("reload".to_string());
+        Ok(())
+    }
+
+    async fn resync(&self) -> anyhow::Result<()> {
+        self.record("resync".to_string());
+        self.require("resync")?;
+        Ok(())
+    }
+
+    async fn get_podcasts(&self) -> anyhow::Result<Vec<Podcast>> {
+        Ok(self.podcasts.clone())
+    }
+
+    async fn get_episodes(&self, _alias: &str) -> anyhow::Result<Vec<Episode>> {
+        Ok(self.episodes.clone())
+    }
+
+    async fn get_queue(&self, speaker: &str, limit: usize) -> anyhow::Result<Vec<QueueItem>> {
+        self.record(format!("get_queue {} {}", speaker, limit));
+        self.require("queue")?;
+        Ok(self.queue.iter().take(limit).cloned().collect())
+    }
+
+    async fn play_uri(&self, speaker: &str, uri: &str, title: &str) -> anyhow::Result<()> {
+        self.record(format!("play_uri {} {} {}", speaker, uri, title));
+        Ok(())
+    }
+
+    async fn skip(&self, speaker: &str, seconds: i32) -> anyhow::Result<()> {
+        self.record(format!("skip {} {}", speaker, seconds));
+        Ok(())
+    }
+
+    async fn seek(&self, speaker: &str, position: u64) -> anyhow::Result<()> {
+        self.record(format!("seek {} {}", speaker, position));
+        self.require("seek")?;
+        Ok(())
+    }
+
+    async fn save_episode_progress(&self, episode_id: &str, position: u64, played: bool) -> anyhow::Result<()> {
+        self.record(format!("save_episode_progress {} {} {}", episode_id, position, played));
+        Ok(())
+    }
+
+    async fn refresh_podcasts(&self) -> anyhow::Result<()> {
+        self.record("refresh_podcasts".to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_header_values() {
+        let header = |auth: Option<Auth>| {
+            let client = ApiClient::with_auth("http://127.0.0.1:9271", auth).unwrap();
+            let request = client.get("/speakers").build().unwrap();
+            request.headers().get(reqwest::header::AUTHORIZATION).map(|v| v.to_str().unwrap().to_string())
+        };
+        let basic = Auth::Basic { user: "frodo".into(), password: "mellon".into() };
+        assert_eq!(header(Some(basic)).as_deref(), Some("Basic ZnJvZG86bWVsbG9u"));
+        assert_eq!(header(Some(Auth::Bearer("abc123".into()))).as_deref(), Some("Bearer abc123"));
+        assert_eq!(header(None), None);
+    }
+
+    #[test]
+    fn test_local_addr_only_for_this_machine() {
+        assert_eq!(local_addr("http://127.0.0.1:9271").as_deref(), Some("127.0.0.1:9271"));
+        assert_eq!(local_addr("http://localhost:9271/").as_deref(), Some("localhost:9271"));
+        assert_eq!(local_addr("https://[::1]").as_deref(), Some("[::1]:443"));
+        assert_eq!(local_addr("http://10.0.0.5:9271"), None);
+        assert_eq!(local_addr("http://sonos.lan:9271"), None);
+        assert_eq!(local_addr("not a url"), None);
     }
 }
@@ -1,6 +1,255 @@
-use crate::api::{Speaker, Playlist};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Write;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use crate::api::{Config, PartyConfig, Speaker, Playlist, PlaylistSource, QueueItem};
+use crate::clock::{self, Clock, SystemClock};
+use crate::history::ResumePoint;
+use crate::keymap::Keymap;
+use serde::{Deserialize, Serialize};
 
+/// Repeated play requests for the same speaker inside this window are ignored
+/// (held or double-tapped Enter would otherwise play twice and skew history).
+pub const PLAY_COOLDOWN: Duration = Duration::from_millis(500);
+
+/// A fading sleep timer ramps volume to zero over this window before pausing.
+pub const SLEEP_FADE: Duration = Duration::from_secs(30);
+
+/// A sleep timer scoped to one group (or everyone), optionally fading out.
+/// The plain whole-house `sleep <mins>` still uses `App::sleep_until`.
+#[derive(Debug, Clone)]
+pub struct SleepTimer {
+    /// Coordinator id of the group to pause; None means every speaker.
+    pub target: Option<String>,
+    /// Display name for status messages.
+    pub label: String,
+    pub until: Instant,
+    pub fade: bool,
+    /// Member volumes captured when the fade began, restored after pausing.
+    pub faded_from: Vec<(String, u8)>,
+}
+
+/// The fetched "coming up" track for one group, valid while `current_title` plays.
+#[derive(Debug, Clone)]
+pub struct NextUp {
+    /// Coordinator id the queue was read from.
+    pub speaker: String,
+    pub current_title: String,
+    /// None when the group has no queue (radio, line-in) or the fetch failed.
+    pub track: Option<QueueItem>,
+}
+
+/// Daemon calls a sleep timer wants made this tick.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SleepAction {
+    SetVolume(String, u8),
+    Pause(String),
+}
+
+/// Minimum gap between "what changed" notifications, so a flurry of external
+/// changes (someone dragging a volume slider) doesn't spam the status line.
+pub const CHANGE_DEBOUNCE: Duration = Duration::from_secs(5);
+
+/// How long a status message stays up. The named lengths are scaled by
+/// `status_linger`; `Exactly` is for messages that match a real deadline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Linger {
+    /// Confirms what was just done: 2s.
+    Brief,
+    /// Worth a read: 3s.
+    Normal,
+    /// Turned out other than asked, or has more to say: 5s.
+    Long,
+    /// A failure and its reason: 8s.
+    Error,
+    /// Until the next message replaces it.
+    Sticky,
+    /// Unscaled, e.g. a confirm window the message describes.
+    Exactly(Duration),
+}
+
+impl Linger {
+    /// The time on screen at `scale`; None for sticky.
+    pub fn duration(self, scale: f64) -> Option<Duration> {
+        let secs = match self {
+            Linger::Brief => 2.0,
+            Linger::Normal => 3.0,
+            Linger::Long => 5.0,
+            Linger::Error => 8.0,
+            Linger::Sticky => return None,
+            Linger::Exactly(d) => return Some(d),
+        };
+        Some(Duration::from_secs_f64(secs * scale.max(0.1)))
+    }
+}
+
+/// How long a first `q` waits for the confirming second one.
+pub const QUIT_CONFIRM: Duration = Duration::from_secs(2);
+
+/// Playlist orders `sort` steps through after the daemon's own `playlist_sort`.
+pub const LOCAL_SORTS: [&str; 3] = ["alphabetical", "recent", "received"];
+
+/// How long a first `history clear` waits for the confirming repeat.
+pub const CLEAR_CONFIRM: Duration = Duration::from_secs(5);
+
+/// How long the on-screen gauge lingers after the last change.
+pub const OSD_DURATION: Duration = Duration::from_secs(1);
+
+/// Volume change per mouse-wheel notch over a Rooms row.
+pub const WHEEL_STEP: i16 = 2;
+
+/// A spun wheel sends at most one `set_volume` per speaker this often; the
+/// volume it lands on goes out once the window passes.
+pub const WHEEL_THROTTLE: Duration = Duration::from_millis(150);
+
+/// Entries the event log keeps; older ones fall off the front.
+pub const EVENT_LOG_CAPACITY: usize = 200;
+
+/// One line of the event log: what happened, at local seconds-of-day `at`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub at: u64,
+    pub text: String,
+}
+
+/// How long a just-started play may take to show up as PLAYING before its
+/// listening session is written off.
+pub const LISTEN_GRACE: Duration = Duration::from_secs(30);
+
+/// A playlist play being timed for history: `playlist` on `speaker` since `since`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listening {
+    pub speaker: String,
+    pub playlist: String,
+    pub since: Instant,
+    /// Seen PLAYING at least once, so a later non-playing state means it ended.
+    heard: bool,
+}
+
+/// A replayed `resume_playlists` entry waiting for its track to show up on
+/// `speaker` so it can seek back to `point`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingResume {
+    pub speaker: String,
+    pub playlist: String,
+    pub point: ResumePoint,
+    pub since: Instant,
+}
+
+/// How long a replay may take to report the saved track before the resume
+/// point is given up as stale (the playlist changed, or starts elsewhere).
+pub const RESUME_WAIT: Duration = Duration::from_secs(10);
+
+/// A play sent to `speaker`, waiting for a poll to show it under way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingStart {
+    pub speaker: String,
+    pub speaker_name: String,
+    pub playlist: String,
+    /// The playlist's preferred volume, if one was applied.
+    pub volume: Option<u8>,
+    pub since: Instant,
+}
+
+/// How long Sonos gets to start a play before a poll that still shows the
+/// speaker idle counts as it not starting.
+pub const PLAY_CONFIRM_DELAY: Duration = Duration::from_millis(1200);
+
+/// Consecutive failed polls (about `POLL_INTERVAL` apart) before the status
+/// line calls sonosd down; one blip is not an outage.
+pub const OUTAGE_AFTER: u32 = 2;
+
+/// After moving the Rooms selection by hand, how long follow mode keeps its hands off.
+pub const FOLLOW_GRACE: Duration = Duration::from_secs(30);
+
+/// Commands kept for Ctrl-R search in the `:` prompt (persisted in prefs).
+pub const COMMAND_HISTORY_CAPACITY: usize = 100;
+
+/// A Ctrl-R search inside the `:` prompt: what's typed so far, the buffer to
+/// restore on Esc, and how many newer matches Ctrl-R has stepped past.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistorySearch {
+    pub query: String,
+    pub original: String,
+    pub skip: usize,
+}
+
+/// What the panic stop (`ctrl+x`) silenced, so `undo` can bring it back:
+/// coordinators that were playing and speakers that weren't muted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Silenced {
+    pub playing: Vec<String>,
+    pub unmuted: Vec<String>,
+}
+
+/// A progress bar as last drawn, so a click on it can be turned into a seek.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeekTarget {
+    pub area: ratatui::layout::Rect,
+    pub speaker: String,
+    pub duration: u64,
+}
+
+/// A Rooms speaker row (name and volume bar) as last drawn, so the mouse
+/// wheel over it can turn that speaker up or down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomRow {
+    pub area: ratatui::layout::Rect,
+    pub speaker: String,
+}
+
+/// The Now Playing group editor (`G`): whose group is being edited, and
+/// which room in the picker (`App::speakers` order) is highlighted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupEdit {
+    pub coordinator: String,
+    pub index: usize,
+}
+
+/// What a transient on-screen gauge is showing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OsdKind {
+    Volume,
+}
+
+/// How much of the bottom help bar to show (`h` cycles, persisted in prefs).
+/// Command and volume prompts always get the full bar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HelpBar {
+    /// Bordered, three rows.
+    #[default]
+    Full,
+    /// The key hints alone on one row.
+    Compact,
+    Hidden,
+}
+
+impl HelpBar {
+    pub fn next(self) -> Self {
+        match self {
+            HelpBar::Full => HelpBar::Compact,
+            HelpBar::Compact => HelpBar::Hidden,
+            HelpBar::Hidden => HelpBar::Full,
+        }
+    }
+}
+
+/// How far `group all` has got: nobody grouped, some groups formed, or every
+/// room it would gather already in one group.
 #[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupingState {
+    None,
+    Partial,
+    Full,
+}
+
+/// Terminal title when nothing is playing, and the one restored on quit.
+pub const DEFAULT_TITLE: &str = "sonos-palantir";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Panel {
     Speakers,
     Playlists,
@@ -23,8 +272,19 @@ pub struct App {
     pub status_message: Option<String>,
     pub volume_input: Option<String>,
     pub command_input: Option<String>,
+    /// Commands run from the `:` prompt, oldest first, without repeats.
+    pub command_history: Vec<String>,
+    pub history_search: Option<HistorySearch>,
     pub sleep_until: Option<std::time::Instant>,
+    /// Scoped and/or fading timers from `sleep <mins> here|fade`.
+    pub sleep_timers: Vec<SleepTimer>,
+    /// Coordinators that were playing when a sleep timer paused them, for `undo`
+    /// and to tell an automatic pause apart from a manual one on resume.
+    pub sleep_paused: Vec<String>,
+    /// When `status_message` stops showing; None keeps it up until replaced.
     pub status_until: Option<std::time::Instant>,
+    /// Multiplier on every status message's time on screen (`status_linger`).
+    pub status_linger: f64,
     pub help_open: bool,
     pub source_mode: SourceMode,
     pub podcasts: Vec<crate::api::Podcast>,
@@ -36,10 +296,162 @@ pub struct App {
     pub skip_back: i32,
     pub current_episode_id: Option<String>,
     pub episode_popup: bool,
+    /// Full-screen focus view of the selected speaker (Enter on Now Playing).
+    pub expanded_now_playing: bool,
+    /// Only the selected speaker's title, artist and progress, borderless (`Z`).
+    pub zen: bool,
+    /// `--mini <speaker>`: a pane showing only this speaker, which stays selected.
+    pub mini: Option<String>,
+    /// `--dry-run`: typed commands (and `on_startup`) report what they would do instead of doing it.
+    pub dry_run: bool,
+    /// When false, ↑/↓ clamp at the ends of lists instead of wrapping around.
+    pub wrap_navigation: bool,
+    /// Mute state of every speaker captured when solo (`S`) began, restored on the second press.
+    pub solo_restore: Option<Vec<(String, bool)>>,
+    /// Set by the panic stop until `undo` restores it.
+    pub silenced: Option<Silenced>,
+    /// When each speaker last accepted a play request, for `PLAY_COOLDOWN`.
+    pub last_play: HashMap<String, Instant>,
+    pub clock: Rc<dyn Clock>,
+    /// How `speakers` is ordered after each fetch — see `sort_speakers`.
+    pub speaker_sort: String,
+    /// Rooms listed first, in this order — overrides `speaker_sort` when non-empty.
+    pub room_order: Vec<String>,
+    /// Group members this far from the group's average volume get colored (0 = never).
+    pub balance_threshold: u8,
+    /// Volumes at or below the first read quiet, at or above the second loud;
+    /// None keeps volume numbers uncoloured (`volume_color_levels: []`).
+    pub volume_levels: Option<(u8, u8)>,
+    /// Dense one-line-per-speaker view replacing the panels (`d`, persisted in prefs).
+    pub dashboard_mode: bool,
+    pub help_bar: HelpBar,
+    pub terminal_title: bool,
+    /// How `playlists` is ordered on load — "popularity", "manual", or daemon order.
+    pub playlist_sort: String,
+    /// Order picked with `sort` (one of `LOCAL_SORTS`), overriding
+    /// `playlist_sort`; None follows the daemon.
+    pub local_sort: Option<String>,
+    /// Playlists in the order the daemon sent them, so any sort can be re-run.
+    received_playlists: Vec<Playlist>,
+    /// Aliases in the user's curated order (Shift+↑/↓, persisted in prefs), used in "manual" mode.
+    pub playlist_order: Vec<String>,
+    /// Transient centered gauge after a key-driven change: (kind, value, shown at).
+    pub osd: Option<(OsdKind, u8, Instant)>,
+    /// `volume_osd` in config; false leaves only the inline numbers.
+    pub osd_enabled: bool,
+    /// Configured "party zone" for `group all` / `g`; empty means everyone.
+    pub group_set: Vec<String>,
+    /// `play_group` in config: who gathers before a playlist starts; empty = off.
+    pub play_group: Vec<String>,
+    pub party: PartyConfig,
+    /// Named zones (`zones` in config): zone name → the rooms it covers.
+    pub zones: HashMap<String, Vec<String>>,
+    /// Volumes captured when `party` began, restored by `party off`.
+    pub party_restore: Option<Vec<(String, u8)>>,
+    /// Preferred volume per playlist alias (`playlist_volumes` in config).
+    pub playlist_volumes: HashMap<String, u8>,
+    /// Aliases that pick up where they left off when replayed (`resume_playlists`).
+    pub resume_playlists: Vec<String>,
+    pub pending_resume: Option<PendingResume>,
+    pub pending_start: Option<PendingStart>,
+    pub next_up_seconds: u64,
+    /// Cached upcoming track, fetched lazily near the end of the current one.
+    pub next_up: Option<NextUp>,
+    /// When the last manual refresh fetched speakers; polled snapshots
+    /// started before this are older than what's on screen and get dropped.
+    pub refreshed_at: Option<Instant>,
+    /// Background polls failed in a row; `OUTAGE_AFTER` or more means sonosd is down.
+    poll_failures: u32,
+    /// Which external changes to announce: "playback", "volume", "grouping".
+    pub change_notifications: Vec<String>,
+    pub last_change_notice: Option<Instant>,
+    /// Progress bars from the last frame; `ui::draw` refills this every render.
+    pub seek_targets: RefCell<Vec<SeekTarget>>,
+    /// Rooms rows from the last frame, refilled like `seek_targets`.
+    pub room_rows: RefCell<Vec<RoomRow>>,
+    /// The Playlists panel as last drawn; empty when it wasn't.
+    pub playlists_area: Cell<ratatui::layout::Rect>,
+    /// Per speaker, when the wheel last sent its volume and whether a newer
+    /// one is waiting out `WHEEL_THROTTLE`.
+    wheel_sends: HashMap<String, (Instant, bool)>,
+    /// Speakers (names or aliases) that "all" actions — group, volume, pause,
+    /// mute, whole-house sleep — leave alone.
+    pub do_not_disturb: Vec<String>,
+    /// Set once the list comes from prefs (a runtime toggle), so config reloads
+    /// don't undo it.
+    pub do_not_disturb_saved: bool,
+    /// Show local time at the right of the status line (kiosk setups).
+    pub show_clock: bool,
+    pub clock_12h: bool,
+    /// Keep Rooms a flat list, with grouping hints, even when rooms are grouped (`rooms_layout: flat`).
+    pub flat_rooms: bool,
+    /// Idle time before the screensaver (`screensaver_minutes`); None = never.
+    pub screensaver_after: Option<Duration>,
+    pub screensaver_style: String,
+    /// Last key/click, or last poll that found something playing.
+    pub last_activity: Instant,
+    /// Ask for a second `q` while anything is playing (kiosk safety).
+    pub confirm_quit_when_playing: bool,
+    /// When the first, unconfirmed `q` was pressed.
+    pub quit_pending: Option<Instant>,
+    /// When the first, unconfirmed `history clear` was entered.
+    pub clear_history_pending: Option<Instant>,
+    /// Optional features the daemon listed at `/capabilities`; None when it
+    /// predates that endpoint, in which case everything is assumed until a 404.
+    pub capabilities: Option<Vec<String>>,
+    /// Features learned to be missing from a 404 on their endpoint.
+    pub missing_features: Vec<String>,
+    /// Recent statuses and actions, newest last, capped at `EVENT_LOG_CAPACITY`.
+    pub event_log: VecDeque<LogEntry>,
+    /// The event log overlay (`L` / `log`), scrolled `log_scroll` lines up from the newest.
+    pub log_open: bool,
+    pub log_scroll: usize,
+    /// `event_log_file` from config, and the file it was opened (truncated) as.
+    log_path: String,
+    log_file: Option<std::fs::File>,
+    /// Which key does what, from the stock bindings plus config's `keybindings`
+    /// (kept raw too, so a reload only re-warns when they change).
+    pub keymap: Keymap,
+    keybindings: HashMap<String, Vec<String>>,
+    /// config.yaml problems as last reported, so a reload only speaks up about new ones.
+    config_errors: Vec<String>,
+    /// The play currently being timed for listening stats.
+    pub listening: Option<Listening>,
+    /// Play history loaded for the stats overlay (`stats`); None when closed.
+    pub stats: Option<Vec<crate::history::PlayEntry>>,
+    /// Play history loaded for the weekly recap overlay (`recap`); None when closed.
+    pub recap: Option<Vec<crate::history::PlayEntry>>,
+    /// `why`: a playlist alias and the play history explaining its popularity
+    /// rank; None when closed.
+    pub why: Option<(String, Vec<crate::history::PlayEntry>)>,
+    /// The every-speaker text table (`list`), scrolled this many rows down;
+    /// None when closed.
+    pub roster: Option<usize>,
+    /// Id of the speaker whose full metadata is overlaid (`i` / `detail`).
+    pub detail: Option<String>,
+    /// Open while adding rooms to, or dropping them from, a group (`G`).
+    pub group_edit: Option<GroupEdit>,
+    /// First visible line of the Rooms panel, as last drawn.
+    pub rooms_offset: Cell<usize>,
+    /// Asks the next draw to center the selected speaker in Rooms (`c`).
+    pub center_rooms: Cell<bool>,
+    /// Select whichever group starts playing next (`F` / `follow`).
+    pub follow_mode: bool,
+    /// When the user last moved the Rooms selection; follow mode waits `FOLLOW_GRACE`.
+    navigated_at: Option<Instant>,
+    /// The room selected before the current one, for `` ` `` (by id, so
+    /// re-sorting doesn't lose it), and the current one as last noted.
+    pub prev_speaker: Option<String>,
+    seen_speaker: Option<String>,
 }
 
 impl App {
     pub fn new() -> Self {
+        Self::with_clock(Rc::new(SystemClock))
+    }
+
+    pub fn with_clock(clock: Rc<dyn Clock>) -> Self {
+        let now = clock.now();
         Self {
             speakers: vec![],
             playlists: vec![],
@@ -50,8 +462,13 @@ impl App {
             status_message: None,
             volume_input: None,
             command_input: None,
+            command_history: vec![],
+            history_search: None,
             sleep_until: None,
+            sleep_timers: vec![],
+            sleep_paused: vec![],
             status_until: None,
+            status_linger: 1.0,
             help_open: false,
             source_mode: SourceMode::Playlists,
             podcasts: vec![],
@@ -63,7 +480,299 @@ impl App {
             skip_back: 10,
             current_episode_id: None,
             episode_popup: false,
+            expanded_now_playing: false,
+            zen: false,
+            mini: None,
+            dry_run: false,
+            wrap_navigation: true,
+            solo_restore: None,
+            silenced: None,
+            party: PartyConfig::default(),
+            zones: HashMap::new(),
+            party_restore: None,
+            last_play: HashMap::new(),
+            clock,
+            speaker_sort: "name".to_string(),
+            room_order: vec![],
+            balance_threshold: 15,
+            volume_levels: Some((25, 80)),
+            dashboard_mode: false,
+            help_bar: HelpBar::Full,
+            terminal_title: false,
+            playlist_sort: "alphabetical".to_string(),
+            local_sort: None,
+            received_playlists: vec![],
+            playlist_order: vec![],
+            osd: None,
+            osd_enabled: true,
+            group_set: vec![],
+            play_group: vec![],
+            playlist_volumes: HashMap::new(),
+            resume_playlists: vec![],
+            pending_resume: None,
+            pending_start: None,
+            next_up_seconds: 10,
+            next_up: None,
+            refreshed_at: None,
+            change_notifications: vec!["playback".into(), "volume".into(), "grouping".into()],
+            last_change_notice: None,
+            seek_targets: RefCell::new(vec![]),
+            room_rows: RefCell::new(vec![]),
+            playlists_area: Cell::new(ratatui::layout::Rect::default()),
+            wheel_sends: HashMap::new(),
+            do_not_disturb: vec![],
+            do_not_disturb_saved: false,
+            show_clock: false,
+            screensaver_after: None,
+            screensaver_style: "clock".to_string(),
+            last_activity: now,
+            clock_12h: false,
+            flat_rooms: false,
+            confirm_quit_when_playing: false,
+            quit_pending: None,
+            clear_history_pending: None,
+            capabilities: None,
+            missing_features: vec![],
+            event_log: VecDeque::new(),
+            log_open: false,
+            log_scroll: 0,
+            log_path: String::new(),
+            log_file: None,
+            keymap: Keymap::default(),
+            keybindings: HashMap::new(),
+            config_errors: vec![],
+            listening: None,
+            stats: None,
+            recap: None,
+            why: None,
+            roster: None,
+            detail: None,
+            group_edit: None,
+            rooms_offset: Cell::new(0),
+            center_rooms: Cell::new(false),
+            follow_mode: false,
+            poll_failures: 0,
+            navigated_at: None,
+            prev_speaker: None,
+            seen_speaker: None,
+        }
+    }
+
+    /// Returns true (and starts a new cooldown) if `speaker` may play now;
+    /// false if it already played within `PLAY_COOLDOWN`.
+    pub fn try_begin_play(&mut self, speaker: &str) -> bool {
+        let now = self.clock.now();
+        if let Some(last) = self.last_play.get(speaker) {
+            if now.saturating_duration_since(*last) < PLAY_COOLDOWN {
+                return false;
+            }
+        }
+        self.last_play.insert(speaker.to_string(), now);
+        true
+    }
+
+    /// Apply daemon-served settings (startup and `:reload`).
+    pub fn apply_config(&mut self, config: &Config) {
+        self.skip_forward = config.podcast_skip_forward;
+        self.skip_back = config.podcast_skip_back;
+        self.wrap_navigation = config.wrap_navigation;
+        self.terminal_title = config.terminal_title;
+        self.osd_enabled = config.volume_osd;
+        self.group_set = config.group_all_speakers.clone();
+        self.play_group = config.play_group.clone();
+        self.party = config.party.clone();
+        self.zones = config.zones.clone();
+        self.playlist_volumes = config.playlist_volumes.clone();
+        self.resume_playlists = config.resume_playlists.clone();
+        self.next_up_seconds = config.next_up_seconds;
+        self.change_notifications = config.change_notifications.clone();
+        self.show_clock = config.show_clock;
+        self.screensaver_after = Some(config.screensaver_minutes)
+            .filter(|m| *m > 0)
+            .map(|m| Duration::from_secs(m as u64 * 60));
+        self.screensaver_style = config.screensaver_style.clone();
+        self.confirm_quit_when_playing = config.confirm_quit_when_playing;
+        self.balance_threshold = config.volume_balance_threshold;
+        self.status_linger = config.status_linger;
+        self.volume_levels = match config.volume_color_levels[..] {
+            [quiet, loud] => Some((quiet, loud)),
+            _ => None,
+        };
+        self.set_log_file(&config.event_log_file);
+        if self.keybindings != config.keybindings {
+            self.keybindings = config.keybindings.clone();
+            let (keymap, warnings) = Keymap::with_overrides(&config.keybindings);
+            self.keymap = keymap;
+            for w in &warnings {
+                self.log_event(format!("keybindings: {}", w));
+            }
+            if let Some(first) = warnings.first() {
+                self.set_status(format!("A flawed rune in keybindings: {} (see L)", first), Linger::Error);
+            }
+        }
+        if self.config_errors != config.config_errors {
+            self.config_errors = config.config_errors.clone();
+            for e in &config.config_errors {
+                self.log_event(format!("config.yaml: {}", e));
+            }
+            if let Some(first) = config.config_errors.first() {
+                self.set_status(format!("A flawed rune in config.yaml: {} (see L)", first), Linger::Error);
+            }
+        }
+        self.clock_12h = config.clock_format == "12h";
+        self.flat_rooms = config.rooms_layout == "flat";
+        if !self.do_not_disturb_saved {
+            self.do_not_disturb = config.do_not_disturb.clone();
+        }
+        if self.playlist_sort != config.playlist_sort {
+            self.playlist_sort = config.playlist_sort.clone();
+            self.resort_playlists();
+        }
+        if self.speaker_sort != config.speaker_sort || self.room_order != config.room_order {
+            self.speaker_sort = config.speaker_sort.clone();
+            self.room_order = config.room_order.clone();
+            // Clone rather than take, so set_speakers can repin the selection by id
+            let speakers = self.speakers.clone();
+            self.set_speakers(speakers);
+        }
+    }
+
+    /// Replace the speaker list with fresh daemon data, sorted per `speaker_sort`,
+    /// keeping the same speaker selected (matched by id) even if its position moved.
+    /// If it vanished, the selection lands on its old neighbour (next, else previous).
+    pub fn set_speakers(&mut self, speakers: Vec<Speaker>) {
+        let mut speakers = logical_speakers(speakers);
+        if speakers.iter().any(|s| s.state == "PLAYING") {
+            self.last_activity = self.clock.now();
+        }
+        let old_ids: Vec<String> = self.speakers.iter().map(|s| s.id()).collect();
+        if self.room_order.is_empty() {
+            sort_speakers(&mut speakers, &self.speaker_sort);
+        } else {
+            sort_by_room_order(&mut speakers, &self.room_order);
+        }
+        let new_ids: Vec<String> = speakers.iter().map(|s| s.id()).collect();
+        self.speakers = speakers;
+        self.speaker_index = repin_index(&old_ids, self.speaker_index, &new_ids);
+        let mini = self.mini_speaker().map(|s| s.id());
+        if let Some(i) = mini.and_then(|id| new_ids.iter().position(|n| *n == id)) {
+            self.speaker_index = i;
+        }
+    }
+
+    /// Apply a background-poll snapshot whose fetch began at `fetched_at`,
+    /// unless a manual refresh has since replaced it with newer data.
+    pub fn apply_polled(&mut self, fetched_at: Instant, speakers: Vec<Speaker>) {
+        if self.refreshed_at.is_some_and(|r| fetched_at < r) {
+            return;
+        }
+        let speakers = logical_speakers(speakers);
+        self.notify_changes(&speakers);
+        let started = self.follow_mode.then(|| newly_started(&self.speakers, &speakers)).flatten();
+        self.set_speakers(speakers);
+        if let Some(id) = started {
+            self.follow(&id);
+        }
+    }
+
+    /// Follow mode: select the group `id` leads, which just started playing,
+    /// unless the user picked a room within `FOLLOW_GRACE` or it's already selected.
+    fn follow(&mut self, id: &str) {
+        let now = self.clock.now();
+        if self.mini.is_some() || self.navigated_at.is_some_and(|at| now.duration_since(at) < FOLLOW_GRACE) {
+            return;
+        }
+        if self.selected_speaker().map(|s| self.coordinator_of(s).id()).as_deref() == Some(id) {
+            return;
+        }
+        let Some(i) = self.speakers.iter().position(|s| s.id() == id) else { return };
+        self.speaker_index = i;
+        self.center_rooms.set(true);
+        let name = self.speakers[i].display_name().to_string();
+        self.set_status(format!("Following the music to {}.", name), Linger::Normal);
+    }
+
+    /// sonosd didn't answer; `status` says why until it does again.
+    pub fn daemon_unreachable(&mut self, status: &str) {
+        self.poll_failures = self.poll_failures.max(OUTAGE_AFTER);
+        self.set_status(status, Linger::Sticky);
+    }
+
+    /// A background poll failed. Reported once it's the `OUTAGE_AFTER`th in a row.
+    pub fn poll_failed(&mut self, status: &str) {
+        self.poll_failures += 1;
+        if self.poll_failures == OUTAGE_AFTER {
+            self.daemon_unreachable(status);
+        }
+    }
+
+    /// A background poll got through. True when that ends an outage — the
+    /// outage status is replaced and the caller should re-fetch what it missed.
+    pub fn poll_succeeded(&mut self) -> bool {
+        let recovered = self.poll_failures >= OUTAGE_AFTER;
+        self.poll_failures = 0;
+        if recovered {
+            self.set_status("The gates stand open again — sonosd answers.", Linger::Long);
+        }
+        recovered
+    }
+
+    /// Announce external changes between the current speakers and `incoming`.
+    /// Stays quiet while another status is showing (usually our own action's
+    /// feedback) and for `CHANGE_DEBOUNCE` after the previous notice.
+    fn notify_changes(&mut self, incoming: &[Speaker]) {
+        let now = self.clock.now();
+        let busy = self.status_showing(now);
+        let recent = self.last_change_notice.is_some_and(|at| now.duration_since(at) < CHANGE_DEBOUNCE);
+        if busy || recent || self.speakers.is_empty() {
+            return;
+        }
+        let changes = describe_changes(&self.speakers, incoming, &self.change_notifications);
+        let Some(first) = changes.first() else { return };
+        let msg = match changes.len() {
+            1 => first.clone(),
+            n => format!("{} (+{} more)", first, n - 1),
+        };
+        self.set_status(msg, Linger::Normal);
+        self.last_change_notice = Some(now);
+    }
+
+    /// Replace the playlist list (e.g. after `:reload`), keeping the selected alias selected.
+    pub fn set_playlists(&mut self, mut playlists: Vec<Playlist>) {
+        self.received_playlists = playlists.clone();
+        match self.active_sort() {
+            "popularity" => crate::history::popularity_sort(&mut playlists, &*self.clock),
+            "manual" => apply_manual_order(&mut playlists, &self.playlist_order),
+            "alphabetical" => playlists.sort_by_key(|p| p.favorite_name.to_lowercase()),
+            "recent" => crate::history::recent_sort(&mut playlists),
+            _ => {}
         }
+        let old: Vec<String> = self.playlists.iter().map(|p| p.alias.clone()).collect();
+        let new: Vec<String> = playlists.iter().map(|p| p.alias.clone()).collect();
+        self.playlists = playlists;
+        self.playlist_index = repin_index(&old, self.playlist_index, &new);
+    }
+
+    /// Re-run the active sort over the playlists as received.
+    fn resort_playlists(&mut self) {
+        let playlists = self.received_playlists.clone();
+        self.set_playlists(playlists);
+    }
+
+    /// The playlist order in force: `local_sort` if chosen, else the daemon's.
+    pub fn active_sort(&self) -> &str {
+        self.local_sort.as_deref().unwrap_or(&self.playlist_sort)
+    }
+
+    /// Handle `sort`: step from the daemon's order through `LOCAL_SORTS` and
+    /// back, re-sorting at once.
+    pub fn cycle_sort(&mut self) {
+        let next = match self.local_sort.as_deref() {
+            None => Some(0),
+            Some(s) => LOCAL_SORTS.iter().position(|l| *l == s).map(|i| i + 1).filter(|&i| i < LOCAL_SORTS.len()),
+        };
+        self.local_sort = next.map(|i| LOCAL_SORTS[i].to_string());
+        self.resort_playlists();
     }
 
     pub fn selected_speaker(&self) -> Option<&Speaker> {
@@ -74,382 +783,3022 @@ impl App {
         self.playlists.get(self.playlist_index)
     }
 
+    /// Stable id of the selected speaker, for API calls.
     pub fn speaker_id(&self) -> Option<String> {
-        self.selected_speaker().map(|s| {
-            s.alias.clone().unwrap_or_else(|| s.name.clone())
-        })
+        self.selected_speaker().map(|s| s.id())
+    }
+
+    /// Display name of the selected speaker, for status messages.
+    pub fn speaker_name(&self) -> Option<String> {
+        self.selected_speaker().map(|s| s.display_name().to_string())
     }
 
     pub fn next_in_list(&mut self) {
+        let wrap = self.wrap_navigation;
         match self.active_panel {
             Panel::Speakers => {
-                if !self.speakers.is_empty() {
-                    self.speaker_index = (self.speaker_index + 1) % self.speakers.len();
-                }
-            }
-            Panel::Playlists => {
-                if self.source_mode == SourceMode::Podcasts {
-                    if self.podcast_drill {
-                        if !self.episodes.is_empty() {
-                            self.episode_index = (self.episode_index + 1) % self.episodes.len();
-                        }
-                    } else if !self.podcasts.is_empty() {
-                        self.podcast_index = (self.podcast_index + 1) % self.podcasts.len();
-                    }
-                } else if !self.playlists.is_empty() {
-                    self.playlist_index = (self.playlist_index + 1) % self.playlists.len();
-                }
+                self.speaker_index = step_next(self.speaker_index, self.speakers.len(), wrap);
+                self.navigated_at = Some(self.clock.now());
             }
+            Panel::Playlists => self.step_source_list(true),
             _ => {}
         }
     }
 
     pub fn prev_in_list(&mut self) {
+        let wrap = self.wrap_navigation;
         match self.active_panel {
             Panel::Speakers => {
-                if !self.speakers.is_empty() {
-                    self.speaker_index = self.speaker_index
-                        .checked_sub(1)
-                        .unwrap_or(self.speakers.len() - 1);
-                }
-            }
-            Panel::Playlists => {
-                if self.source_mode == SourceMode::Podcasts {
-                    if self.podcast_drill {
-                        if !self.episodes.is_empty() {
-                            self.episode_index = self.episode_index
-                                .checked_sub(1)
-                                .unwrap_or(self.episodes.len() - 1);
-                        }
-                    } else if !self.podcasts.is_empty() {
-                        self.podcast_index = self.podcast_index
-                            .checked_sub(1)
-                            .unwrap_or(self.podcasts.len() - 1);
-                    }
-                } else if !self.playlists.is_empty() {
-                    self.playlist_index = self.playlist_index
-                        .checked_sub(1)
-                        .unwrap_or(self.playlists.len() - 1);
-                }
+                self.speaker_index = step_prev(self.speaker_index, self.speakers.len(), wrap);
+                self.navigated_at = Some(self.clock.now());
             }
+            Panel::Playlists => self.step_source_list(false),
             _ => {}
         }
     }
 
-    pub fn cycle_panel(&mut self) {
-        self.active_panel = match self.active_panel {
-            Panel::Speakers => Panel::Playlists,
-            Panel::Playlists => Panel::NowPlaying,
-            Panel::NowPlaying => Panel::Speakers,
+    /// Move through whichever list the Playlists panel shows — playlists,
+    /// podcasts or episodes — whatever panel is focused (the mouse wheel).
+    pub fn step_source_list(&mut self, forward: bool) {
+        let wrap = self.wrap_navigation;
+        let step = |i: usize, len: usize| if forward { step_next(i, len, wrap) } else { step_prev(i, len, wrap) };
+        if self.source_mode == SourceMode::Podcasts {
+            if self.podcast_drill {
+                self.episode_index = step(self.episode_index, self.episodes.len());
+            } else {
+                self.podcast_index = step(self.podcast_index, self.podcasts.len());
+            }
+        } else {
+            self.playlist_index = step(self.playlist_index, self.playlists.len());
+        }
+    }
+
+    /// Window title for the selected speaker: "Title — Artist [42%]", or the app
+    /// name when nothing is loaded.
+    pub fn title_text(&self) -> String {
+        let Some(track) = self.selected_speaker().and_then(|s| s.track.as_ref()) else {
+            return DEFAULT_TITLE.to_string();
         };
+        if track.duration == 0 {
+            return format!("{} — {}", track.title, track.artist);
+        }
+        let pct = (track.position * 100 / track.duration).min(100);
+        format!("{} — {} [{}%]", track.title, track.artist, pct)
     }
 
-    pub fn set_status(&mut self, msg: impl Into<String>, secs: u64) {
-        self.status_message = Some(msg.into());
-        self.status_until = Some(
-            std::time::Instant::now() + std::time::Duration::from_secs(secs)
-        );
+    /// Pop up (or refresh) the gauge for `kind`, if enabled.
+    pub fn show_osd(&mut self, kind: OsdKind, value: u8) {
+        if self.osd_enabled {
+            self.osd = Some((kind, value, self.clock.now()));
+        }
     }
 
-    pub fn active_status(&self) -> String {
-        // Sleep countdown takes lowest priority — shown only when no timed message
-        if let Some(until) = self.status_until {
-            if until > std::time::Instant::now() {
-                return self.status_message.clone().unwrap_or_default();
+    /// Drop the gauge once `OSD_DURATION` has passed since it was last shown.
+    pub fn expire_osd(&mut self) {
+        if let Some((_, _, at)) = self.osd {
+            if self.clock.now().saturating_duration_since(at) >= OSD_DURATION {
+                self.osd = None;
             }
         }
-        if let Some(sleep_until) = self.sleep_until {
-            let now = std::time::Instant::now();
-            if let Some(remaining) = sleep_until.checked_duration_since(now) {
-                let secs = remaining.as_secs();
-                return format!("Sleep: {}:{:02} remaining", secs / 60, secs % 60);
+    }
+
+    /// Optimistically mark the track `coordinator`'s group is playing as rated,
+    /// until the next poll; returns its title.
+    pub fn set_rating(&mut self, coordinator: &str, rating: &str) -> Option<String> {
+        let key = self.speakers.iter().find(|s| s.id() == coordinator)?.group_key().to_string();
+        let mut title = None;
+        for sp in &mut self.speakers {
+            if sp.id() == coordinator || sp.coordinator_key() == Some(key.as_str()) {
+                if let Some(track) = sp.track.as_mut() {
+                    track.rating = Some(rating.to_string());
+                    title = Some(track.title.clone());
+                }
             }
         }
-        String::new()
+        title
     }
 
-    pub fn is_grouped(&self) -> bool {
-        // A speaker is a group follower when its coordinator differs from its own name.
-        // If any follower exists, speakers are grouped.
-        self.speakers.iter().any(|s| {
-            s.group_coordinator
-                .as_deref()
-                .map(|coord| coord != s.name)
-                .unwrap_or(false)
-        })
+    /// Optimistically record a volume change until the next poll confirms it.
+    pub fn set_volume(&mut self, id: &str, volume: u8) {
+        for sp in &mut self.speakers {
+            if sp.id() == id {
+                sp.volume = volume;
+            }
+        }
     }
 
-    /// Returns all speakers whose coordinator is `coordinator_name`.
-    pub fn group_members_of<'a>(&'a self, coordinator_name: &str) -> Vec<&'a Speaker> {
-        self.speakers.iter().filter(|s| {
-            s.group_coordinator.as_deref() == Some(coordinator_name)
-        }).collect()
+    /// How loud a zone is at a glance: the average volume of `coordinator`'s group.
+    pub fn group_volume(&self, coordinator: &str) -> Option<u8> {
+        let coord = self.speakers.iter().find(|s| s.id() == coordinator)?;
+        let members = self.group_members_of(coord);
+        if members.is_empty() {
+            return Some(coord.volume);
+        }
+        let total: u32 = members.iter().map(|m| m.volume as u32).sum();
+        Some((total as f32 / members.len() as f32).round() as u8)
     }
 
-    /// Returns speakers with no group_coordinator (truly ungrouped/solo).
-    pub fn solo_speakers(&self) -> Vec<&Speaker> {
-        self.speakers.iter().filter(|s| s.group_coordinator.is_none()).collect()
+    /// `balance`: the members of `sp`'s group not already at the group's average
+    /// volume, with that average. None unless the group has two or more members.
+    pub fn balance_plan(&self, sp: &Speaker) -> Option<(Vec<String>, u8)> {
+        let coord = self.coordinator_of(sp);
+        let members = self.group_members_of(coord);
+        if members.len() < 2 {
+            return None;
+        }
+        let average = self.group_volume(&coord.id())?;
+        let ids = members.iter().filter(|m| m.volume != average).map(|m| m.id()).collect();
+        Some((ids, average))
     }
 
-    /// Returns coordinator speakers (group_coordinator == their own name).
-    pub fn coordinators(&self) -> Vec<&Speaker> {
-        self.speakers.iter().filter(|s| {
-            s.group_coordinator.as_deref() == Some(s.name.as_str())
-        }).collect()
+    /// The coordinator whose whole group `+`/`-` should scale: set when Now
+    /// Playing is focused on a speaker that's part of a multi-room group.
+    pub fn focused_group(&self) -> Option<String> {
+        if self.active_panel != Panel::NowPlaying {
+            return None;
+        }
+        let coord = self.coordinator_of(self.selected_speaker()?);
+        (self.group_members_of(coord).len() > 1).then(|| coord.id())
     }
 
-    pub fn toggle_source(&mut self) {
-        self.source_mode = match self.source_mode {
-            SourceMode::Playlists => SourceMode::Podcasts,
-            SourceMode::Podcasts => SourceMode::Playlists,
-        };
-        self.podcast_drill = false;
+    /// Open the group editor on the focused Now Playing speaker's group —
+    /// a solo speaker starts one of its own. False outside Now Playing.
+    pub fn open_group_edit(&mut self) -> bool {
+        if self.active_panel != Panel::NowPlaying {
+            return false;
+        }
+        let Some(sp) = self.selected_speaker() else { return false };
+        let coordinator = self.coordinator_of(sp).id();
+        let index = self.speakers.iter().position(|s| s.id() == coordinator).unwrap_or(0);
+        self.group_edit = Some(GroupEdit { coordinator, index });
+        true
     }
 
-    pub fn selected_podcast(&self) -> Option<&crate::api::Podcast> {
-        self.podcasts.get(self.podcast_index)
+    pub fn move_group_edit(&mut self, delta: i32) {
+        let len = self.speakers.len();
+        if let Some(edit) = self.group_edit.as_mut().filter(|_| len > 0) {
+            edit.index = (edit.index as i32 + delta).clamp(0, len as i32 - 1) as usize;
+        }
     }
 
-    pub fn selected_episode(&self) -> Option<&crate::api::Episode> {
-        self.episodes.get(self.episode_index)
+    /// The group editor's coordinator and highlighted room, if both are still online.
+    pub fn group_edit_pick(&self) -> Option<(&Speaker, &Speaker)> {
+        let edit = self.group_edit.as_ref()?;
+        let coordinator = self.speakers.iter().find(|s| s.id() == edit.coordinator)?;
+        Some((coordinator, self.speakers.get(edit.index)?))
     }
 
-    pub fn is_podcast_playing(&self) -> bool {
-        self.selected_speaker()
-            .and_then(|s| s.track.as_ref())
-            .map(|t| t.source == "Podcast")
-            .unwrap_or(false)
+    /// Whether `sp` sits in `coordinator`'s group (a lone coordinator counts).
+    pub fn in_group_of(&self, sp: &Speaker, coordinator: &Speaker) -> bool {
+        sp.id() == coordinator.id() || sp.coordinator_key() == Some(coordinator.group_key())
     }
 
-    /// One entry per distinct group (represented by coordinator) + each solo speaker.
-    pub fn playing_entities(&self) -> Vec<&Speaker> {
-        let mut seen: std::collections::HashSet<&str> = std::collections::HashSet::new();
-        let mut result = vec![];
-        for sp in &self.speakers {
-            match &sp.group_coordinator {
-                Some(coord) if coord == &sp.name => {
-                    // coordinator — include once
-                    if seen.insert(coord.as_str()) {
-                        result.push(sp);
-                    }
-                }
-                None => result.push(sp), // ungrouped solo
-                _ => {}                  // follower — skip
+    /// Optimistically move speaker `id` into `coordinator`'s group, or out on its
+    /// own when None, until the next poll reports the real topology.
+    pub fn set_membership(&mut self, id: &str, coordinator: Option<&str>) {
+        let target = coordinator.unwrap_or(id);
+        let Some((uid, name)) = self.speakers.iter()
+            .find(|s| s.id() == target)
+            .map(|s| (s.uid.clone(), s.name.clone()))
+        else { return };
+        for sp in &mut self.speakers {
+            if sp.id() == id {
+                sp.coordinator_uid = (!uid.is_empty()).then(|| uid.clone());
+                sp.group_coordinator = Some(name.clone());
             }
         }
-        result
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::api::Speaker;
 
-    fn make_speaker(name: &str, coordinator: Option<&str>) -> Speaker {
-        Speaker {
-            name: name.to_string(),
-            alias: None,
-            ip: "0.0.0.0".to_string(),
-            volume: 25,
-            muted: false,
-            state: "PLAYING".to_string(),
-            group_coordinator: coordinator.map(|s| s.to_string()),
-            track: None,
+    /// Optimistically move every member of `coordinator`'s group by the change
+    /// in group volume, until the next poll reports Sonos's proportional result.
+    pub fn set_group_volume(&mut self, coordinator: &str, volume: u8) {
+        let Some(current) = self.group_volume(coordinator) else { return };
+        let delta = volume as i16 - current as i16;
+        let key = self.speakers.iter().find(|s| s.id() == coordinator).map(|s| s.group_key().to_string());
+        for sp in &mut self.speakers {
+            if key.is_some() && sp.coordinator_key() == key.as_deref() {
+                sp.volume = (sp.volume as i16 + delta).clamp(0, 100) as u8;
+            }
         }
     }
 
-    #[test]
-    fn test_is_grouped_when_follower_present() {
-        let mut app = App::new();
-        app.speakers = vec![
-            make_speaker("Family Room", Some("Family Room")),
-            make_speaker("cthulhu", Some("Family Room")),
-        ];
-        assert!(app.is_grouped());
+    /// Whether the daemon can do `feature`; anything outside
+    /// `api::OPTIONAL_FEATURES` is core and always assumed present.
+    pub fn supports(&self, feature: &str) -> bool {
+        if !crate::api::OPTIONAL_FEATURES.contains(&feature) {
+            return true;
+        }
+        !self.missing_features.iter().any(|f| f == feature)
+            && self.capabilities.as_ref().is_none_or(|caps| caps.iter().any(|f| f == feature))
     }
 
-    #[test]
-    fn test_is_not_grouped_when_all_self_coordinating() {
-        let mut app = App::new();
-        app.speakers = vec![
-            make_speaker("Family Room", Some("Family Room")),
-            make_speaker("cthulhu", Some("cthulhu")),
-        ];
-        assert!(!app.is_grouped());
+    pub fn mark_unsupported(&mut self, feature: &str) {
+        if !self.missing_features.iter().any(|f| f == feature) {
+            self.missing_features.push(feature.to_string());
+        }
     }
 
-    #[test]
-    fn test_is_not_grouped_when_coordinators_null() {
-        let mut app = App::new();
-        app.speakers = vec![
-            make_speaker("Family Room", None),
-            make_speaker("cthulhu", None),
-        ];
-        assert!(!app.is_grouped());
+    /// Handle `q`: quit at once, unless confirmation is on and music is playing —
+    /// then the first press only arms a `QUIT_CONFIRM` window for the second.
+    pub fn request_quit(&mut self) {
+        let now = self.clock.now();
+        let confirmed = self.quit_pending.is_some_and(|at| now.duration_since(at) < QUIT_CONFIRM);
+        let playing = self.speakers.iter().any(|s| s.state == "PLAYING");
+        if !self.confirm_quit_when_playing || !playing || confirmed {
+            self.should_quit = true;
+            return;
+        }
+        self.quit_pending = Some(now);
+        self.set_status("Music still plays in Middle-earth. Press q again to quit.", Linger::Exactly(QUIT_CONFIRM));
     }
 
-    #[test]
-    fn test_volume_input_starts_none() {
-        let app = App::new();
-        assert!(app.volume_input.is_none());
+    /// Move the running sleep timer by `mins` (either way), never to before
+    /// now. Returns the time left, or None if no timer is running.
+    pub fn adjust_sleep(&mut self, mins: i32) -> Option<Duration> {
+        let now = self.clock.now();
+        let until = self.sleep_until?;
+        let by = Duration::from_secs(mins.unsigned_abs() as u64 * 60);
+        let moved = if mins >= 0 { until + by } else { until.checked_sub(by).unwrap_or(now).max(now) };
+        self.sleep_until = Some(moved);
+        Some(moved - now)
     }
 
-    #[test]
-    fn test_volume_input_can_be_set() {
-        let mut app = App::new();
-        app.volume_input = Some(String::from("42"));
-        assert_eq!(app.volume_input.as_deref(), Some("42"));
+    /// The screensaver shows after `screensaver_after` without input or playback.
+    pub fn screensaver_active(&self) -> bool {
+        self.screensaver_after.is_some_and(|after| self.clock.now().duration_since(self.last_activity) >= after)
+            && !self.speakers.iter().any(|s| s.state == "PLAYING")
     }
 
-    #[test]
-    fn test_active_status_returns_empty_when_nothing_set() {
-        let app = App::new();
-        assert_eq!(app.active_status(), "");
+    /// Handle `history clear`: true once it's been repeated within
+    /// `CLEAR_CONFIRM`; the first entry only arms the window.
+    pub fn confirm_history_clear(&mut self) -> bool {
+        let now = self.clock.now();
+        if self.clear_history_pending.take().is_some_and(|at| now.duration_since(at) < CLEAR_CONFIRM) {
+            return true;
+        }
+        self.clear_history_pending = Some(now);
+        self.set_status("This will burn every record of play. Enter history clear again to confirm.", Linger::Exactly(CLEAR_CONFIRM));
+        false
     }
 
-    #[test]
-    fn test_set_status_returns_message_immediately() {
-        let mut app = App::new();
-        app.set_status("The gates of Moria are sealed.", 5);
-        assert_eq!(app.active_status(), "The gates of Moria are sealed.");
+    /// After history was pruned on disk: stop timing the current play (its
+    /// entry may be gone), refresh an open stats, recap or why overlay, and re-sort.
+    pub fn history_changed(&mut self) {
+        self.listening = None;
+        if self.stats.is_some() {
+            self.stats = Some(crate::history::load());
+        }
+        if self.recap.is_some() {
+            self.recap = Some(crate::history::load());
+        }
+        if let Some((_, entries)) = self.why.as_mut() {
+            *entries = crate::history::load();
+        }
+        self.resort_playlists();
     }
 
-    #[test]
-    fn test_active_status_returns_empty_when_expired() {
-        let mut app = App::new();
-        // Set a status that already expired
-        app.status_message = Some("old message".to_string());
-        app.status_until = Some(std::time::Instant::now() - std::time::Duration::from_secs(1));
-        assert_eq!(app.active_status(), "");
+    /// Flip dashboard mode; navigation then always targets the speaker list.
+    pub fn toggle_dashboard(&mut self) {
+        self.dashboard_mode = !self.dashboard_mode;
+        if self.dashboard_mode {
+            self.active_panel = Panel::Speakers;
+        }
     }
 
-    #[test]
+    pub fn cycle_panel(&mut self) {
+        self.active_panel = match self.active_panel {
+            Panel::Speakers => Panel::Playlists,
+            Panel::Playlists => Panel::NowPlaying,
+            Panel::NowPlaying => Panel::Speakers,
+        };
+    }
+
+    pub fn set_status(&mut self, msg: impl Into<String>, linger: Linger) {
+        let msg = msg.into();
+        self.log_event(msg.clone());
+        self.status_message = Some(msg);
+        self.status_until = linger.duration(self.status_linger).map(|d| self.clock.now() + d);
+    }
+
+    /// Whether the status message is still up at `now` (sticky ones always are).
+    fn status_showing(&self, now: std::time::Instant) -> bool {
+        self.status_message.is_some() && self.status_until.is_none_or(|until| until > now)
+    }
+
+    /// Whether a pending sleep timer will pause `sp`: the whole-house timer,
+    /// an everyone-scoped one, or one aimed at its group. Untargeted timers
+    /// pass over do-not-disturb rooms.
+    pub fn sleep_pending_for(&self, sp: &Speaker) -> bool {
+        let excluded = self.excludes(sp);
+        if self.sleep_until.is_some() && !excluded {
+            return true;
+        }
+        let group = self.coordinator_of(sp).id();
+        self.sleep_timers.iter().any(|t| match &t.target {
+            Some(id) => *id == group,
+            None => !excluded,
+        })
+    }
+
+    /// Select the coordinator of the next playing group after the selected
+    /// speaker's, wrapping around, and center it in Rooms. Returns its name,
+    /// or None when nothing is playing.
+    pub fn select_playing(&mut self) -> Option<String> {
+        let current = self.selected_speaker().map(|s| self.coordinator_of(s).id());
+        let playing: Vec<String> = self.speakers.iter()
+            .filter(|s| s.state == "PLAYING" && self.coordinator_of(s).id() == s.id())
+            .map(|s| s.id())
+            .collect();
+        let next = match current.and_then(|c| playing.iter().position(|id| *id == c)) {
+            Some(i) => &playing[(i + 1) % playing.len()],
+            None => playing.first()?,
+        };
+        self.speaker_index = self.speakers.iter().position(|s| s.id() == *next)?;
+        self.navigated_at = Some(self.clock.now());
+        self.center_rooms.set(true);
+        self.speaker_name()
+    }
+
+    /// The music `bring` carries: the selected speaker's group if it's
+    /// playing, else the first playing group. Returns its coordinator.
+    pub fn bring_source(&self) -> Option<&Speaker> {
+        let playing = |s: &&Speaker| s.state == "PLAYING";
+        let selected = self.selected_speaker().map(|s| self.coordinator_of(s)).filter(playing);
+        selected.or_else(|| self.speakers.iter().find(|s| playing(s) && self.coordinator_of(s).id() == s.id()))
+    }
+
+    /// Record a change of selected room since the last call (the main loop
+    /// calls this once a turn), so the room left behind becomes `prev_speaker`.
+    pub fn note_selection(&mut self) {
+        let id = self.speaker_id();
+        if id != self.seen_speaker {
+            if let Some(left) = self.seen_speaker.take() {
+                self.prev_speaker = Some(left);
+            }
+            self.seen_speaker = id;
+        }
+    }
+
+    /// Flip the selection to the previously selected room; its name, or None
+    /// when there's none — or it has left the network, which forgets it.
+    pub fn swap_speaker(&mut self) -> Option<String> {
+        self.note_selection();
+        let prev = self.prev_speaker.clone()?;
+        let Some(i) = self.speakers.iter().position(|s| s.id() == prev) else {
+            self.prev_speaker = None;
+            return None;
+        };
+        self.speaker_index = i;
+        self.navigated_at = Some(self.clock.now());
+        self.note_selection();
+        self.speaker_name()
+    }
+
+    /// Start timing a play on `speaker`, returning how long the previous one
+    /// lasted (it ends here) if one was still running.
+    pub fn begin_listening(&mut self, speaker: &str, playlist: &str) -> Option<Duration> {
+        let previous = self.end_listening();
+        self.listening = Some(Listening {
+            speaker: speaker.to_string(),
+            playlist: playlist.to_string(),
+            since: self.clock.now(),
+            heard: false,
+        });
+        previous
+    }
+
+    /// Where the timed play stands, if its playlist is in `resume_playlists`.
+    /// Ask before the play ends: afterwards there's no play to ask about.
+    pub fn resume_point(&self) -> Option<(String, ResumePoint)> {
+        let l = self.listening.as_ref()?;
+        if !self.resume_playlists.contains(&l.playlist) {
+            return None;
+        }
+        let track = self.speakers.iter().find(|s| s.id() == l.speaker)?.track.as_ref()?;
+        // Streams report no duration and can't be sought back into
+        (track.duration > 0 && track.position > 0).then(|| {
+            (l.playlist.clone(), ResumePoint { title: track.title.clone(), position: track.position })
+        })
+    }
+
+    /// After a replay was started on `speaker`, wait for `point`'s track.
+    pub fn await_resume(&mut self, speaker: &str, playlist: &str, point: ResumePoint) {
+        self.pending_resume = Some(PendingResume {
+            speaker: speaker.to_string(),
+            playlist: playlist.to_string(),
+            point,
+            since: self.clock.now(),
+        });
+    }
+
+    /// After a poll: the seek (speaker, position) that resumes the pending
+    /// replay, once its saved track is playing. A point past the end of that
+    /// track, or a track that never shows up within `RESUME_WAIT`, is dropped.
+    pub fn resume_due(&mut self) -> Option<(String, u64)> {
+        let pending = self.pending_resume.as_ref()?;
+        let track = self.speakers.iter()
+            .find(|s| s.id() == pending.speaker)
+            .and_then(|s| s.track.as_ref());
+        match track {
+            Some(t) if t.title == pending.point.title => {
+                let pending = self.pending_resume.take()?;
+                if pending.point.position < t.duration {
+                    return Some((pending.speaker, pending.point.position));
+                }
+                self.set_status(format!("The saved place in {} is lost — starting afresh.", pending.playlist), Linger::Normal);
+                None
+            }
+            _ if self.clock.now().duration_since(pending.since) >= RESUME_WAIT => {
+                let pending = self.pending_resume.take()?;
+                self.set_status(format!("The road has changed — {} starts from the beginning.", pending.playlist), Linger::Normal);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// After `playlist` was sent to `speaker`: say it's starting, and have
+    /// the coming polls confirm it did.
+    pub fn await_start(&mut self, speaker: &str, speaker_name: &str, playlist: &str, volume: Option<u8>) {
+        self.set_status(format!("Starting {} on {}…", playlist, speaker_name), Linger::Long);
+        self.pending_start = Some(PendingStart {
+            speaker: speaker.to_string(),
+            speaker_name: speaker_name.to_string(),
+            playlist: playlist.to_string(),
+            volume,
+            since: self.clock.now(),
+        });
+    }
+
+    /// After a poll fetched at `fetched_at`: settle the pending start. The
+    /// speaker playing confirms it; still idle in a snapshot taken
+    /// `PLAY_CONFIRM_DELAY` after the play, it didn't start. Snapshots from
+    /// before the play, or without the speaker, prove nothing either way.
+    pub fn confirm_start(&mut self, fetched_at: Instant) {
+        let Some(pending) = self.pending_start.as_ref().filter(|p| fetched_at >= p.since) else { return };
+        let Some(sp) = self.speakers.iter().find(|s| s.id() == pending.speaker) else {
+            self.pending_start = None;
+            return;
+        };
+        let started = matches!(sp.state.as_str(), "PLAYING" | "TRANSITIONING");
+        if !started && fetched_at < pending.since + PLAY_CONFIRM_DELAY {
+            return;
+        }
+        let Some(p) = self.pending_start.take() else { return };
+        match (started, p.volume) {
+            (false, _) => self.set_status(format!("{} didn't start on {} — is the source available?", p.playlist, p.speaker_name), Linger::Error),
+            (true, Some(vol)) => self.set_status(format!("Playing {} on {} — volume set to {}", p.playlist, p.speaker_name, vol), Linger::Normal),
+            (true, None) => self.set_status(format!("Playing {} on {}", p.playlist, p.speaker_name), Linger::Normal),
+        }
+    }
+
+    /// Stop timing, returning how long the play lasted.
+    pub fn end_listening(&mut self) -> Option<Duration> {
+        let l = self.listening.take()?;
+        Some(if l.heard { self.clock.now().duration_since(l.since) } else { Duration::ZERO })
+    }
+
+    /// After a poll: the length of the timed play if it has now stopped
+    /// (paused, stopped, or its speaker vanished). A play never heard within
+    /// `LISTEN_GRACE` counts as zero.
+    pub fn listening_ended(&mut self) -> Option<Duration> {
+        let l = self.listening.as_mut()?;
+        let state = self.speakers.iter().find(|s| s.id() == l.speaker).map(|s| s.state.as_str());
+        match state {
+            Some("PLAYING") | Some("TRANSITIONING") => {
+                l.heard = true;
+                None
+            }
+            _ if !l.heard && self.clock.now().duration_since(l.since) < LISTEN_GRACE => None,
+            _ => self.end_listening(),
+        }
+    }
+
+    /// Record an action or status in the event log (and the session log file, if any).
+    pub fn log_event(&mut self, text: impl Into<String>) {
+        let entry = LogEntry { at: self.clock.local_seconds_of_day(), text: text.into() };
+        if let Some(file) = &mut self.log_file {
+            let _ = writeln!(file, "{} {}", clock::format_hms(entry.at), entry.text);
+        }
+        if self.event_log.len() == EVENT_LOG_CAPACITY {
+            self.event_log.pop_front();
+        }
+        self.event_log.push_back(entry);
+    }
+
+    /// Start writing the event log to `path` for this session, truncating it;
+    /// an empty path stops. Reopening the same path (a config reload) is a no-op.
+    pub fn set_log_file(&mut self, path: &str) {
+        if path == self.log_path {
+            return;
+        }
+        self.log_path = path.to_string();
+        self.log_file = None;
+        if path.is_empty() {
+            return;
+        }
+        match std::fs::File::create(path) {
+            Ok(file) => self.log_file = Some(file),
+            Err(e) => self.set_status(format!("The chronicle cannot be written to {}: {}", path, e), Linger::Error),
+        }
+    }
+
+    /// Add a command to the history, moving a repeat to the newest end.
+    /// Returns false (and records nothing) for a blank line.
+    pub fn remember_command(&mut self, line: &str) -> bool {
+        let line = line.trim();
+        if line.is_empty() {
+            return false;
+        }
+        self.command_history.retain(|c| c != line);
+        self.command_history.push(line.to_string());
+        let excess = self.command_history.len().saturating_sub(COMMAND_HISTORY_CAPACITY);
+        self.command_history.drain(..excess);
+        true
+    }
+
+    /// Ctrl-R in the `:` prompt: start searching the history, or step to the
+    /// next older match if a search is already running and has one.
+    pub fn search_history(&mut self) {
+        match &mut self.history_search {
+            Some(search) => {
+                search.skip += 1;
+                if self.history_match().is_none() {
+                    self.history_search.as_mut().unwrap().skip -= 1;
+                }
+            }
+            None => {
+                let original = self.command_input.clone().unwrap_or_default();
+                self.history_search = Some(HistorySearch { query: String::new(), original, skip: 0 });
+            }
+        }
+    }
+
+    /// Change the search query; the newest match is shown again.
+    pub fn edit_history_query(&mut self, edit: impl FnOnce(&mut String)) {
+        if let Some(search) = &mut self.history_search {
+            edit(&mut search.query);
+            search.skip = 0;
+        }
+    }
+
+    /// The command the running search lands on: the newest one containing the
+    /// query (ignoring case), less any Ctrl-R steps.
+    pub fn history_match(&self) -> Option<&str> {
+        let search = self.history_search.as_ref()?;
+        let query = search.query.to_lowercase();
+        self.command_history
+            .iter()
+            .rev()
+            .filter(|c| c.to_lowercase().contains(&query))
+            .nth(search.skip)
+            .map(String::as_str)
+    }
+
+    /// Leave the search, putting back the prompt as it was before Ctrl-R.
+    pub fn cancel_history_search(&mut self) {
+        if let Some(search) = self.history_search.take() {
+            self.command_input = Some(search.original);
+        }
+    }
+
+    pub fn toggle_log(&mut self) {
+        self.log_open = !self.log_open;
+        self.log_scroll = 0;
+    }
+
+    /// Scroll the `list` table `delta` rows down (+) or up (-), keeping a row in view.
+    pub fn scroll_roster(&mut self, delta: isize) {
+        let max = self.speakers.len().saturating_sub(1);
+        if let Some(scroll) = &mut self.roster {
+            *scroll = scroll.saturating_add_signed(delta).min(max);
+        }
+    }
+
+    /// Scroll the log overlay `delta` lines toward older (+) or newer (-) entries.
+    pub fn scroll_log(&mut self, delta: isize) {
+        let max = self.event_log.len().saturating_sub(1);
+        self.log_scroll = self.log_scroll.saturating_add_signed(delta).min(max);
+    }
+
+    pub fn active_status(&self) -> String {
+        // Sleep countdown takes lowest priority — shown only when no message
+        // is up, and a sticky one holds the line until it's replaced
+        let now = self.clock.now();
+        if self.status_showing(now) {
+            return self.status_message.clone().unwrap_or_default();
+        }
+        if let Some(sleep_until) = self.sleep_until {
+            if let Some(remaining) = sleep_until.checked_duration_since(now) {
+                let secs = remaining.as_secs();
+                return format!("Sleep: {}:{:02} remaining", secs / 60, secs % 60);
+            }
+        }
+        if let Some(timer) = self.sleep_timers.iter().min_by_key(|t| t.until) {
+            if let Some(remaining) = timer.until.checked_duration_since(now) {
+                let secs = remaining.as_secs();
+                return format!("Sleep ({}): {}:{:02} remaining", timer.label, secs / 60, secs % 60);
+            }
+        }
+        String::new()
+    }
+
+    /// Lowest-priority status fallback: the selected speaker's track, shown only
+    /// when the Now Playing panel isn't focused and nothing else wants the line.
+    pub fn ticker(&self) -> Option<String> {
+        if self.active_panel == Panel::NowPlaying || !self.active_status().is_empty() {
+            return None;
+        }
+        let track = self.selected_speaker()?.track.as_ref()?;
+        Some(format!("♫ {} — {} — {}", track.title, track.artist, track.album))
+    }
+
+    pub fn is_grouped(&self) -> bool {
+        // A speaker is a group follower when its coordinator differs from its own name.
+        // If any follower exists, speakers are grouped.
+        self.speakers.iter().any(|s| {
+            s.coordinator_key()
+                .map(|coord| coord != s.group_key())
+                .unwrap_or(false)
+        })
+    }
+
+    /// Whether the rooms `group all` gathers (`group_targets`, else everyone)
+    /// already share one coordinator; failing that, whether anything is grouped.
+    pub fn grouping_state(&self) -> GroupingState {
+        let targets = self.group_targets();
+        let gathered: Vec<&Speaker> = self.speakers.iter()
+            .filter(|s| targets.as_ref().is_none_or(|ids| ids.contains(&s.id())))
+            .collect();
+        let lead = gathered.first().map(|s| self.coordinator_of(s).id());
+        if gathered.len() >= 2 && gathered.iter().all(|s| Some(self.coordinator_of(s).id()) == lead) {
+            GroupingState::Full
+        } else if self.is_grouped() {
+            GroupingState::Partial
+        } else {
+            GroupingState::None
+        }
+    }
+
+    /// Whether Rooms draws groups as boxed topology rather than the flat list.
+    pub fn show_topology(&self) -> bool {
+        self.is_grouped() && !self.flat_rooms
+    }
+
+    /// Returns all speakers whose coordinator is `coordinator` (including itself).
+    pub fn group_members_of<'a>(&'a self, coordinator: &Speaker) -> Vec<&'a Speaker> {
+        let key = coordinator.group_key();
+        self.speakers.iter().filter(|s| s.coordinator_key() == Some(key)).collect()
+    }
+
+    /// The group a solo `sp` joins on `o`: the largest other group (a playing
+    /// one on a tie), else a playing room on its own. None when there's
+    /// neither. Returns that group's coordinator.
+    pub fn join_target(&self, sp: &Speaker) -> Option<&Speaker> {
+        let mut leads: Vec<&Speaker> = vec![];
+        for other in self.speakers.iter().filter(|s| s.id() != sp.id()) {
+            let coord = self.coordinator_of(other);
+            if coord.id() != sp.id() && !leads.iter().any(|c| c.id() == coord.id()) {
+                leads.push(coord);
+            }
+        }
+        let biggest = leads.iter()
+            .filter(|c| self.group_size(c) > 1)
+            .max_by_key(|c| (self.group_size(c), c.state == "PLAYING"));
+        biggest.or_else(|| leads.iter().find(|c| c.state == "PLAYING")).copied()
+    }
+
+    /// Rooms in `coordinator`'s group, itself included (1 when it plays alone).
+    pub fn group_size(&self, coordinator: &Speaker) -> usize {
+        self.speakers.iter().filter(|s| self.coordinator_of(s).id() == coordinator.id()).count()
+    }
+
+    /// Returns speakers with no group_coordinator (truly ungrouped/solo).
+    pub fn solo_speakers(&self) -> Vec<&Speaker> {
+        self.speakers.iter().filter(|s| s.coordinator_key().is_none()).collect()
+    }
+
+    /// Returns coordinator speakers (their coordinator is themselves).
+    pub fn coordinators(&self) -> Vec<&Speaker> {
+        self.speakers.iter().filter(|s| s.is_coordinator()).collect()
+    }
+
+    /// Speaker ids `group all` should gather, in configured order (first is the
+    /// coordinator). None means no subset is configured and nobody is excluded —
+    /// group everything. Configured names not currently reported by the daemon
+    /// are skipped as offline; do-not-disturb speakers are always skipped.
+    pub fn group_targets(&self) -> Option<Vec<String>> {
+        if self.group_set.is_empty() {
+            return (!self.do_not_disturb.is_empty()).then(|| self.all_targets());
+        }
+        Some(self.resolve_online(&self.group_set))
+    }
+
+    /// Ids of the online, not-do-not-disturb speakers among `names`, in order.
+    fn resolve_online(&self, names: &[String]) -> Vec<String> {
+        names.iter()
+            .filter_map(|name| self.speakers.iter().find(|s| {
+                s.display_name().eq_ignore_ascii_case(name) || s.name.eq_ignore_ascii_case(name)
+            }))
+            .filter(|s| !self.excludes(s))
+            .map(|s| s.id())
+            .collect()
+    }
+
+    /// The coordinator of the group playing zone `name` (any case): the group
+    /// holding the most of the zone's online rooms, the earliest-listed room's
+    /// group on a tie. None for an unknown zone or one with nobody online.
+    pub fn zone_coordinator(&self, name: &str) -> Option<&Speaker> {
+        let (_, rooms) = self.zones.iter().find(|(zone, _)| zone.eq_ignore_ascii_case(name))?;
+        let online: Vec<&Speaker> = rooms.iter()
+            .filter_map(|room| self.speakers.iter().find(|s| {
+                s.display_name().eq_ignore_ascii_case(room) || s.name.eq_ignore_ascii_case(room)
+            }))
+            .collect();
+        let mut best: Option<(&Speaker, usize)> = None;
+        for sp in &online {
+            let coord = self.coordinator_of(sp);
+            let held = online.iter().filter(|o| self.coordinator_of(o).id() == coord.id()).count();
+            if best.is_none_or(|(_, most)| held > most) {
+                best = Some((coord, held));
+            }
+        }
+        best.map(|(coord, _)| coord)
+    }
+
+    /// Ids of every room in the group playing zone `name` (see
+    /// `zone_coordinator`), coordinator first. None for an unknown zone.
+    pub fn zone_members(&self, name: &str) -> Option<Vec<String>> {
+        let coord = self.zone_coordinator(name)?;
+        let mut ids = vec![coord.id()];
+        ids.extend(self.group_members_of(coord).iter().map(|s| s.id()).filter(|id| *id != coord.id()));
+        Some(ids)
+    }
+
+    /// Split `play <zone or room> <playlist>` into (coordinator id, target
+    /// label, playlist query). The target is the longest leading run of words
+    /// naming a zone or, failing that, a room exactly; a zone wins when both
+    /// share a name. None when no target leads, no playlist follows, or the
+    /// whole line already names a playlist exactly.
+    pub fn play_target(&self, line: &str) -> Option<(String, String, String)> {
+        let exact = line.to_lowercase();
+        if self.playlists.iter().any(|p| p.alias.to_lowercase() == exact || p.favorite_name.to_lowercase() == exact) {
+            return None;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        for n in (1..words.len()).rev() {
+            let (target, rest) = (words[..n].join(" "), words[n..].join(" "));
+            if self.find_playlist(&rest).is_none() {
+                continue;
+            }
+            if let Some(coord) = self.zone_coordinator(&target) {
+                return Some((coord.id(), target, rest));
+            }
+            let room = self.speakers.iter().find(|s| {
+                s.display_name().eq_ignore_ascii_case(&target) || s.name.eq_ignore_ascii_case(&target)
+            });
+            if let Some(sp) = room {
+                return Some((self.coordinator_of(sp).id(), sp.display_name().to_string(), rest));
+            }
+        }
+        None
+    }
+
+    /// Where `play <line>` lands and what it starts: the target's id and name
+    /// (a zone or room named first, else the selected speaker) and the
+    /// matched alias, None when no playlist answers. None with no speakers.
+    pub fn resolve_play(&self, line: &str) -> Option<(String, String, Option<String>)> {
+        if let Some((id, target, query)) = self.play_target(line) {
+            let alias = self.find_playlist(&query).map(|pl| pl.alias.clone());
+            return Some((id, target, alias));
+        }
+        let (id, name) = (self.speaker_id()?, self.speaker_name()?);
+        Some((id, name, self.find_playlist(line).map(|pl| pl.alias.clone())))
+    }
+
+    /// Ids of the `play_group` rooms that are online, first leads; empty
+    /// when the option is off or none of them answer.
+    pub fn play_group_targets(&self) -> Vec<String> {
+        self.resolve_online(&self.play_group)
+    }
+
+    /// The (id, name) of the room a `play_group` play lands on: the first of
+    /// the set that's online. None when the option is off or nobody answers.
+    pub fn play_group_leader(&self) -> Option<(String, String)> {
+        let id = self.play_group_targets().into_iter().next()?;
+        let sp = self.speakers.iter().find(|s| s.id() == id)?;
+        Some((id, sp.display_name().to_string()))
+    }
+
+    /// Who `party` gathers, first leads: `party.speakers`, else the `group all` set.
+    pub fn party_targets(&self) -> Vec<String> {
+        if !self.party.speakers.is_empty() {
+            return self.resolve_online(&self.party.speakers);
+        }
+        self.group_targets().unwrap_or_else(|| self.all_targets())
+    }
+
+    /// Remember `ids`' volumes (unless a party is already on) and set them all to
+    /// `volume`. Returns the (speaker id, volume) changes to send.
+    pub fn begin_party(&mut self, ids: &[String], volume: u8) -> Vec<(String, u8)> {
+        if self.party_restore.is_none() {
+            self.party_restore = Some(self.speakers.iter()
+                .filter(|s| ids.contains(&s.id()))
+                .map(|s| (s.id(), s.volume))
+                .collect());
+        }
+        let volume = volume.min(100);
+        for id in ids {
+            self.set_volume(id, volume);
+        }
+        ids.iter().map(|id| (id.clone(), volume)).collect()
+    }
+
+    /// End the party: return the captured volumes so they can be restored.
+    pub fn end_party(&mut self) -> Vec<(String, u8)> {
+        let prior = self.party_restore.take().unwrap_or_default();
+        for (id, volume) in &prior {
+            self.set_volume(id, *volume);
+        }
+        prior
+    }
+
+    /// Whether `name` (a room name or alias) is marked do-not-disturb.
+    pub fn is_excluded(&self, name: &str) -> bool {
+        self.do_not_disturb.iter().any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    pub fn excludes(&self, sp: &Speaker) -> bool {
+        self.is_excluded(sp.display_name()) || self.is_excluded(&sp.name)
+    }
+
+    /// Ids of every speaker an "all" action should touch.
+    pub fn all_targets(&self) -> Vec<String> {
+        self.speakers.iter().filter(|s| !self.excludes(s)).map(|s| s.id()).collect()
+    }
+
+    /// Flip do-not-disturb for the selected speaker; returns its new state.
+    pub fn toggle_do_not_disturb(&mut self) -> Option<bool> {
+        let sp = self.selected_speaker()?;
+        let (display, name) = (sp.display_name().to_string(), sp.name.clone());
+        let excluded = self.excludes(sp);
+        if excluded {
+            self.do_not_disturb.retain(|n| !n.eq_ignore_ascii_case(&display) && !n.eq_ignore_ascii_case(&name));
+        } else {
+            self.do_not_disturb.push(display);
+        }
+        self.do_not_disturb_saved = true;
+        Some(!excluded)
+    }
+
+    /// Preferred volume for `alias`, if one is configured, clamped to 0–100.
+    pub fn playlist_volume(&self, alias: &str) -> Option<u8> {
+        self.playlist_volumes.get(alias).map(|v| (*v).min(100))
+    }
+
+    /// Ids of the speakers a sleep timer covers: the target's group, or everyone.
+    fn sleep_members(&self, target: Option<&str>) -> Vec<String> {
+        let Some(coordinator) = target else {
+            return self.all_targets();
+        };
+        let key = self.speakers.iter()
+            .find(|s| s.id() == coordinator)
+            .map(|s| s.group_key().to_string());
+        self.speakers.iter()
+            .filter(|s| s.id() == coordinator || (key.is_some() && s.coordinator_key() == key.as_deref()))
+            .map(|s| s.id())
+            .collect()
+    }
+
+    /// Remember which of `ids` a sleep timer is about to pause: the coordinators
+    /// of those still playing. Call before the pause goes out.
+    pub fn remember_sleep_paused(&mut self, ids: &[String]) {
+        let woken: Vec<String> = self.speakers.iter()
+            .filter(|s| ids.contains(&s.id()) && s.state == "PLAYING")
+            .map(|s| self.coordinator_of(s).id())
+            .collect();
+        for id in woken {
+            if !self.sleep_paused.contains(&id) {
+                self.sleep_paused.push(id);
+            }
+        }
+    }
+
+    /// Forget `coordinator`'s sleep pause, returning whether it had one — i.e.
+    /// whether this resume is waking it from a timer rather than a manual pause.
+    pub fn wake_from_sleep(&mut self, coordinator: &str) -> bool {
+        let before = self.sleep_paused.len();
+        self.sleep_paused.retain(|id| id != coordinator);
+        self.sleep_paused.len() != before
+    }
+
+    /// Advance scoped/fading sleep timers: step fading volumes down, and on expiry
+    /// pause the target and restore the pre-fade volumes. Returns the calls to send.
+    pub fn tick_sleep_timers(&mut self) -> Vec<SleepAction> {
+        let now = self.clock.now();
+        let mut actions = vec![];
+        let mut finished = vec![];
+        for i in 0..self.sleep_timers.len() {
+            let timer = self.sleep_timers[i].clone();
+            let members = self.sleep_members(timer.target.as_deref());
+            let remaining = timer.until.saturating_duration_since(now);
+
+            if timer.fade && remaining < SLEEP_FADE {
+                if timer.faded_from.is_empty() {
+                    self.sleep_timers[i].faded_from = self.speakers.iter()
+                        .filter(|s| members.contains(&s.id()))
+                        .map(|s| (s.id(), s.volume))
+                        .collect();
+                }
+                for (id, original) in self.sleep_timers[i].faded_from.clone() {
+                    let level = (original as u128 * remaining.as_millis() / SLEEP_FADE.as_millis()) as u8;
+                    let current = self.speakers.iter().find(|s| s.id() == id).map(|s| s.volume);
+                    if current.is_some_and(|v| level < v) {
+                        actions.push(SleepAction::SetVolume(id.clone(), level));
+                        self.set_volume(&id, level);
+                    }
+                }
+            }
+
+            if remaining.is_zero() {
+                self.remember_sleep_paused(&members);
+                match &timer.target {
+                    Some(coordinator) => actions.push(SleepAction::Pause(coordinator.clone())),
+                    None => actions.extend(members.iter().cloned().map(SleepAction::Pause)),
+                }
+                for (id, original) in &self.sleep_timers[i].faded_from {
+                    actions.push(SleepAction::SetVolume(id.clone(), *original));
+                }
+                finished.push(i);
+            }
+        }
+        for i in finished.into_iter().rev() {
+            let timer = self.sleep_timers.remove(i);
+            for (id, original) in &timer.faded_from {
+                self.set_volume(id, *original);
+            }
+            self.set_status(format!("{} drifts into sleep.", timer.label), Linger::Long);
+        }
+        actions
+    }
+
+    /// Drop every sleep timer, returning volume restores for any mid-fade.
+    pub fn cancel_sleep(&mut self) -> Vec<SleepAction> {
+        self.sleep_until = None;
+        let mut actions = vec![];
+        for timer in std::mem::take(&mut self.sleep_timers) {
+            for (id, original) in timer.faded_from {
+                self.set_volume(&id, original);
+                actions.push(SleepAction::SetVolume(id, original));
+            }
+        }
+        actions
+    }
+
+    fn near_track_end(&self, sp: &Speaker) -> bool {
+        let Some(track) = &sp.track else { return false };
+        self.next_up_seconds > 0
+            && self.supports("queue")
+            && track.duration > 0
+            && track.duration.saturating_sub(track.position) <= self.next_up_seconds
+    }
+
+    /// Coordinator id whose queue should be fetched now: the selected speaker's
+    /// group is near the end of a track we haven't looked ahead from yet.
+    pub fn next_up_request(&self) -> Option<String> {
+        let sp = self.selected_speaker()?;
+        if !self.near_track_end(sp) {
+            return None;
+        }
+        let id = self.coordinator_of(sp).id();
+        let title = &sp.track.as_ref()?.title;
+        match &self.next_up {
+            Some(n) if n.speaker == id && n.current_title == *title => None,
+            _ => Some(id),
+        }
+    }
+
+    /// Upcoming track to show under `sp`'s Now Playing block, if it's due.
+    pub fn next_up_for(&self, sp: &Speaker) -> Option<&QueueItem> {
+        let next = self.next_up.as_ref()?;
+        let title = &sp.track.as_ref()?.title;
+        if next.speaker == self.coordinator_of(sp).id() && next.current_title == *title && self.near_track_end(sp) {
+            next.track.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Quick-jump (`1`–`9`): select the `n`th speaker (1-based) and focus Rooms.
+    /// Numbers past the end of the list are ignored.
+    pub fn jump_to_speaker(&mut self, n: usize) {
+        if n >= 1 && n <= self.speakers.len() {
+            self.speaker_index = n - 1;
+            self.active_panel = Panel::Speakers;
+        }
+    }
+
+    /// Move the selected playlist one slot up (`-1`) or down (`1`), keeping it
+    /// selected, and capture the result as the manual order. False at either end.
+    pub fn move_playlist(&mut self, delta: isize) -> bool {
+        let from = self.playlist_index;
+        let Some(to) = from.checked_add_signed(delta).filter(|&to| to < self.playlists.len()) else {
+            return false;
+        };
+        if from >= self.playlists.len() {
+            return false;
+        }
+        self.playlists.swap(from, to);
+        self.playlist_index = to;
+        self.playlist_order = self.playlists.iter().map(|p| p.alias.clone()).collect();
+        true
+    }
+
+    /// Fuzzy speaker lookup by alias or room name, case-insensitive:
+    /// exact match first, then prefix, then substring.
+    pub fn find_speaker(&self, query: &str) -> Option<&Speaker> {
+        let q = query.to_lowercase();
+        let names = |s: &Speaker| [s.display_name().to_lowercase(), s.name.to_lowercase()];
+        self.speakers.iter().find(|s| names(s).contains(&q))
+            .or_else(|| self.speakers.iter().find(|s| names(s).iter().any(|n| n.starts_with(&q))))
+            .or_else(|| self.speakers.iter().find(|s| names(s).iter().any(|n| n.contains(&q))))
+    }
+
+    /// Playlist lookup for `play` by alias or favorite name, case-insensitive:
+    /// exact match first, then substring, then the best subsequence
+    /// (`jzcl` → "Jazz Classics").
+    pub fn find_playlist(&self, query: &str) -> Option<&Playlist> {
+        let q = query.to_lowercase();
+        let names = |p: &Playlist| [p.alias.to_lowercase(), p.favorite_name.to_lowercase()];
+        self.playlists.iter().find(|p| names(p).contains(&q))
+            .or_else(|| self.playlists.iter().find(|p| names(p).iter().any(|n| n.contains(&q))))
+            .or_else(|| crate::command::best_subsequence(&q, &self.playlists))
+    }
+
+    /// Select the room called `name` (alias or room name, any case); false if
+    /// it isn't on the network, leaving the selection where it was.
+    pub fn select_speaker_named(&mut self, name: &str) -> bool {
+        let Some(i) = self.speakers.iter().position(|s| {
+            s.display_name().eq_ignore_ascii_case(name) || s.name.eq_ignore_ascii_case(name)
+        }) else { return false };
+        self.speaker_index = i;
+        true
+    }
+
+    /// Where this window is now, for `prefs::save_session`.
+    pub fn session(&self) -> crate::prefs::Session {
+        crate::prefs::Session {
+            panel: Some(self.active_panel),
+            speaker: self.speaker_id(),
+            playlist: self.playlists.get(self.playlist_index).map(|pl| pl.alias.clone()),
+        }
+    }
+
+    /// Pick up where a saved session left off. Rooms and playlists are found
+    /// by identity, so reordering since doesn't matter; vanished ones leave
+    /// the selection alone. `speaker` is false when config's `default_speaker`
+    /// already chose the room.
+    pub fn restore_session(&mut self, session: &crate::prefs::Session, speaker: bool) {
+        if let Some(panel) = session.panel {
+            self.active_panel = panel;
+        }
+        if let Some(i) = session.speaker.as_ref().filter(|_| speaker).and_then(|id| self.speakers.iter().position(|s| s.id() == *id)) {
+            self.speaker_index = i;
+        }
+        if let Some(i) = session.playlist.as_ref().and_then(|alias| self.playlists.iter().position(|pl| pl.alias == *alias)) {
+            self.playlist_index = i;
+        }
+    }
+
+    /// The `--mini` pane's speaker, if it's on the network.
+    pub fn mini_speaker(&self) -> Option<&Speaker> {
+        self.mini.as_deref().and_then(|q| self.find_speaker(q))
+    }
+
+    /// The speaker that controls playback for `sp`: its group coordinator if
+    /// grouped (and known), otherwise `sp` itself.
+    pub fn coordinator_of<'a>(&'a self, sp: &'a Speaker) -> &'a Speaker {
+        sp.coordinator_key()
+            .and_then(|key| self.speakers.iter().find(|s| s.group_key() == key))
+            .unwrap_or(sp)
+    }
+
+    /// The speaker and position a click at (`column`, `row`) on a drawn progress bar
+    /// seeks to. Streams (zero duration) have no bar and never match.
+    pub fn seek_target_at(&self, column: u16, row: u16) -> Option<(String, u64)> {
+        let targets = self.seek_targets.borrow();
+        let t = targets.iter().find(|t| {
+            t.duration > 0
+                && row == t.area.y
+                && column >= t.area.x
+                && column < t.area.x + t.area.width
+        })?;
+        let ratio = (column - t.area.x) as f64 / t.area.width as f64;
+        Some((t.speaker.clone(), (ratio * t.duration as f64) as u64))
+    }
+
+    /// The speaker whose Rooms row was drawn at (`column`, `row`).
+    pub fn room_row_at(&self, column: u16, row: u16) -> Option<String> {
+        let position = ratatui::layout::Position { x: column, y: row };
+        self.room_rows.borrow().iter().find(|r| r.area.contains(position)).map(|r| r.speaker.clone())
+    }
+
+    /// One wheel notch on speaker `id`: step its volume (within 0–100) at once,
+    /// and return it when it should be sent now — otherwise it waits for
+    /// `wheel_volumes_due`. None for an unknown speaker.
+    pub fn wheel_volume(&mut self, id: &str, step: i16) -> Option<u8> {
+        let now = self.clock.now();
+        let sp = self.speakers.iter().find(|s| s.id() == id)?;
+        let volume = (sp.volume as i16 + step).clamp(0, 100) as u8;
+        self.set_volume(id, volume);
+        match self.wheel_sends.get_mut(id) {
+            Some((sent_at, waiting)) if now.duration_since(*sent_at) < WHEEL_THROTTLE => {
+                *waiting = true;
+                None
+            }
+            _ => {
+                self.wheel_sends.insert(id.to_string(), (now, false));
+                Some(volume)
+            }
+        }
+    }
+
+    /// Wheel volumes held back by `WHEEL_THROTTLE` whose window has passed.
+    pub fn wheel_volumes_due(&mut self) -> Vec<(String, u8)> {
+        let now = self.clock.now();
+        let mut due = Vec::new();
+        for (id, (sent_at, waiting)) in &mut self.wheel_sends {
+            if *waiting && now.duration_since(*sent_at) >= WHEEL_THROTTLE {
+                if let Some(sp) = self.speakers.iter().find(|s| s.id() == *id) {
+                    due.push((id.clone(), sp.volume));
+                }
+                (*sent_at, *waiting) = (now, false);
+            }
+        }
+        self.wheel_sends.retain(|_, (sent_at, waiting)| *waiting || now.duration_since(*sent_at) < WHEEL_THROTTLE);
+        due
+    }
+
+    /// Optimistically set the transport state of `coordinator` and its group members.
+    pub fn set_group_state(&mut self, coordinator: &str, state: &str) {
+        let key = self.speakers.iter().find(|s| s.id() == coordinator).map(|s| s.group_key().to_string());
+        for sp in &mut self.speakers {
+            if sp.id() == coordinator || (key.is_some() && sp.coordinator_key() == key.as_deref()) {
+                sp.state = state.to_string();
+            }
+        }
+    }
+
+    /// Index of `sp` in `self.speakers`, matched by stable id.
+    pub fn index_of(&self, sp: &Speaker) -> Option<usize> {
+        let id = sp.id();
+        self.speakers.iter().position(|s| s.id() == id)
+    }
+
+    /// Optimistically record a mute change until the next poll confirms it.
+    pub fn set_muted(&mut self, id: &str, muted: bool) {
+        for sp in &mut self.speakers {
+            if sp.id() == id {
+                sp.muted = muted;
+            }
+        }
+    }
+
+    /// Panic stop: mark every group paused and every speaker muted — do-not-disturb
+    /// or not — and return (coordinators to pause, speakers to mute). What was
+    /// playing or unmuted is remembered for `undo`, adding to an earlier panic's.
+    pub fn silence_everything(&mut self) -> (Vec<String>, Vec<String>) {
+        let groups: Vec<String> = self.speakers.iter()
+            .filter(|s| self.coordinator_of(s).id() == s.id())
+            .map(|s| s.id())
+            .collect();
+        let speakers: Vec<String> = self.speakers.iter().map(|s| s.id()).collect();
+        let silenced = self.silenced.get_or_insert_with(Silenced::default);
+        for sp in &self.speakers {
+            if groups.contains(&sp.id()) && matches!(sp.state.as_str(), "PLAYING" | "TRANSITIONING") && !silenced.playing.contains(&sp.id()) {
+                silenced.playing.push(sp.id());
+            }
+            if !sp.muted && !silenced.unmuted.contains(&sp.id()) {
+                silenced.unmuted.push(sp.id());
+            }
+        }
+        for id in &groups {
+            self.set_group_state(id, "PAUSED_PLAYBACK");
+        }
+        for id in &speakers {
+            self.set_muted(id, true);
+        }
+        self.solo_restore = None;
+        (groups, speakers)
+    }
+
+    /// Start soloing the selected speaker: remember everyone's mute state and
+    /// return the (speaker id, muted) changes to send — all others muted, selected unmuted.
+    pub fn begin_solo(&mut self) -> Vec<(String, bool)> {
+        let selected = match self.speaker_id() {
+            Some(id) => id,
+            None => return vec![],
+        };
+        let prior: Vec<(String, bool)> = self.speakers.iter()
+            .map(|s| (s.id(), s.muted))
+            .collect();
+        let changes: Vec<(String, bool)> = prior.iter()
+            .map(|(id, _)| (id.clone(), *id != selected))
+            .collect();
+        self.solo_restore = Some(prior);
+        for (id, muted) in &changes {
+            self.set_muted(id, *muted);
+        }
+        changes
+    }
+
+    /// End solo: return the captured mute states so they can be restored.
+    pub fn end_solo(&mut self) -> Vec<(String, bool)> {
+        let prior = self.solo_restore.take().unwrap_or_default();
+        for (id, muted) in &prior {
+            self.set_muted(id, *muted);
+        }
+        prior
+    }
+
+    pub fn toggle_source(&mut self) {
+        self.source_mode = match self.source_mode {
+            SourceMode::Playlists => SourceMode::Podcasts,
+            SourceMode::Podcasts => SourceMode::Playlists,
+        };
+        self.podcast_drill = false;
+    }
+
+    pub fn selected_podcast(&self) -> Option<&crate::api::Podcast> {
+        self.podcasts.get(self.podcast_index)
+    }
+
+    pub fn selected_episode(&self) -> Option<&crate::api::Episode> {
+        self.episodes.get(self.episode_index)
+    }
+
+    pub fn is_podcast_playing(&self) -> bool {
+        self.selected_speaker()
+            .and_then(|s| s.track.as_ref())
+            .map(|t| t.source == "Podcast")
+            .unwrap_or(false)
+    }
+
+    /// One entry per distinct group (represented by coordinator) + each solo speaker.
+    pub fn playing_entities(&self) -> Vec<&Speaker> {
+        let mut seen: std::collections::HashSet<&str> = std::collections::HashSet::new();
+        let mut result = vec![];
+        for sp in &self.speakers {
+            match sp.coordinator_key() {
+                // coordinator — include once
+                Some(coord) if coord == sp.group_key() && seen.insert(coord) => {
+                    result.push(sp);
+                }
+                None => result.push(sp), // ungrouped solo
+                _ => {}                  // follower — skip
+            }
+        }
+        result
+    }
+}
+
+/// Where a selection at `index` in `old` should land in `new`: the same key if it
+/// survived, else the nearest surviving old neighbour (after, then before), else clamped.
+fn repin_index(old: &[String], index: usize, new: &[String]) -> usize {
+    let find = |key: &String| new.iter().position(|k| k == key);
+    if index < old.len() {
+        let after = old[index..].iter().find_map(find);
+        let before = || old[..index].iter().rev().find_map(find);
+        if let Some(i) = after.or_else(before) {
+            return i;
+        }
+    }
+    index.min(new.len().saturating_sub(1))
+}
+
+/// One entry per logical room: members bonded to a speaker that's also in
+/// the list (stereo pair halves, surrounds, subs) are folded into it. Members
+/// whose primary isn't reported stay, so nothing silently disappears.
+pub fn logical_speakers(speakers: Vec<Speaker>) -> Vec<Speaker> {
+    let uids: HashSet<String> = speakers.iter().map(|s| s.uid.clone()).filter(|u| !u.is_empty()).collect();
+    speakers
+        .into_iter()
+        .filter(|s| !s.bonded_to.as_ref().is_some_and(|primary| *primary != s.uid && uids.contains(primary)))
+        .collect()
+}
+
+/// Add Sonos Favorites not already covered by a configured playlist
+/// (matched case-insensitively on favorite name).
+pub fn merge_favorites(playlists: &mut Vec<Playlist>, favorites: Vec<String>) {
+    let existing: std::collections::HashSet<String> = playlists
+        .iter()
+        .map(|p| p.favorite_name.to_lowercase())
+        .collect();
+    for title in favorites {
+        if !existing.contains(&title.to_lowercase()) {
+            playlists.push(Playlist {
+                alias: title.clone(),
+                favorite_name: title,
+                source: PlaylistSource::Favorite,
+            });
+        }
+    }
+}
+
+/// Reorder `playlists` to follow `order` (by alias). Entries missing from `order`,
+/// e.g. newly added favorites, keep their relative order after the curated ones.
+pub fn apply_manual_order(playlists: &mut [Playlist], order: &[String]) {
+    let rank = |p: &Playlist| order.iter().position(|a| *a == p.alias).unwrap_or(usize::MAX);
+    playlists.sort_by_key(rank);
+}
+
+/// Themed one-liners for notable differences between two speaker snapshots,
+/// limited to the enabled `categories`. Speakers are matched by id.
+pub fn describe_changes(old: &[Speaker], new: &[Speaker], categories: &[String]) -> Vec<String> {
+    let on = |c: &str| categories.iter().any(|k| k == c);
+    let name_of = |key: &str| {
+        new.iter().find(|s| s.group_key() == key).map(|s| s.display_name().to_string())
+    };
+    let mut out = Vec::new();
+    for sp in new {
+        let Some(prev) = old.iter().find(|o| o.id() == sp.id()) else { continue };
+        let name = sp.display_name();
+        if on("playback") && prev.state != sp.state {
+            match sp.state.as_str() {
+                "PLAYING" => out.push(format!("{} started playing.", name)),
+                "PAUSED_PLAYBACK" => out.push(format!("{} paused.", name)),
+                "STOPPED" => out.push(format!("{} fell silent.", name)),
+                _ => {}
+            }
+        }
+        if on("volume") && prev.volume != sp.volume {
+            out.push(format!("{} volume {} → {}.", name, prev.volume, sp.volume));
+        }
+        let leader = |s: &Speaker| s.coordinator_key().filter(|&k| k != s.group_key()).map(str::to_string);
+        if on("grouping") && leader(prev) != leader(sp) {
+            match leader(sp).and_then(|k| name_of(&k)) {
+                Some(coord) => out.push(format!("{} joined {}'s fellowship.", name, coord)),
+                None => out.push(format!("{} left the fellowship.", name)),
+            }
+        }
+    }
+    out
+}
+
+/// What had drifted by a `resync`: rooms that appeared or vanished, then
+/// rooms whose group changed.
+pub fn describe_resync(old: &[Speaker], new: &[Speaker]) -> Vec<String> {
+    let known = |list: &[Speaker], sp: &Speaker| list.iter().any(|s| s.id() == sp.id());
+    let mut out: Vec<String> = new.iter()
+        .filter(|sp| !known(old, sp))
+        .map(|sp| format!("{} answers the call.", sp.display_name()))
+        .collect();
+    out.extend(old.iter()
+        .filter(|sp| !known(new, sp))
+        .map(|sp| format!("{} has passed into the West.", sp.display_name())));
+    out.extend(describe_changes(old, new, &["grouping".to_string()]));
+    out
+}
+
+/// Id of the first group leader (or lone speaker) in `new` that is PLAYING
+/// but wasn't in `old`. Speakers `old` didn't know about don't count.
+fn newly_started(old: &[Speaker], new: &[Speaker]) -> Option<String> {
+    new.iter()
+        .filter(|sp| sp.state == "PLAYING")
+        .filter(|sp| sp.coordinator_key().is_none_or(|k| k == sp.group_key()))
+        .find(|sp| old.iter().any(|o| o.id() == sp.id() && o.state != "PLAYING"))
+        .map(|sp| sp.id())
+}
+
+/// Order speakers deterministically so polls don't shuffle the list.
+/// `"name"`: by display name. `"group"`: by group (named after its coordinator),
+/// coordinator first, then members by name. Anything else keeps daemon order.
+pub fn sort_speakers(speakers: &mut [Speaker], mode: &str) {
+    let name_key = |s: &Speaker| (s.display_name().to_lowercase(), s.id());
+    match mode {
+        "name" => speakers.sort_by_key(name_key),
+        "group" => {
+            let coordinator_names: HashMap<String, String> = speakers.iter()
+                .filter(|s| s.is_coordinator())
+                .map(|s| (s.group_key().to_string(), s.display_name().to_lowercase()))
+                .collect();
+            speakers.sort_by_key(|s| {
+                let group = s.coordinator_key()
+                    .and_then(|k| coordinator_names.get(k).cloned())
+                    .unwrap_or_else(|| s.display_name().to_lowercase());
+                (group, !s.is_coordinator(), name_key(s))
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Put rooms named (or aliased) in `order` first, in that order; the rest
+/// follow alphabetically. Names in `order` that aren't present are skipped.
+pub fn sort_by_room_order(speakers: &mut [Speaker], order: &[String]) {
+    let order: Vec<String> = order.iter().map(|n| n.to_lowercase()).collect();
+    speakers.sort_by_key(|s| {
+        let names = [s.display_name().to_lowercase(), s.name.to_lowercase()];
+        let rank = order.iter().position(|n| names.contains(n)).unwrap_or(order.len());
+        (rank, names[0].clone(), s.id())
+    });
+}
+
+/// Next index in a list of `len` items, wrapping to 0 or clamping at the end.
+fn step_next(index: usize, len: usize, wrap: bool) -> usize {
+    if len == 0 {
+        index
+    } else if index + 1 < len {
+        index + 1
+    } else if wrap {
+        0
+    } else {
+        len - 1
+    }
+}
+
+/// Previous index in a list of `len` items, wrapping to the end or clamping at 0.
+fn step_prev(index: usize, len: usize, wrap: bool) -> usize {
+    if len == 0 {
+        index
+    } else if index > 0 {
+        index - 1
+    } else if wrap {
+        len - 1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Speaker, Track};
+    use crate::clock::MockClock;
+
+    fn mock_app() -> (App, Rc<MockClock>) {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        (App::with_clock(clock.clone()), clock)
+    }
+
+    /// Speaker with UID `RINCON_<name>`; `coordinator` is the coordinator's name.
+    fn make_speaker(name: &str, coordinator: Option<&str>) -> Speaker {
+        Speaker {
+            uid: format!("RINCON_{}", name),
+            coordinator_uid: coordinator.map(|c| format!("RINCON_{}", c)),
+            name: name.to_string(),
+            alias: None,
+            ip: "0.0.0.0".to_string(),
+            volume: 25,
+            muted: false,
+            state: "PLAYING".to_string(),
+            group_coordinator: coordinator.map(|s| s.to_string()),
+            track: None,
+            bass: None,
+            treble: None,
+            loudness: None,
+            play_mode: None,
+            cross_fade: None,
+            model: None,
+            bonded_to: None,
+            bond: None,
+        }
+    }
+
+    #[test]
+    fn test_logical_speakers_fold_bonded_members_into_their_primary() {
+        let mut app = App::new();
+        let mut primary = make_speaker("Living Room", None);
+        primary.bond = Some("stereo".into());
+        let mut right = make_speaker("Living Room R", None);
+        right.bonded_to = Some("RINCON_Living Room".into());
+        let mut orphan = make_speaker("Sub", None);
+        orphan.bonded_to = Some("RINCON_Gone".into());
+        app.set_speakers(vec![right, primary, make_speaker("Kitchen", None), orphan]);
+        let names: Vec<String> = app.speakers.iter().map(|s| s.label()).collect();
+        assert_eq!(names, vec!["Kitchen", "Living Room (stereo)", "Sub"]);
+    }
+
+    #[test]
+    fn test_is_grouped_when_follower_present() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("Family Room", Some("Family Room")),
+            make_speaker("cthulhu", Some("Family Room")),
+        ];
+        assert!(app.is_grouped());
+    }
+
+    #[test]
+    fn test_grouping_state_none_partial_full() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("Den", Some("Den")),
+            make_speaker("Kitchen", Some("Kitchen")),
+            make_speaker("Office", None),
+        ];
+        assert_eq!(app.grouping_state(), GroupingState::None);
+        app.speakers[1].coordinator_uid = Some("RINCON_Den".into());
+        assert_eq!(app.grouping_state(), GroupingState::Partial);
+        app.speakers[2].coordinator_uid = Some("RINCON_Den".into());
+        assert_eq!(app.grouping_state(), GroupingState::Full);
+
+        // Only the configured set counts; a do-not-disturb room is left out of it
+        app.speakers[2].coordinator_uid = None;
+        app.group_set = vec!["Den".into(), "Kitchen".into()];
+        assert_eq!(app.grouping_state(), GroupingState::Full);
+        app.group_set.clear();
+        app.do_not_disturb = vec!["Office".into()];
+        assert_eq!(app.grouping_state(), GroupingState::Full);
+    }
+
+    #[test]
+    fn test_grouping_state_with_two_groups_is_partial() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("Den", Some("Den")),
+            make_speaker("Kitchen", Some("Den")),
+            make_speaker("Office", Some("Office")),
+            make_speaker("Patio", Some("Office")),
+        ];
+        assert_eq!(app.grouping_state(), GroupingState::Partial);
+        // A lone online room can't be "fully" grouped
+        app.speakers.truncate(1);
+        assert_eq!(app.grouping_state(), GroupingState::None);
+    }
+
+    #[test]
+    fn test_is_not_grouped_when_all_self_coordinating() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("Family Room", Some("Family Room")),
+            make_speaker("cthulhu", Some("cthulhu")),
+        ];
+        assert!(!app.is_grouped());
+    }
+
+    #[test]
+    fn test_is_not_grouped_when_coordinators_null() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("Family Room", None),
+            make_speaker("cthulhu", None),
+        ];
+        assert!(!app.is_grouped());
+    }
+
+    #[test]
+    fn test_volume_input_starts_none() {
+        let app = App::new();
+        assert!(app.volume_input.is_none());
+    }
+
+    #[test]
+    fn test_volume_input_can_be_set() {
+        let mut app = App::new();
+        app.volume_input = Some(String::from("42"));
+        assert_eq!(app.volume_input.as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_active_status_returns_empty_when_nothing_set() {
+        let app = App::new();
+        assert_eq!(app.active_status(), "");
+    }
+
+    #[test]
+    fn test_set_status_returns_message_immediately() {
+        let mut app = App::new();
+        app.set_status("The gates of Moria are sealed.", Linger::Long);
+        assert_eq!(app.active_status(), "The gates of Moria are sealed.");
+    }
+
+    #[test]
+    fn test_active_status_returns_empty_when_expired() {
+        let (mut app, clock) = mock_app();
+        app.set_status("old message", Linger::Long);
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(app.active_status(), "");
+    }
+
+    #[test]
     fn test_active_status_returns_sleep_countdown() {
+        let (mut app, _clock) = mock_app();
+        app.sleep_until = Some(app.clock.now() + Duration::from_secs(90));
+        assert_eq!(app.active_status(), "Sleep: 1:30 remaining");
+    }
+
+    #[test]
+    fn test_active_status_falls_back_to_sleep_after_message_expires() {
+        let (mut app, clock) = mock_app();
+        app.sleep_until = Some(app.clock.now() + Duration::from_secs(600));
+        app.set_status("The fellowship is assembled.", Linger::Normal);
+        assert_eq!(app.active_status(), "The fellowship is assembled.");
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(app.active_status(), "Sleep: 9:57 remaining");
+    }
+
+    #[test]
+    fn test_sticky_status_outlasts_the_sleep_countdown_until_replaced() {
+        let (mut app, clock) = mock_app();
+        app.sleep_until = Some(app.clock.now() + Duration::from_secs(7200));
+        app.set_status("The gates are shut.", Linger::Sticky);
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(app.active_status(), "The gates are shut.");
+        app.set_status("The gates stand open again.", Linger::Brief);
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(app.active_status(), "Sleep: 59:58 remaining");
+    }
+
+    #[test]
+    fn test_status_linger_scales_named_lengths_but_not_exact_ones() {
+        let (mut app, clock) = mock_app();
+        app.apply_config(&Config { status_linger: 2.0, ..Config::default() });
+        app.set_status("Refreshed.", Linger::Brief);
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(app.active_status(), "Refreshed.");
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(app.active_status(), "");
+        app.set_status("Press q again to quit.", Linger::Exactly(QUIT_CONFIRM));
+        clock.advance(QUIT_CONFIRM);
+        assert_eq!(app.active_status(), "");
+        assert!(Linger::Error.duration(1.0) > Linger::Normal.duration(1.0));
+    }
+
+    fn grouped_den_kitchen_office() -> (App, Rc<MockClock>) {
+        let (mut app, clock) = mock_app();
+        let mut den = make_speaker("Den", Some("Den"));
+        den.volume = 30;
+        let mut kitchen = make_speaker("Kitchen", Some("Den"));
+        kitchen.volume = 60;
+        let mut office = make_speaker("Office", None);
+        office.volume = 40;
+        app.speakers = vec![den, kitchen, office];
+        (app, clock)
+    }
+
+    #[test]
+    fn test_group_sleep_fades_then_pauses_only_that_group() {
+        let (mut app, clock) = grouped_den_kitchen_office();
+        app.sleep_timers.push(SleepTimer {
+            target: Some("RINCON_Den".to_string()),
+            label: "Den".to_string(),
+            until: app.clock.now() + Duration::from_secs(60),
+            fade: true,
+            faded_from: vec![],
+        });
+        assert!(app.tick_sleep_timers().is_empty(), "no fade before the last 30s");
+
+        clock.advance(Duration::from_secs(45)); // 15s left: half volume
+        let actions = app.tick_sleep_timers();
+        assert_eq!(actions, vec![
+            SleepAction::SetVolume("RINCON_Den".to_string(), 15),
+            SleepAction::SetVolume("RINCON_Kitchen".to_string(), 30),
+        ]);
+        assert!(app.tick_sleep_timers().is_empty(), "unchanged level isn't resent");
+
+        clock.advance(Duration::from_secs(15));
+        let actions = app.tick_sleep_timers();
+        assert_eq!(actions, vec![
+            SleepAction::SetVolume("RINCON_Den".to_string(), 0),
+            SleepAction::SetVolume("RINCON_Kitchen".to_string(), 0),
+            SleepAction::Pause("RINCON_Den".to_string()),
+            SleepAction::SetVolume("RINCON_Den".to_string(), 30),
+            SleepAction::SetVolume("RINCON_Kitchen".to_string(), 60),
+        ]);
+        assert!(app.sleep_timers.is_empty());
+        let volumes: Vec<u8> = app.speakers.iter().map(|s| s.volume).collect();
+        assert_eq!(volumes, vec![30, 60, 40]);
+    }
+
+    #[test]
+    fn test_sleep_without_target_pauses_everyone() {
+        let (mut app, clock) = grouped_den_kitchen_office();
+        app.sleep_timers.push(SleepTimer {
+            target: None,
+            label: "All".to_string(),
+            until: app.clock.now() + Duration::from_secs(10),
+            fade: false,
+            faded_from: vec![],
+        });
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(app.tick_sleep_timers().len(), 3);
+    }
+
+    #[test]
+    fn test_cancel_sleep_restores_mid_fade_volumes() {
+        let (mut app, clock) = grouped_den_kitchen_office();
+        app.sleep_timers.push(SleepTimer {
+            target: Some("RINCON_Office".to_string()),
+            label: "Office".to_string(),
+            until: app.clock.now() + Duration::from_secs(20),
+            fade: true,
+            faded_from: vec![],
+        });
+        clock.advance(Duration::from_secs(10));
+        app.tick_sleep_timers();
+        assert_eq!(app.speakers[2].volume, 13);
+        assert_eq!(app.cancel_sleep(), vec![SleepAction::SetVolume("RINCON_Office".to_string(), 40)]);
+        assert_eq!(app.speakers[2].volume, 40);
+        assert!(app.sleep_timers.is_empty());
+    }
+
+    #[test]
+    fn test_active_status_shows_group_sleep_countdown() {
+        let (mut app, _clock) = mock_app();
+        app.sleep_timers.push(SleepTimer {
+            target: Some("RINCON_Den".to_string()),
+            label: "Den".to_string(),
+            until: app.clock.now() + Duration::from_secs(75),
+            fade: false,
+            faded_from: vec![],
+        });
+        assert_eq!(app.active_status(), "Sleep (Den): 1:15 remaining");
+    }
+
+    #[test]
+    fn test_coordinators_returns_only_coordinators() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("cthulhu", Some("cthulhu")),
+            make_speaker("family", Some("cthulhu")),
+            make_speaker("hermit", None),
+        ];
+        let coords = app.coordinators();
+        assert_eq!(coords.len(), 1);
+        assert_eq!(coords[0].name, "cthulhu");
+    }
+
+    #[test]
+    fn test_solo_speakers_returns_ungrouped() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("cthulhu", Some("cthulhu")),
+            make_speaker("hermit", None),
+        ];
+        let solos = app.solo_speakers();
+        assert_eq!(solos.len(), 1);
+        assert_eq!(solos[0].name, "hermit");
+    }
+
+    #[test]
+    fn test_group_members_of_returns_all_members() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("cthulhu", Some("cthulhu")),
+            make_speaker("family", Some("cthulhu")),
+            make_speaker("hermit", None),
+        ];
+        let members = app.group_members_of(&app.speakers[0]);
+        assert_eq!(members.len(), 2);
+    }
+
+    #[test]
+    fn test_group_members_of_returns_empty_for_unknown_coordinator() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("cthulhu", Some("cthulhu")),
+        ];
+        let nobody = make_speaker("nobody", None);
+        let members = app.group_members_of(&nobody);
+        assert!(members.is_empty());
+    }
+
+    #[test]
+    fn test_playing_entities_deduplicates_groups() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("cthulhu", Some("cthulhu")),  // coordinator
+            make_speaker("family", Some("cthulhu")),   // follower — skip
+            make_speaker("hermit", None),              // solo
+        ];
+        let entities = app.playing_entities();
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities[0].name, "cthulhu");
+        assert_eq!(entities[1].name, "hermit");
+    }
+
+    #[test]
+    fn test_solo_speakers_empty_when_all_grouped() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("cthulhu", Some("cthulhu")),
+            make_speaker("family", Some("cthulhu")),
+        ];
+        assert!(app.solo_speakers().is_empty());
+    }
+
+    #[test]
+    fn test_playing_entities_empty_speakers() {
+        let app = App::new();
+        assert!(app.playing_entities().is_empty());
+    }
+
+    #[test]
+    fn test_playing_entities_all_followers_returns_empty() {
+        let mut app = App::new();
+        // Both speakers point to a coordinator "ghost" that isn't in the list
+        app.speakers = vec![
+            make_speaker("follower1", Some("ghost")),
+            make_speaker("follower2", Some("ghost")),
+        ];
+        assert!(app.playing_entities().is_empty());
+    }
+
+    #[test]
+    fn test_toggle_source() {
+        let mut app = App::new();
+        assert_eq!(app.source_mode, SourceMode::Playlists);
+        app.toggle_source();
+        assert_eq!(app.source_mode, SourceMode::Podcasts);
+        app.toggle_source();
+        assert_eq!(app.source_mode, SourceMode::Playlists);
+    }
+
+    #[test]
+    fn test_toggle_source_resets_drill() {
+        let mut app = App::new();
+        app.podcast_drill = true;
+        app.toggle_source();
+        assert!(!app.podcast_drill);
+    }
+
+    #[test]
+    fn test_podcast_navigation() {
+        let mut app = App::new();
+        app.source_mode = SourceMode::Podcasts;
+        app.active_panel = Panel::Playlists;
+        app.podcasts = vec![
+            crate::api::Podcast { alias: "a".into(), name: "A".into(), url: "".into(), unplayed: 0 },
+            crate::api::Podcast { alias: "b".into(), name: "B".into(), url: "".into(), unplayed: 0 },
+        ];
+        app.next_in_list();
+        assert_eq!(app.podcast_index, 1);
+        app.next_in_list();
+        assert_eq!(app.podcast_index, 0);
+    }
+
+    fn make_playlist(alias: &str) -> Playlist {
+        Playlist { alias: alias.to_string(), favorite_name: alias.to_string(), source: PlaylistSource::Configured }
+    }
+
+    #[test]
+    fn test_zone_resolves_to_the_group_holding_most_of_its_rooms() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("Den", Some("Kitchen")),
+            make_speaker("Kitchen", Some("Kitchen")),
+            make_speaker("Office", None),
+        ];
+        app.zones = HashMap::from([
+            ("Downstairs".to_string(), vec!["Office".to_string(), "Den".to_string(), "kitchen".to_string()]),
+            ("Attic".to_string(), vec!["Loft".to_string()]),
+        ]);
+        assert_eq!(app.zone_coordinator("downstairs").map(|s| s.name.as_str()), Some("Kitchen"));
+        assert!(app.zone_coordinator("Attic").is_none(), "nobody online");
+        assert!(app.zone_coordinator("Garden").is_none());
+    }
+
+    #[test]
+    fn test_play_target_splits_zone_or_room_from_playlist() {
+        let mut app = App::new();
+        app.speakers = vec![make_speaker("Den", Some("Den")), make_speaker("Kitchen", Some("Den")), make_speaker("Office", None)];
+        app.playlists = vec![make_playlist("altwave"), make_playlist("office hours")];
+        app.zones = HashMap::from([("Office".to_string(), vec!["Kitchen".to_string()])]);
+        let target = |line| app.play_target(line);
+        assert_eq!(target("Kitchen altwave"), Some(("RINCON_Den".into(), "Kitchen".into(), "altwave".into())));
+        // A zone sharing a room's name wins
+        assert_eq!(target("office altwave"), Some(("RINCON_Den".into(), "office".into(), "altwave".into())));
+        // The whole line naming a playlist is never split
+        assert_eq!(target("office hours"), None);
+        assert_eq!(target("altwave"), None);
+        assert_eq!(target("Garden altwave"), None);
+    }
+
+    #[test]
+    fn test_swap_speaker_flips_between_the_last_two_rooms() {
+        let mut app = App::new();
+        app.set_speakers(vec![make_speaker("Den", None), make_speaker("Kitchen", None), make_speaker("Office", None)]);
+        app.note_selection();
+        assert_eq!(app.swap_speaker(), None, "nowhere to go back to yet");
+        app.next_in_list();
+        app.next_in_list();
+        app.note_selection();
+        assert_eq!(app.swap_speaker().as_deref(), Some("Den"));
+        assert_eq!(app.swap_speaker().as_deref(), Some("Office"));
+        // A re-sort keeps the pairing; a vanished room is forgotten
+        app.set_speakers(vec![make_speaker("Office", None), make_speaker("Kitchen", None)]);
+        assert_eq!(app.speaker_name().as_deref(), Some("Office"));
+        assert_eq!(app.swap_speaker(), None);
+        assert!(app.prev_speaker.is_none());
+    }
+
+    #[test]
+    fn test_speaker_navigation_wraps_by_default() {
+        let mut app = App::new();
+        app.speakers = vec![make_speaker("a", None), make_speaker("b", None)];
+        app.speaker_index = 1;
+        app.next_in_list();
+        assert_eq!(app.speaker_index, 0);
+        app.prev_in_list();
+        assert_eq!(app.speaker_index, 1);
+    }
+
+    #[test]
+    fn test_speaker_navigation_clamps_when_wrap_disabled() {
+        let mut app = App::new();
+        app.wrap_navigation = false;
+        app.speakers = vec![make_speaker("a", None), make_speaker("b", None)];
+        app.speaker_index = 1;
+        app.next_in_list();
+        assert_eq!(app.speaker_index, 1);
+        app.speaker_index = 0;
+        app.prev_in_list();
+        assert_eq!(app.speaker_index, 0);
+    }
+
+    #[test]
+    fn test_playlist_navigation_wraps_by_default() {
+        let mut app = App::new();
+        app.active_panel = Panel::Playlists;
+        app.playlists = vec![make_playlist("a"), make_playlist("b"), make_playlist("c")];
+        app.prev_in_list();
+        assert_eq!(app.playlist_index, 2);
+        app.next_in_list();
+        assert_eq!(app.playlist_index, 0);
+    }
+
+    #[test]
+    fn test_playlist_navigation_clamps_when_wrap_disabled() {
+        let mut app = App::new();
+        app.wrap_navigation = false;
+        app.active_panel = Panel::Playlists;
+        app.playlists = vec![make_playlist("a"), make_playlist("b"), make_playlist("c")];
+        app.prev_in_list();
+        assert_eq!(app.playlist_index, 0);
+        app.playlist_index = 2;
+        app.next_in_list();
+        assert_eq!(app.playlist_index, 2);
+    }
+
+    #[test]
+    fn test_apply_config_sets_wrap_navigation() {
+        let mut app = App::new();
+        let config = Config { wrap_navigation: false, ..Config::default() };
+        app.apply_config(&config);
+        assert!(!app.wrap_navigation);
+    }
+
+    #[test]
+    fn test_config_errors_are_logged_once_and_shown() {
+        let mut app = App::new();
+        let config = Config { config_errors: vec!["line 4: unknown setting prot (did you mean port?)".into()], ..Config::default() };
+        app.apply_config(&config);
+        assert_eq!(app.active_status(), "A flawed rune in config.yaml: line 4: unknown setting prot (did you mean port?) (see L)");
+        assert!(app.event_log.iter().any(|e| e.text == "config.yaml: line 4: unknown setting prot (did you mean port?)"));
+        let logged = app.event_log.len();
+        app.apply_config(&config);
+        assert_eq!(app.event_log.len(), logged, "an unchanged reload stays quiet");
+    }
+
+    #[test]
+    fn test_begin_solo_mutes_all_but_selected() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("cthulhu", None),
+            make_speaker("family", None),
+            make_speaker("study", None),
+        ];
+        app.speaker_index = 1;
+        let changes = app.begin_solo();
+        assert_eq!(changes, vec![
+            ("RINCON_cthulhu".to_string(), true),
+            ("RINCON_family".to_string(), false),
+            ("RINCON_study".to_string(), true),
+        ]);
+        assert!(app.speakers[0].muted);
+        assert!(!app.speakers[1].muted);
+        assert!(app.solo_restore.is_some());
+    }
+
+    #[test]
+    fn test_end_solo_restores_prior_mute_state() {
+        let mut app = App::new();
+        app.speakers = vec![make_speaker("cthulhu", None), make_speaker("family", None)];
+        app.speakers[1].muted = true; // was already muted before solo
+        app.speaker_index = 0;
+        app.begin_solo();
+        let restore = app.end_solo();
+        assert_eq!(restore, vec![
+            ("RINCON_cthulhu".to_string(), false),
+            ("RINCON_family".to_string(), true),
+        ]);
+        assert!(!app.speakers[0].muted);
+        assert!(app.speakers[1].muted);
+        assert!(app.solo_restore.is_none());
+    }
+
+    fn make_track(title: &str) -> crate::api::Track {
+        crate::api::Track {
+            title: title.to_string(),
+            artist: "Ratboys".to_string(),
+            album: "Happy Birthday, Ratboy".to_string(),
+            duration: 231,
+            position: 83,
+            source: String::new(),
+            quality: String::new(),
+            rating: None,
+        }
+    }
+
+    #[test]
+    fn test_osd_shows_then_expires() {
+        let (mut app, clock) = mock_app();
+        app.show_osd(OsdKind::Volume, 40);
+        assert_eq!(app.osd.map(|(k, v, _)| (k, v)), Some((OsdKind::Volume, 40)));
+        clock.advance(Duration::from_millis(600));
+        app.show_osd(OsdKind::Volume, 45);
+        clock.advance(Duration::from_millis(600));
+        app.expire_osd();
+        assert!(app.osd.is_some(), "refreshing the gauge restarts its timer");
+        clock.advance(OSD_DURATION);
+        app.expire_osd();
+        assert!(app.osd.is_none());
+    }
+
+    #[test]
+    fn test_osd_disabled_by_config() {
+        let mut app = App::new();
+        app.apply_config(&Config { volume_osd: false, ..Config::default() });
+        app.show_osd(OsdKind::Volume, 40);
+        assert!(app.osd.is_none());
+    }
+
+    #[test]
+    fn test_find_speaker_prefers_exact_then_prefix_then_substring() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("Kitchen Two", None),
+            make_speaker("Kitchen", None),
+            make_speaker("Den", None),
+        ];
+        assert_eq!(app.find_speaker("kitchen").unwrap().name, "Kitchen");
+        assert_eq!(app.find_speaker("kit").unwrap().name, "Kitchen Two");
+        assert_eq!(app.find_speaker("EN").unwrap().name, "Kitchen Two");
+        assert_eq!(app.find_speaker("de").unwrap().name, "Den");
+        assert!(app.find_speaker("attic").is_none());
+    }
+
+    #[test]
+    fn test_find_playlist_falls_back_to_subsequence() {
+        let mut app = App::new();
+        app.playlists = vec![
+            Playlist { alias: "jazz".into(), favorite_name: "Jazz Classics".into(), source: PlaylistSource::Configured },
+            Playlist { alias: "jzcl".into(), favorite_name: "Jazz Club".into(), source: PlaylistSource::Configured },
+            Playlist { alias: "lofi".into(), favorite_name: "Lo-Fi Beats".into(), source: PlaylistSource::Configured },
+        ];
+        assert_eq!(app.find_playlist("JZCL").unwrap().alias, "jzcl", "exact beats subsequence");
+        assert_eq!(app.find_playlist("beat").unwrap().alias, "lofi");
+        assert_eq!(app.find_playlist("jzcla").unwrap().alias, "jazz");
+        assert_eq!(app.find_playlist("lfb").unwrap().alias, "lofi");
+        assert!(app.find_playlist("xyz").is_none());
+    }
+
+    #[test]
+    fn test_coordinator_of_member_and_solo() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("Den", Some("Den")),
+            make_speaker("Kitchen", Some("Den")),
+            make_speaker("Office", None),
+        ];
+        assert_eq!(app.coordinator_of(&app.speakers[1]).name, "Den");
+        assert_eq!(app.coordinator_of(&app.speakers[2]).name, "Office");
+    }
+
+    #[test]
+    fn test_set_group_state_updates_members() {
+        let mut app = App::new();
+        app.speakers = vec![
+            make_speaker("Den", Some("Den")),
+            make_speaker("Kitchen", Some("Den")),
+            make_speaker("Office", None),
+        ];
+        app.set_group_state("RINCON_Den", "PAUSED_PLAYBACK");
+        let states: Vec<&str> = app.speakers.iter().map(|s| s.state.as_str()).collect();
+        assert_eq!(states, vec!["PAUSED_PLAYBACK", "PAUSED_PLAYBACK", "PLAYING"]);
+    }
+
+    #[test]
+    fn test_title_text_shows_track_and_percent() {
+        let mut app = App::new();
+        assert_eq!(app.title_text(), DEFAULT_TITLE);
+        let mut sp = make_speaker("Kitchen", None);
+        sp.track = Some(make_track("Elvis in the Freezer"));
+        app.speakers = vec![sp];
+        assert_eq!(app.title_text(), "Elvis in the Freezer — Ratboys [35%]");
+        app.speakers[0].track.as_mut().unwrap().duration = 0;
+        assert_eq!(app.title_text(), "Elvis in the Freezer — Ratboys");
+    }
+
+    #[test]
+    fn test_ticker_shows_selected_track() {
+        let mut app = App::new();
+        let mut sp = make_speaker("cthulhu", None);
+        sp.track = Some(make_track("Penny in the Lake"));
+        app.speakers = vec![sp];
+        assert_eq!(
+            app.ticker().as_deref(),
+            Some("♫ Penny in the Lake — Ratboys — Happy Birthday, Ratboy"),
+        );
+    }
+
+    #[test]
+    fn test_ticker_hidden_when_now_playing_focused_or_status_active() {
+        let mut app = App::new();
+        let mut sp = make_speaker("cthulhu", None);
+        sp.track = Some(make_track("Penny in the Lake"));
+        app.speakers = vec![sp];
+        app.active_panel = Panel::NowPlaying;
+        assert!(app.ticker().is_none());
+        app.active_panel = Panel::Speakers;
+        app.set_status("The fellowship is assembled.", Linger::Long);
+        assert!(app.ticker().is_none());
+    }
+
+    #[test]
+    fn test_ticker_none_without_track() {
+        let mut app = App::new();
+        app.speakers = vec![make_speaker("cthulhu", None)];
+        assert!(app.ticker().is_none());
+    }
+
+    #[test]
+    fn test_play_cooldown_ignores_repeats_within_window() {
+        let (mut app, clock) = mock_app();
+        assert!(app.try_begin_play("cthulhu"));
+        clock.advance(Duration::from_millis(200));
+        assert!(!app.try_begin_play("cthulhu"));
+        clock.advance(Duration::from_millis(299));
+        assert!(!app.try_begin_play("cthulhu"));
+        clock.advance(Duration::from_millis(1));
+        assert!(app.try_begin_play("cthulhu"));
+    }
+
+    #[test]
+    fn test_play_cooldown_is_per_speaker() {
+        let (mut app, clock) = mock_app();
+        assert!(app.try_begin_play("cthulhu"));
+        clock.advance(Duration::from_millis(100));
+        assert!(app.try_begin_play("family"));
+    }
+
+    #[test]
+    fn test_duplicate_room_names_are_told_apart_by_uid() {
+        let mut app = App::new();
+        let mut upstairs = make_speaker("Kitchen", Some("Kitchen"));
+        upstairs.uid = "RINCON_UP".to_string();
+        upstairs.coordinator_uid = Some("RINCON_UP".to_string());
+        let mut downstairs = make_speaker("Kitchen", None);
+        downstairs.uid = "RINCON_DOWN".to_string();
+        app.speakers = vec![upstairs, downstairs];
+
+        app.speaker_index = 1;
+        assert_eq!(app.speaker_id().as_deref(), Some("RINCON_DOWN"));
+        assert_eq!(app.speaker_name().as_deref(), Some("Kitchen"));
+
+        let members = app.group_members_of(&app.speakers[0]);
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].uid, "RINCON_UP");
+        assert_eq!(app.solo_speakers()[0].uid, "RINCON_DOWN");
+        assert_eq!(app.index_of(&app.speakers[1].clone()), Some(1));
+    }
+
+    #[test]
+    fn test_speaker_id_falls_back_to_alias_without_uid() {
+        let mut app = App::new();
+        let mut sp = make_speaker("Family Room", None);
+        sp.uid = String::new();
+        sp.alias = Some("family".to_string());
+        app.speakers = vec![sp];
+        assert_eq!(app.speaker_id().as_deref(), Some("family"));
+    }
+
+    fn names(app: &App) -> Vec<&str> {
+        app.speakers.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_set_speakers_sorts_by_name_and_keeps_selection() {
+        let mut app = App::new();
+        app.set_speakers(vec![make_speaker("study", None), make_speaker("cthulhu", None)]);
+        assert_eq!(names(&app), vec!["cthulhu", "study"]);
+        app.speaker_index = 1; // study
+
+        // Next poll arrives in a different order with a new room
+        app.set_speakers(vec![
+            make_speaker("study", None),
+            make_speaker("bedroom", None),
+            make_speaker("cthulhu", None),
+        ]);
+        assert_eq!(names(&app), vec!["bedroom", "cthulhu", "study"]);
+        assert_eq!(app.selected_speaker().unwrap().name, "study");
+    }
+
+    #[test]
+    fn test_apply_polled_drops_snapshot_older_than_manual_refresh() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        let poll_started = clock.now();
+        clock.advance(Duration::from_millis(500));
+        app.set_speakers(vec![make_speaker("study", None)]);
+        app.refreshed_at = Some(clock.now());
+
+        app.apply_polled(poll_started, vec![]);
+        assert_eq!(names(&app), vec!["study"]);
+
+        clock.advance(Duration::from_secs(2));
+        app.apply_polled(clock.now(), vec![make_speaker("attic", None)]);
+        assert_eq!(names(&app), vec!["attic"]);
+    }
+
+    fn all_changes() -> Vec<String> {
+        vec!["playback".into(), "volume".into(), "grouping".into()]
+    }
+
+    #[test]
+    fn test_describe_changes_playback_volume_and_grouping() {
+        let old = vec![make_speaker("Study", None), make_speaker("Den", None)];
+        let mut new = old.clone();
+        new[0].state = "PAUSED_PLAYBACK".into();
+        new[1].volume = 40;
+        new[1].coordinator_uid = Some("RINCON_Study".into());
+        assert_eq!(describe_changes(&old, &new, &all_changes()), vec![
+            "Study paused.",
+            "Den volume 25 → 40.",
+            "Den joined Study's fellowship.",
+        ]);
+        assert_eq!(describe_changes(&new, &old, &all_changes())[2], "Den left the fellowship.");
+    }
+
+    #[test]
+    fn test_describe_resync_lists_rooms_found_lost_and_regrouped() {
+        let old = vec![make_speaker("Study", None), make_speaker("Den", None), make_speaker("Attic", None)];
+        let mut new = vec![make_speaker("Study", None), make_speaker("Den", Some("Study")), make_speaker("Porch", None)];
+        new[0].volume = 60;
+        assert_eq!(describe_resync(&old, &new), vec![
+            "Porch answers the call.",
+            "Attic has passed into the West.",
+            "Den joined Study's fellowship.",
+        ]);
+        assert!(describe_resync(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_describe_changes_respects_categories() {
+        let old = vec![make_speaker("Study", None)];
+        let mut new = old.clone();
+        new[0].volume = 60;
+        assert!(describe_changes(&old, &new, &["playback".into()]).is_empty());
+    }
+
+    #[test]
+    fn test_poll_notifies_external_change_with_debounce() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        let mut study = make_speaker("Study", None);
+        study.state = "STOPPED".into();
+        app.set_speakers(vec![study.clone()]);
+
+        study.state = "PLAYING".into();
+        app.apply_polled(clock.now(), vec![study.clone()]);
+        assert_eq!(app.active_status(), "Study started playing.");
+
+        clock.advance(Duration::from_secs(4));
+        study.volume = 50;
+        app.apply_polled(clock.now(), vec![study.clone()]);
+        assert_eq!(app.active_status(), "", "debounced within CHANGE_DEBOUNCE");
+    }
+
+    #[test]
+    fn test_follow_mode_selects_what_starts_playing() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        let mut rooms = vec![make_speaker("Den", None), make_speaker("Study", None)];
+        for sp in &mut rooms {
+            sp.state = "STOPPED".into();
+        }
+        app.set_speakers(rooms.clone());
+        app.follow_mode = true;
+
+        rooms[1].state = "PLAYING".into();
+        app.apply_polled(clock.now(), rooms.clone());
+        assert_eq!(app.speaker_name().as_deref(), Some("Study"));
+        assert_eq!(app.active_status(), "Following the music to Study.");
+
+        // A room picked by hand holds for FOLLOW_GRACE
+        app.prev_in_list();
+        rooms[1].state = "STOPPED".into();
+        app.apply_polled(clock.now(), rooms.clone());
+        rooms[1].state = "PLAYING".into();
+        app.apply_polled(clock.now(), rooms.clone());
+        assert_eq!(app.speaker_name().as_deref(), Some("Den"));
+
+        clock.advance(FOLLOW_GRACE);
+        rooms[1].state = "STOPPED".into();
+        app.apply_polled(clock.now(), rooms.clone());
+        rooms[1].state = "PLAYING".into();
+        app.apply_polled(clock.now(), rooms.clone());
+        assert_eq!(app.speaker_name().as_deref(), Some("Study"));
+    }
+
+    #[test]
+    fn test_seek_target_at_maps_column_to_position() {
+        let app = App::new();
+        app.seek_targets.borrow_mut().push(SeekTarget {
+            area: ratatui::layout::Rect { x: 10, y: 5, width: 20, height: 1 },
+            speaker: "RINCON_Den".into(),
+            duration: 200,
+        });
+        assert_eq!(app.seek_target_at(10, 5), Some(("RINCON_Den".into(), 0)));
+        assert_eq!(app.seek_target_at(20, 5), Some(("RINCON_Den".into(), 100)));
+        assert_eq!(app.seek_target_at(30, 5), None);
+        assert_eq!(app.seek_target_at(20, 6), None);
+    }
+
+    #[test]
+    fn test_seek_target_at_ignores_streams() {
+        let app = App::new();
+        app.seek_targets.borrow_mut().push(SeekTarget {
+            area: ratatui::layout::Rect { x: 0, y: 0, width: 20, height: 1 },
+            speaker: "RINCON_Den".into(),
+            duration: 0,
+        });
+        assert_eq!(app.seek_target_at(5, 0), None);
+    }
+
+    #[test]
+    fn test_session_restores_by_identity_and_skips_vanished_entries() {
         let mut app = App::new();
-        app.sleep_until = Some(std::time::Instant::now() + std::time::Duration::from_secs(90));
-        let status = app.active_status();
-        assert!(status.starts_with("Sleep: 1:"), "Expected 'Sleep: 1:xx remaining', got: {}", status);
+        app.set_speakers(vec![make_speaker("Den", None), make_speaker("Kitchen", None)]);
+        app.set_playlists(vec![
+            Playlist { alias: "altwave".into(), favorite_name: "Alt Wave".into(), source: PlaylistSource::Configured },
+            Playlist { alias: "zen".into(), favorite_name: "Zen".into(), source: PlaylistSource::Configured },
+        ]);
+        let session = crate::prefs::Session {
+            panel: Some(Panel::Playlists),
+            speaker: Some("RINCON_Kitchen".into()),
+            playlist: Some("zen".into()),
+        };
+        app.restore_session(&session, false);
+        assert_eq!((app.active_panel, app.speaker_index, app.playlist_index), (Panel::Playlists, 0, 1));
+        app.restore_session(&session, true);
+        assert_eq!(app.speaker_index, 1);
+        assert_eq!(app.session(), session);
+
+        app.restore_session(&crate::prefs::Session { speaker: Some("RINCON_Attic".into()), ..Default::default() }, true);
+        assert_eq!(app.speaker_index, 1);
     }
 
     #[test]
-    fn test_coordinators_returns_only_coordinators() {
+    fn test_wheel_volume_steps_at_once_and_throttles_sends() {
+        let (mut app, clock) = mock_app();
+        app.set_speakers(vec![make_speaker("Den", None)]);
+        app.speakers[0].volume = 99;
+        assert_eq!(app.wheel_volume("RINCON_Den", WHEEL_STEP), Some(100), "capped at 100");
+        assert_eq!(app.wheel_volume("RINCON_Den", -WHEEL_STEP), None);
+        assert_eq!(app.wheel_volume("RINCON_Den", -WHEEL_STEP), None);
+        assert_eq!(app.speakers[0].volume, 96, "shown before it's sent");
+        assert!(app.wheel_volumes_due().is_empty());
+
+        clock.advance(WHEEL_THROTTLE);
+        assert_eq!(app.wheel_volumes_due(), vec![("RINCON_Den".to_string(), 96)]);
+        clock.advance(WHEEL_THROTTLE);
+        assert!(app.wheel_volumes_due().is_empty());
+        assert_eq!(app.wheel_volume("RINCON_Den", WHEEL_STEP), Some(98), "a quiet wheel sends at once");
+        assert_eq!(app.wheel_volume("RINCON_Nowhere", WHEEL_STEP), None);
+    }
+
+    #[test]
+    fn test_remember_sleep_paused_keeps_playing_coordinators_only() {
         let mut app = App::new();
         app.speakers = vec![
-            make_speaker("cthulhu", Some("cthulhu")),
-            make_speaker("family", Some("cthulhu")),
-            make_speaker("hermit", None),
+            make_speaker("Den", Some("Den")),
+            make_speaker("Kitchen", Some("Den")),
+            make_speaker("Study", None),
         ];
-        let coords = app.coordinators();
-        assert_eq!(coords.len(), 1);
-        assert_eq!(coords[0].name, "cthulhu");
+        app.speakers[2].state = "PAUSED_PLAYBACK".into();
+        let ids: Vec<String> = app.speakers.iter().map(|s| s.id()).collect();
+        app.remember_sleep_paused(&ids);
+        assert_eq!(app.sleep_paused, vec!["RINCON_Den"]);
+        assert!(app.wake_from_sleep("RINCON_Den"));
+        assert!(!app.wake_from_sleep("RINCON_Den"), "a second resume is a manual one");
     }
 
     #[test]
-    fn test_solo_speakers_returns_ungrouped() {
+    fn test_expiring_timer_records_what_it_paused() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        app.speakers = vec![make_speaker("Den", None)];
+        app.sleep_timers.push(SleepTimer {
+            target: Some("RINCON_Den".into()),
+            label: "Den".into(),
+            until: clock.now() + Duration::from_secs(60),
+            fade: false,
+            faded_from: vec![],
+        });
+        clock.advance(Duration::from_secs(61));
+        app.tick_sleep_timers();
+        assert_eq!(app.sleep_paused, vec!["RINCON_Den"]);
+    }
+
+    #[test]
+    fn test_do_not_disturb_skips_speaker_in_all_targets() {
         let mut app = App::new();
-        app.speakers = vec![
-            make_speaker("cthulhu", Some("cthulhu")),
-            make_speaker("hermit", None),
-        ];
-        let solos = app.solo_speakers();
-        assert_eq!(solos.len(), 1);
-        assert_eq!(solos[0].name, "hermit");
+        app.speakers = vec![make_speaker("Den", None), make_speaker("Nursery", None)];
+        assert_eq!(app.group_targets(), None);
+        app.do_not_disturb = vec!["nursery".into()];
+        assert!(app.is_excluded("Nursery"));
+        assert_eq!(app.all_targets(), vec!["RINCON_Den"]);
+        assert_eq!(app.group_targets(), Some(vec!["RINCON_Den".to_string()]));
     }
 
     #[test]
-    fn test_group_members_of_returns_all_members() {
+    fn test_toggle_do_not_disturb_survives_config_reload() {
         let mut app = App::new();
-        app.speakers = vec![
-            make_speaker("cthulhu", Some("cthulhu")),
-            make_speaker("family", Some("cthulhu")),
-            make_speaker("hermit", None),
-        ];
-        let members = app.group_members_of("cthulhu");
-        assert_eq!(members.len(), 2);
+        app.speakers = vec![make_speaker("Nursery", None)];
+        assert_eq!(app.toggle_do_not_disturb(), Some(true));
+        app.apply_config(&Config::default());
+        assert!(app.is_excluded("Nursery"));
+        assert_eq!(app.toggle_do_not_disturb(), Some(false));
+        assert!(app.do_not_disturb.is_empty());
     }
 
     #[test]
-    fn test_group_members_of_returns_empty_for_unknown_coordinator() {
+    fn test_party_targets_prefer_party_speakers() {
         let mut app = App::new();
-        app.speakers = vec![
-            make_speaker("cthulhu", Some("cthulhu")),
-        ];
-        let members = app.group_members_of("nobody");
-        assert!(members.is_empty());
+        app.speakers = vec![make_speaker("Den", None), make_speaker("Kitchen", None), make_speaker("Study", None)];
+        assert_eq!(app.party_targets().len(), 3);
+        app.group_set = vec!["Study".into(), "Den".into()];
+        assert_eq!(app.party_targets(), vec!["RINCON_Study", "RINCON_Den"]);
+        app.party.speakers = vec!["kitchen".into(), "den".into(), "attic".into()];
+        assert_eq!(app.party_targets(), vec!["RINCON_Kitchen", "RINCON_Den"]);
     }
 
     #[test]
-    fn test_playing_entities_deduplicates_groups() {
+    fn test_party_volume_snapshot_round_trip() {
+        let mut app = App::new();
+        app.speakers = vec![make_speaker("Den", None), make_speaker("Kitchen", None)];
+        app.speakers[1].volume = 10;
+        let ids: Vec<String> = app.speakers.iter().map(|s| s.id()).collect();
+        app.begin_party(&ids, 45);
+        // A second `party` mustn't overwrite the pre-party snapshot
+        app.begin_party(&ids, 60);
+        assert!(app.speakers.iter().all(|s| s.volume == 60));
+        assert_eq!(app.end_party(), vec![
+            ("RINCON_Den".to_string(), 25),
+            ("RINCON_Kitchen".to_string(), 10),
+        ]);
+        assert_eq!(app.speakers[1].volume, 10);
+        assert!(app.party_restore.is_none());
+    }
+
+    #[test]
+    fn test_group_volume_averages_members() {
         let mut app = App::new();
         app.speakers = vec![
-            make_speaker("cthulhu", Some("cthulhu")),  // coordinator
-            make_speaker("family", Some("cthulhu")),   // follower — skip
-            make_speaker("hermit", None),              // solo
+            make_speaker("Den", Some("Den")),
+            make_speaker("Kitchen", Some("Den")),
+            make_speaker("Study", None),
         ];
-        let entities = app.playing_entities();
-        assert_eq!(entities.len(), 2);
-        assert_eq!(entities[0].name, "cthulhu");
-        assert_eq!(entities[1].name, "hermit");
+        app.speakers[0].volume = 20;
+        app.speakers[1].volume = 45;
+        app.speakers[2].volume = 70;
+        assert_eq!(app.group_volume("RINCON_Den"), Some(33));
+        assert_eq!(app.group_volume("RINCON_Study"), Some(70));
+        assert_eq!(app.group_volume("RINCON_Nowhere"), None);
+
+        app.set_group_volume("RINCON_Den", 38);
+        assert_eq!((app.speakers[0].volume, app.speakers[1].volume), (25, 50));
+        assert_eq!(app.speakers[2].volume, 70);
     }
 
     #[test]
-    fn test_solo_speakers_empty_when_all_grouped() {
+    fn test_focused_group_needs_now_playing_and_a_real_group() {
         let mut app = App::new();
-        app.speakers = vec![
-            make_speaker("cthulhu", Some("cthulhu")),
-            make_speaker("family", Some("cthulhu")),
+        app.speakers = vec![make_speaker("Den", Some("Den")), make_speaker("Kitchen", Some("Den"))];
+        app.speaker_index = 1;
+        assert_eq!(app.focused_group(), None);
+        app.active_panel = Panel::NowPlaying;
+        assert_eq!(app.focused_group(), Some("RINCON_Den".to_string()));
+        app.speakers[1].coordinator_uid = None;
+        app.speaker_index = 1;
+        assert_eq!(app.focused_group(), None);
+    }
+
+    #[test]
+    fn test_request_quit_is_immediate_by_default() {
+        let mut app = App::new();
+        app.speakers = vec![make_speaker("Den", None)];
+        app.request_quit();
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_request_quit_confirms_while_playing() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        app.confirm_quit_when_playing = true;
+        app.speakers = vec![make_speaker("Den", None)];
+        app.request_quit();
+        assert!(!app.should_quit);
+
+        // Too slow: the window lapses and the next press asks again
+        clock.advance(Duration::from_secs(3));
+        app.request_quit();
+        assert!(!app.should_quit);
+
+        clock.advance(Duration::from_secs(1));
+        app.request_quit();
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_request_quit_skips_confirmation_when_silent() {
+        let mut app = App::new();
+        app.confirm_quit_when_playing = true;
+        app.speakers = vec![make_speaker("Den", None)];
+        app.speakers[0].state = "PAUSED_PLAYBACK".into();
+        app.request_quit();
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_adjust_sleep_moves_the_timer_but_not_into_the_past() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        assert_eq!(app.adjust_sleep(10), None);
+        app.sleep_until = Some(clock.now() + Duration::from_secs(600));
+        assert_eq!(app.adjust_sleep(10), Some(Duration::from_secs(1200)));
+        assert_eq!(app.adjust_sleep(-5), Some(Duration::from_secs(900)));
+        assert_eq!(app.adjust_sleep(-60), Some(Duration::ZERO));
+        assert_eq!(app.sleep_until, Some(clock.now()));
+    }
+
+    #[test]
+    fn test_screensaver_waits_for_idle_and_silence() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        app.apply_config(&Config { screensaver_minutes: 5, ..Config::default() });
+        let mut speakers = vec![make_speaker("den", None)];
+        speakers[0].state = "PAUSED_PLAYBACK".into();
+        app.set_speakers(speakers.clone());
+        clock.advance(Duration::from_secs(299));
+        assert!(!app.screensaver_active());
+        clock.advance(Duration::from_secs(1));
+        assert!(app.screensaver_active());
+
+        // Playback starting elsewhere wakes it, and restarts the idle wait
+        speakers[0].state = "PLAYING".into();
+        app.set_speakers(speakers.clone());
+        assert!(!app.screensaver_active());
+        speakers[0].state = "STOPPED".into();
+        app.set_speakers(speakers);
+        assert!(!app.screensaver_active());
+        clock.advance(Duration::from_secs(300));
+        assert!(app.screensaver_active());
+
+        app.apply_config(&Config::default());
+        assert!(!app.screensaver_active(), "0 minutes disables it");
+    }
+
+    #[test]
+    fn test_history_clear_needs_a_prompt_repeat() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        assert!(!app.confirm_history_clear());
+        clock.advance(CLEAR_CONFIRM);
+        assert!(!app.confirm_history_clear(), "window lapsed: ask again");
+        clock.advance(Duration::from_secs(1));
+        assert!(app.confirm_history_clear());
+        assert!(!app.confirm_history_clear(), "a confirmed clear disarms");
+    }
+
+    #[test]
+    fn test_supports_trusts_capabilities_then_learned_404s() {
+        let mut app = App::new();
+        assert!(app.supports("seek"), "unknown daemon: assume yes");
+        app.capabilities = Some(vec!["seek".into(), "mute".into()]);
+        assert!(app.supports("seek"));
+        assert!(!app.supports("queue"));
+        app.mark_unsupported("seek");
+        assert!(!app.supports("seek"));
+    }
+
+    #[test]
+    fn test_sort_speakers_by_group_puts_coordinator_first() {
+        let mut speakers = vec![
+            make_speaker("attic", Some("zebra")),
+            make_speaker("bedroom", None),
+            make_speaker("zebra", Some("zebra")),
+            make_speaker("den", Some("zebra")),
         ];
-        assert!(app.solo_speakers().is_empty());
+        sort_speakers(&mut speakers, "group");
+        let order: Vec<&str> = speakers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(order, vec!["bedroom", "zebra", "attic", "den"]);
     }
 
     #[test]
-    fn test_playing_entities_empty_speakers() {
-        let app = App::new();
-        assert!(app.playing_entities().is_empty());
+    fn test_room_order_lists_named_rooms_first_then_the_rest_alphabetically() {
+        let mut speakers = vec![
+            make_speaker("attic", None),
+            make_speaker("Kitchen", None),
+            make_speaker("cellar", None),
+            make_speaker("Living Room", None),
+        ];
+        let order = vec!["living room".to_string(), "Garage".to_string(), "Kitchen".to_string()];
+        sort_by_room_order(&mut speakers, &order);
+        let order: Vec<&str> = speakers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(order, vec!["Living Room", "Kitchen", "attic", "cellar"]);
     }
 
     #[test]
-    fn test_playing_entities_all_followers_returns_empty() {
+    fn test_room_order_keeps_selection_across_resorts() {
         let mut app = App::new();
-        // Both speakers point to a coordinator "ghost" that isn't in the list
-        app.speakers = vec![
-            make_speaker("follower1", Some("ghost")),
-            make_speaker("follower2", Some("ghost")),
+        app.set_speakers(vec![make_speaker("attic", None), make_speaker("den", None)]);
+        app.speaker_index = 0; // attic
+        let config = Config { room_order: vec!["den".to_string()], ..Config::default() };
+        app.apply_config(&config);
+        assert_eq!(names(&app), vec!["den", "attic"]);
+        assert_eq!(app.selected_speaker().unwrap().name, "attic");
+    }
+
+    #[test]
+    fn test_sort_speakers_daemon_keeps_order() {
+        let mut speakers = vec![make_speaker("study", None), make_speaker("attic", None)];
+        sort_speakers(&mut speakers, "daemon");
+        assert_eq!(speakers[0].name, "study");
+    }
+
+    #[test]
+    fn test_set_speakers_selection_moves_to_next_neighbor_when_speaker_vanishes() {
+        let mut app = App::new();
+        app.set_speakers(vec![
+            make_speaker("attic", None),
+            make_speaker("bedroom", None),
+            make_speaker("cthulhu", None),
+        ]);
+        app.speaker_index = 1; // bedroom
+        app.set_speakers(vec![make_speaker("cthulhu", None), make_speaker("attic", None)]);
+        assert_eq!(app.selected_speaker().unwrap().name, "cthulhu");
+    }
+
+    #[test]
+    fn test_set_speakers_selection_falls_back_to_previous_when_last_vanishes() {
+        let mut app = App::new();
+        app.set_speakers(vec![make_speaker("attic", None), make_speaker("bedroom", None)]);
+        app.speaker_index = 1; // bedroom, the last one
+        app.set_speakers(vec![make_speaker("attic", None)]);
+        assert_eq!(app.speaker_index, 0);
+        assert_eq!(app.selected_speaker().unwrap().name, "attic");
+    }
+
+    #[test]
+    fn test_mini_speaker_stays_selected_as_rooms_come_and_go() {
+        let mut app = App::new();
+        app.mini = Some("bed".to_string());
+        app.set_speakers(vec![make_speaker("attic", None), make_speaker("bedroom", None)]);
+        assert_eq!(app.selected_speaker().unwrap().name, "bedroom");
+        app.set_speakers(vec![make_speaker("bedroom", None)]);
+        app.set_speakers(vec![make_speaker("attic", None), make_speaker("annex", None), make_speaker("bedroom", None)]);
+        assert_eq!(app.selected_speaker().unwrap().name, "bedroom");
+    }
+
+    #[test]
+    fn test_status_messages_land_in_a_capped_event_log() {
+        let mut app = App::with_clock(Rc::new(MockClock::new(1_772_000_000)));
+        app.set_status("The Fellowship rests.", Linger::Brief);
+        assert_eq!(app.event_log.back(), Some(&LogEntry { at: 6 * 3600 + 13 * 60 + 20, text: "The Fellowship rests.".into() }));
+        for i in 0..EVENT_LOG_CAPACITY {
+            app.log_event(format!("event {}", i));
+        }
+        assert_eq!(app.event_log.len(), EVENT_LOG_CAPACITY);
+        assert_eq!(app.event_log.front().unwrap().text, "event 0");
+        app.scroll_log(1000);
+        assert_eq!(app.log_scroll, EVENT_LOG_CAPACITY - 1);
+    }
+
+    #[test]
+    fn test_listening_is_timed_from_play_until_it_stops() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        app.set_speakers(vec![make_speaker("den", None)]);
+        app.speakers[0].state = "STOPPED".into();
+        assert_eq!(app.begin_listening("RINCON_den", "altwave"), None);
+        // Not yet reported as playing: still within the grace period
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(app.listening_ended(), None);
+        app.speakers[0].state = "PLAYING".into();
+        assert_eq!(app.listening_ended(), None);
+        clock.advance(Duration::from_secs(600));
+        app.speakers[0].state = "PAUSED_PLAYBACK".into();
+        assert_eq!(app.listening_ended(), Some(Duration::from_secs(605)));
+        assert!(app.listening.is_none());
+    }
+
+    #[test]
+    fn test_listening_never_heard_counts_as_zero() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        app.begin_listening("RINCON_gone", "altwave");
+        clock.advance(LISTEN_GRACE);
+        assert_eq!(app.listening_ended(), Some(Duration::ZERO));
+    }
+
+    fn long_track(title: &str, duration: u64, position: u64) -> Option<Track> {
+        Some(Track {
+            title: title.into(), artist: "".into(), album: "".into(),
+            duration, position, source: "".into(), quality: "".into(), rating: None,
+        })
+    }
+
+    #[test]
+    fn test_resume_point_only_for_opted_in_seekable_plays() {
+        let mut app = App::new();
+        app.set_speakers(vec![make_speaker("den", None)]);
+        app.speakers[0].track = long_track("Essential Mix", 7200, 1800);
+        app.begin_listening("RINCON_den", "mix");
+        assert_eq!(app.resume_point(), None, "not in resume_playlists");
+
+        app.resume_playlists = vec!["mix".into()];
+        let point = ResumePoint { title: "Essential Mix".into(), position: 1800 };
+        assert_eq!(app.resume_point(), Some(("mix".into(), point)));
+
+        // A radio stream has no duration to come back to
+        app.speakers[0].track = long_track("KEXP", 0, 1800);
+        assert_eq!(app.resume_point(), None);
+    }
+
+    #[test]
+    fn test_resume_due_seeks_once_the_saved_track_plays() {
+        let clock = Rc::new(MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock.clone());
+        app.set_speakers(vec![make_speaker("den", None)]);
+        let point = ResumePoint { title: "Essential Mix".into(), position: 1800 };
+        app.await_resume("RINCON_den", "mix", point.clone());
+        assert_eq!(app.resume_due(), None, "nothing playing yet");
+        app.speakers[0].track = long_track("Essential Mix", 7200, 3);
+        assert_eq!(app.resume_due(), Some(("RINCON_den".into(), 1800)));
+        assert!(app.pending_resume.is_none());
+
+        // The saved place is past the end of what's there now
+        app.await_resume("RINCON_den", "mix", point.clone());
+        app.speakers[0].track = long_track("Essential Mix", 1200, 3);
+        assert_eq!(app.resume_due(), None);
+        assert!(app.pending_resume.is_none());
+
+        // A different opening track: give up after RESUME_WAIT
+        app.await_resume("RINCON_den", "mix", point);
+        app.speakers[0].track = long_track("Intro", 300, 3);
+        assert_eq!(app.resume_due(), None);
+        assert!(app.pending_resume.is_some());
+        clock.advance(RESUME_WAIT);
+        assert_eq!(app.resume_due(), None);
+        assert!(app.pending_resume.is_none());
+        assert!(app.status_message.as_deref().unwrap().contains("starts from the beginning"));
+    }
+
+    #[test]
+    fn test_select_playing_cycles_through_playing_coordinators() {
+        let mut app = App::new();
+        let mut speakers = vec![
+            make_speaker("attic", None),
+            make_speaker("den", Some("kitchen")),
+            make_speaker("kitchen", Some("kitchen")),
+            make_speaker("study", None),
         ];
-        assert!(app.playing_entities().is_empty());
+        speakers[0].state = "STOPPED".into();
+        app.set_speakers(speakers);
+        assert_eq!(app.select_playing().as_deref(), Some("kitchen"));
+        assert!(app.center_rooms.get());
+        assert_eq!(app.select_playing().as_deref(), Some("study"));
+        assert_eq!(app.select_playing().as_deref(), Some("kitchen"));
+        for sp in &mut app.speakers {
+            sp.state = "PAUSED_PLAYBACK".into();
+        }
+        assert_eq!(app.select_playing(), None);
     }
 
     #[test]
-    fn test_toggle_source() {
+    fn test_set_speakers_empty_list_resets_index() {
         let mut app = App::new();
-        assert_eq!(app.source_mode, SourceMode::Playlists);
-        app.toggle_source();
-        assert_eq!(app.source_mode, SourceMode::Podcasts);
-        app.toggle_source();
-        assert_eq!(app.source_mode, SourceMode::Playlists);
+        app.set_speakers(vec![make_speaker("attic", None), make_speaker("bedroom", None)]);
+        app.speaker_index = 1;
+        app.set_speakers(vec![]);
+        assert_eq!(app.speaker_index, 0);
+        assert!(app.selected_speaker().is_none());
     }
 
     #[test]
-    fn test_toggle_source_resets_drill() {
+    fn test_set_playlists_keeps_selected_alias() {
         let mut app = App::new();
-        app.podcast_drill = true;
-        app.toggle_source();
-        assert!(!app.podcast_drill);
+        app.playlists = vec![make_playlist("altwave"), make_playlist("jazz")];
+        app.playlist_index = 1;
+        app.set_playlists(vec![make_playlist("ambient"), make_playlist("altwave"), make_playlist("jazz")]);
+        assert_eq!(app.selected_playlist().unwrap().alias, "jazz");
     }
 
     #[test]
-    fn test_podcast_navigation() {
+    fn test_merge_favorites_skips_configured() {
+        let mut playlists = vec![Playlist { alias: "altwave".into(), favorite_name: "Alt Wave".into(), source: PlaylistSource::Configured }];
+        merge_favorites(&mut playlists, vec!["alt wave".into(), "Jazz Classics".into()]);
+        assert_eq!(playlists.len(), 2);
+        assert_eq!(playlists[1].alias, "Jazz Classics");
+    }
+
+    #[test]
+    fn test_group_targets_resolves_configured_subset() {
         let mut app = App::new();
-        app.source_mode = SourceMode::Podcasts;
+        app.speakers = vec![make_speaker("Den", None), make_speaker("Kitchen", None), make_speaker("Bedroom", None)];
+        assert_eq!(app.group_targets(), None);
+        app.group_set = vec!["kitchen".to_string(), "Attic".to_string(), "Den".to_string()];
+        assert_eq!(app.group_targets(), Some(vec!["RINCON_Kitchen".to_string(), "RINCON_Den".to_string()]));
+    }
+
+    #[test]
+    fn test_playlist_volume_is_opt_in_and_clamped() {
+        let mut app = App::new();
+        app.playlist_volumes = HashMap::from([("ambient".to_string(), 15), ("party".to_string(), 150)]);
+        assert_eq!(app.playlist_volume("ambient"), Some(15));
+        assert_eq!(app.playlist_volume("party"), Some(100));
+        assert_eq!(app.playlist_volume("altwave"), None);
+    }
+
+    #[test]
+    fn test_next_up_fetched_once_near_track_end() {
+        let mut app = App::new();
+        let mut sp = make_speaker("Den", None);
+        sp.track = Some(make_track("Elvis in the Freezer")); // 83 of 231
+        app.speakers = vec![sp];
+        assert_eq!(app.next_up_request(), None);
+
+        app.speakers[0].track.as_mut().unwrap().position = 225;
+        assert_eq!(app.next_up_request(), Some("RINCON_Den".to_string()));
+        let next = QueueItem { title: "Black Country".into(), artist: "Ratboys".into(), album: String::new() };
+        app.next_up = Some(NextUp {
+            speaker: "RINCON_Den".into(),
+            current_title: "Elvis in the Freezer".into(),
+            track: Some(next.clone()),
+        });
+        assert_eq!(app.next_up_request(), None);
+        assert_eq!(app.next_up_for(&app.speakers[0]), Some(&next));
+
+        // Track changed: stale cache is hidden and a fresh fetch is due
+        app.speakers[0].track.as_mut().unwrap().title = "Black Country".into();
+        assert_eq!(app.next_up_for(&app.speakers[0]), None);
+        assert_eq!(app.next_up_request(), Some("RINCON_Den".to_string()));
+
+        app.next_up_seconds = 0;
+        assert_eq!(app.next_up_request(), None);
+    }
+
+    #[test]
+    fn test_jump_to_speaker_selects_and_focuses() {
+        let mut app = App::new();
+        app.speakers = vec![make_speaker("Den", None), make_speaker("Kitchen", None)];
         app.active_panel = Panel::Playlists;
-        app.podcasts = vec![
-            crate::api::Podcast { alias: "a".into(), name: "A".into(), url: "".into(), unplayed: 0 },
-            crate::api::Podcast { alias: "b".into(), name: "B".into(), url: "".into(), unplayed: 0 },
+        app.jump_to_speaker(2);
+        assert_eq!(app.speaker_index, 1);
+        assert_eq!(app.active_panel, Panel::Speakers);
+        app.jump_to_speaker(3);
+        app.jump_to_speaker(0);
+        assert_eq!(app.speaker_index, 1);
+    }
+
+    #[test]
+    fn test_move_playlist_swaps_and_records_order() {
+        let mut app = App::new();
+        app.playlists = vec![make_playlist("a"), make_playlist("b"), make_playlist("c")];
+        app.playlist_index = 1;
+        assert!(app.move_playlist(-1));
+        assert_eq!(app.playlist_index, 0);
+        assert_eq!(app.playlist_order, vec!["b", "a", "c"]);
+        assert!(!app.move_playlist(-1));
+        app.playlist_index = 2;
+        assert!(!app.move_playlist(1));
+        assert_eq!(app.playlist_order, vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_move_playlist_on_empty_list_is_noop() {
+        let mut app = App::new();
+        assert!(!app.move_playlist(1));
+        assert!(!app.move_playlist(-1));
+    }
+
+    #[test]
+    fn test_apply_manual_order_appends_unknown() {
+        let mut playlists = vec![make_playlist("new"), make_playlist("a"), make_playlist("b"), make_playlist("newer")];
+        apply_manual_order(&mut playlists, &["b".to_string(), "a".to_string()]);
+        let aliases: Vec<&str> = playlists.iter().map(|p| p.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["b", "a", "new", "newer"]);
+    }
+
+    #[test]
+    fn test_manual_sort_applies_on_set_playlists_and_config_change() {
+        let mut app = App::new();
+        app.playlist_order = vec!["c".to_string(), "a".to_string()];
+        app.set_playlists(vec![make_playlist("a"), make_playlist("b"), make_playlist("c")]);
+        assert_eq!(app.playlists[0].alias, "a");
+        app.apply_config(&Config { playlist_sort: "manual".to_string(), ..Config::default() });
+        let aliases: Vec<&str> = app.playlists.iter().map(|p| p.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_cycle_sort_steps_through_local_orders_and_back() {
+        let mut app = App::new();
+        app.playlist_order = vec!["b".to_string()];
+        app.apply_config(&Config { playlist_sort: "manual".to_string(), ..Config::default() });
+        let received = vec![
+            Playlist { alias: "c".into(), favorite_name: "Ambient".into(), source: PlaylistSource::Configured },
+            make_playlist("a"),
+            make_playlist("b"),
         ];
-        app.next_in_list();
-        assert_eq!(app.podcast_index, 1);
-        app.next_in_list();
-        assert_eq!(app.podcast_index, 0);
+        app.set_playlists(received);
+        app.playlist_index = 1;
+        let aliases = |app: &App| app.playlists.iter().map(|p| p.alias.clone()).collect::<Vec<_>>();
+        assert_eq!(aliases(&app), vec!["b", "c", "a"]);
+
+        app.cycle_sort();
+        assert_eq!(app.active_sort(), "alphabetical");
+        assert_eq!(aliases(&app), vec!["a", "c", "b"], "by favorite name");
+        assert_eq!(app.selected_playlist().unwrap().alias, "c", "selection follows the alias");
+        app.cycle_sort();
+        assert_eq!(app.active_sort(), "recent");
+        app.cycle_sort();
+        assert_eq!(app.active_sort(), "received");
+        assert_eq!(aliases(&app), vec!["c", "a", "b"]);
+        app.cycle_sort();
+        assert_eq!(app.local_sort, None);
+        assert_eq!(aliases(&app), vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_merge_favorites_tags_source() {
+        let mut playlists = vec![make_playlist("altwave")];
+        merge_favorites(&mut playlists, vec!["Jazz Classics".into()]);
+        assert_eq!(playlists[0].source, PlaylistSource::Configured);
+        assert_eq!(playlists[1].source, PlaylistSource::Favorite);
+    }
+
+    #[test]
+    fn test_toggle_dashboard_focuses_speakers() {
+        let mut app = App::new();
+        app.active_panel = Panel::Playlists;
+        app.toggle_dashboard();
+        assert!(app.dashboard_mode);
+        assert_eq!(app.active_panel, Panel::Speakers);
+        app.toggle_dashboard();
+        assert!(!app.dashboard_mode);
+    }
+
+    #[test]
+    fn test_remember_command_dedupes_and_caps() {
+        let mut app = App::new();
+        assert!(!app.remember_command("   "));
+        for i in 0..COMMAND_HISTORY_CAPACITY + 5 {
+            app.remember_command(&format!("vol {}", i));
+        }
+        assert_eq!(app.command_history.len(), COMMAND_HISTORY_CAPACITY);
+        assert_eq!(app.command_history[0], "vol 5");
+        app.remember_command(" vol 5 ");
+        assert_eq!(app.command_history.last().map(String::as_str), Some("vol 5"));
+        assert_eq!(app.command_history.iter().filter(|c| *c == "vol 5").count(), 1);
+    }
+
+    #[test]
+    fn test_history_search_steps_to_older_matches_and_restores_on_cancel() {
+        let mut app = App::new();
+        for line in ["play altwave", "vol 30", "play Jazz Classics", "next"] {
+            app.remember_command(line);
+        }
+        app.command_input = Some("sl".into());
+        app.search_history();
+        app.edit_history_query(|q| q.push_str("PLAY"));
+        assert_eq!(app.history_match(), Some("play Jazz Classics"));
+        app.search_history();
+        assert_eq!(app.history_match(), Some("play altwave"));
+        // Nothing older: stays put
+        app.search_history();
+        assert_eq!(app.history_match(), Some("play altwave"));
+        // Typing goes back to the newest match
+        app.edit_history_query(|q| q.push_str(" a"));
+        assert_eq!(app.history_match(), Some("play altwave"));
+        app.edit_history_query(|q| q.push('z'));
+        assert_eq!(app.history_match(), None);
+        app.cancel_history_search();
+        assert!(app.history_search.is_none());
+        assert_eq!(app.command_input.as_deref(), Some("sl"));
     }
 }
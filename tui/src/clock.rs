@@ -0,0 +1,136 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of time for timers, status expiry, and history timestamps.
+/// `SystemClock` in the app; `MockClock` in tests for deterministic expiry.
+pub trait Clock {
+    /// Monotonic time for timers and expiries.
+    fn now(&self) -> Instant;
+    /// Wall-clock seconds since the Unix epoch, for persisted timestamps.
+    fn unix_now(&self) -> u64;
+    /// Seconds since local midnight, for wall-clock features like `sleep at`.
+    fn local_seconds_of_day(&self) -> u64;
+}
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Seconds from `now_of_day` until the next `hour:minute` — later today, or
+/// tomorrow if that time has already passed (or is right now).
+pub fn seconds_until(now_of_day: u64, hour: u8, minute: u8) -> u64 {
+    let target = hour as u64 * 3600 + minute as u64 * 60;
+    let now = now_of_day % DAY;
+    if target > now { target - now } else { DAY - now + target }
+}
+
+/// `HH:MM:SS` for a local seconds-of-day value.
+pub fn format_hms(secs_of_day: u64) -> String {
+    let s = secs_of_day % DAY;
+    format!("{:02}:{:02}:{:02}", s / 3600, (s / 60) % 60, s % 60)
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn unix_now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+
+    fn local_seconds_of_day(&self) -> u64 {
+        local_seconds_of_day(self.unix_now())
+    }
+}
+
+/// Seconds since local midnight at `unix`, by the C library's zone rules.
+#[cfg(unix)]
+fn local_seconds_of_day(unix: u64) -> u64 {
+    let now = unix as libc::time_t;
+    // SAFETY: `tm` is plain data; localtime_r only writes into it and is thread-safe.
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&now, &mut tm) }.is_null() {
+        return unix % DAY; // no zone info: fall back to UTC
+    }
+    (tm.tm_hour as u64) * 3600 + (tm.tm_min as u64) * 60 + tm.tm_sec as u64
+}
+
+/// Elsewhere there's no zone database to ask, so local time is UTC.
+#[cfg(not(unix))]
+fn local_seconds_of_day(unix: u64) -> u64 {
+    unix % DAY
+}
+
+#[cfg(test)]
+pub use mock::MockClock;
+
+#[cfg(test)]
+mod mock {
+    use super::{Clock, DAY};
+    use std::cell::Cell;
+    use std::time::{Duration, Instant};
+
+    /// A clock that only moves when told to.
+    pub struct MockClock {
+        start: Instant,
+        elapsed: Cell<Duration>,
+        unix_start: u64,
+    }
+
+    impl MockClock {
+        pub fn new(unix_start: u64) -> Self {
+            Self { start: Instant::now(), elapsed: Cell::new(Duration::ZERO), unix_start }
+        }
+
+        pub fn advance(&self, by: Duration) {
+            self.elapsed.set(self.elapsed.get() + by);
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> Instant {
+            self.start + self.elapsed.get()
+        }
+
+        fn unix_now(&self) -> u64 {
+            self.unix_start + self.elapsed.get().as_secs()
+        }
+
+        /// Tests run in UTC.
+        fn local_seconds_of_day(&self) -> u64 {
+            self.unix_now() % DAY
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_mock_clock_advances_both_timelines() {
+        let clock = MockClock::new(1_000);
+        let t0 = clock.now();
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(clock.now() - t0, Duration::from_secs(90));
+        assert_eq!(clock.unix_now(), 1_090);
+    }
+
+    #[test]
+    fn test_seconds_until_later_today_or_tomorrow() {
+        let ten_pm = 22 * 3600;
+        assert_eq!(seconds_until(ten_pm, 23, 30), 90 * 60);
+        assert_eq!(seconds_until(ten_pm, 7, 5), (9 * 60 + 5) * 60);
+        assert_eq!(seconds_until(ten_pm, 22, 0), 24 * 3600);
+    }
+
+    #[test]
+    fn test_format_hms_pads_each_field() {
+        assert_eq!(format_hms(7 * 3600 + 5 * 60 + 9), "07:05:09");
+        assert_eq!(format_hms(DAY + 61), "00:01:01");
+    }
+}
@@ -1,21 +1,127 @@
+use crate::api::Playlist;
+
 #[derive(Debug, PartialEq)]
 pub enum Command {
     Play(String),
+    /// `play #n` — the n-th (1-based) playlist in the list as currently shown.
+    PlayIndex(usize),
+    /// `solo <playlist>` — play on the selected speaker alone, skipping `play_group`.
+    PlaySolo(String),
     /// (optional speaker alias/"all", volume 0-100)
     Volume(Option<String>, u8),
+    /// `vol [target] +5` / `-5`: step each targeted speaker from its own volume.
+    VolumeBy(Option<String>, i16),
     GroupAll,
     Ungroup,
     Next,
     Prev,
     Sleep(u32),
+    /// `sleep <mins> [here] [fade]` — a timer with modifiers.
+    SleepWith(u32, SleepOpts),
+    /// `sleep at HH:MM` — local wall-clock time (hour 0-23, minute 0-59).
+    SleepAt(u8, u8),
     SleepCancel,
+    /// `sleep +10` / `sleep -5` — move the running timer by that many minutes.
+    SleepAdjust(i32),
     Reload,
+    /// Have sonosd rediscover speakers, then re-fetch and log what had drifted.
+    Resync,
     Source,
     PodcastRefresh,
     Mark,
+    /// Pause the named speaker (fuzzy-matched), `all`, or the selected one if None.
+    Pause(Option<String>),
+    Resume(Option<String>),
+    /// Stop (not just pause) the named or selected speaker.
+    Stop(Option<String>),
+    /// Flip the selected speaker between playing and paused.
+    Toggle,
+    /// `mute|unmute [toggle] [<speaker>|all]` — target None means the selected speaker.
+    Mute { target: Option<String>, action: MuteAction },
+    /// Resume exactly the speakers the last sleep timer paused.
+    Undo,
+    /// Group the party set, set its volume, start its playlist (all from config).
+    Party,
+    /// Ungroup and put back the volumes from before `party`.
+    PartyOff,
+    /// Set every member of the named (or selected) speaker's group to the group's average volume.
+    Balance(Option<String>),
+    /// Toggle the event log overlay.
+    Log,
+    /// Show listening time per playlist and overall.
+    Stats,
+    /// Summarize the past seven days: plays, top playlists, busiest day.
+    Recap,
+    /// Empty the play history; needs repeating within `CLEAR_CONFIRM`.
+    HistoryClear,
+    /// Forget the most recent play.
+    HistoryDrop,
+    /// Cycle the playlist order: daemon's, alphabetical, recent, as received.
+    Sort,
+    /// Show the named (or selected) speaker's IP, model and other metadata.
+    Detail(Option<String>),
+    /// Append the selected speaker's current track to the named Sonos playlist.
+    SaveTrack(String),
+    /// Thumb the selected group's track up or down.
+    Like,
+    Dislike,
+    /// `follow [on|off]` — set follow mode, or flip it when None.
+    Follow(Option<bool>),
+    /// `bring <speaker>` — carry the selection's music to that room.
+    Bring(String),
+    /// Show the plays behind the selected playlist's popularity rank.
+    Why,
+    /// Every speaker's state, volume, mute and track as one plain-text table.
+    List,
     Unknown(String),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MuteAction {
+    Mute,
+    Unmute,
+    Toggle,
+}
+
+/// What the `v` prompt's entry asks for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolumeEntry {
+    /// `40`: exactly that (capped at 100).
+    Set(u8),
+    /// `+10` / `-5`: that far up or down; a bare sign steps like `+`/`-`.
+    Change(i16),
+    /// `m`: toggle mute.
+    Mute,
+}
+
+/// Read a `v` prompt entry. Empty or anything else is None, which cancels.
+pub fn parse_volume_entry(input: &str) -> Option<VolumeEntry> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("m") {
+        return Some(VolumeEntry::Mute);
+    }
+    let step = |digits: &str| match digits {
+        "" => Some(5),
+        d if d.chars().all(|c| c.is_ascii_digit()) => d.parse::<i16>().ok(),
+        _ => None,
+    };
+    if let Some(digits) = input.strip_prefix('+') {
+        return step(digits).map(VolumeEntry::Change);
+    }
+    if let Some(digits) = input.strip_prefix('-') {
+        return step(digits).map(|d| VolumeEntry::Change(-d));
+    }
+    input.parse::<u8>().ok().map(|v| VolumeEntry::Set(v.min(100)))
+}
+
+/// Modifiers for `sleep <mins>`: `here` scopes it to the selected speaker's
+/// group, `fade` ramps the volume down before pausing.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SleepOpts {
+    pub here: bool,
+    pub fade: bool,
+}
+
 pub fn parse(input: &str) -> Option<Command> {
     let input = input.trim();
     if input.is_empty() {
@@ -27,15 +133,23 @@ pub fn parse(input: &str) -> Option<Command> {
         .unwrap_or((input, ""));
 
     match cmd {
-        "play" | "p" => Some(Command::Play(rest.to_string())),
+        // Bare `p` matches the previous-track key; with an argument it's `play`
+        "p" if rest.is_empty() => Some(Command::Prev),
+        "play" | "p" => match rest.strip_prefix('#').map(str::parse::<usize>) {
+            Some(Ok(n)) => Some(Command::PlayIndex(n)),
+            _ => Some(Command::Play(rest.to_string())),
+        },
+        "solo" if !rest.is_empty() => Some(Command::PlaySolo(rest.to_string())),
         "vol" | "volume" => {
-            // "vol 30"  OR  "vol cthulhu 30"  OR  "vol all 30"
-            if let Ok(v) = rest.parse::<u8>() {
-                Some(Command::Volume(None, v))
-            } else if let Some((name, num_str)) = rest.split_once(' ') {
-                num_str.parse::<u8>().ok().map(|v| Command::Volume(Some(name.to_string()), v))
-            } else {
-                None
+            // "vol 30", "vol cthulhu 30", "vol all 30", "vol upstairs +5"
+            let (name, value) = match rest.rsplit_once(' ') {
+                Some((name, value)) => (Some(name.trim().to_string()), value),
+                None => (None, rest),
+            };
+            match parse_volume_entry(value)? {
+                VolumeEntry::Set(v) => Some(Command::Volume(name, v)),
+                VolumeEntry::Change(step) => Some(Command::VolumeBy(name, step)),
+                VolumeEntry::Mute => None,
             }
         }
         "group" => {
@@ -49,13 +163,36 @@ pub fn parse(input: &str) -> Option<Command> {
         "next" | "n" => Some(Command::Next),
         "prev" | "previous" => Some(Command::Prev),
         "sleep" => {
-            if rest == "0" || rest == "cancel" {
-                Some(Command::SleepCancel)
+            if let Some(time) = rest.strip_prefix("at ") {
+                let (h, m) = time.trim().split_once(':')?;
+                let (h, m) = (h.parse::<u8>().ok()?, m.parse::<u8>().ok()?);
+                return (h < 24 && m < 60).then_some(Command::SleepAt(h, m));
+            }
+            if rest.starts_with(['+', '-']) {
+                return rest.parse::<i32>().ok().map(Command::SleepAdjust);
+            }
+            let mut words = rest.split_whitespace();
+            let mins = words.next();
+            if mins == Some("0") || mins == Some("cancel") {
+                return Some(Command::SleepCancel);
+            }
+            let mins = mins?.parse::<u32>().ok()?;
+            let mut opts = SleepOpts::default();
+            for word in words {
+                match word {
+                    "here" => opts.here = true,
+                    "fade" => opts.fade = true,
+                    _ => return None,
+                }
+            }
+            if opts == SleepOpts::default() {
+                Some(Command::Sleep(mins))
             } else {
-                rest.parse::<u32>().ok().map(Command::Sleep)
+                Some(Command::SleepWith(mins, opts))
             }
         }
         "reload" => Some(Command::Reload),
+        "resync" => Some(Command::Resync),
         "source" => Some(Command::Source),
         "podcast" => {
             if rest == "refresh" {
@@ -65,22 +202,68 @@ pub fn parse(input: &str) -> Option<Command> {
             }
         }
         "mark" => Some(Command::Mark),
+        "pause" => Some(Command::Pause(Some(rest.to_string()).filter(|r| !r.is_empty()))),
+        "resume" => Some(Command::Resume(Some(rest.to_string()).filter(|r| !r.is_empty()))),
+        "stop" => Some(Command::Stop(Some(rest.to_string()).filter(|r| !r.is_empty()))),
+        "toggle" => Some(Command::Toggle),
+        "undo" => Some(Command::Undo),
+        "log" => Some(Command::Log),
+        "stats" => Some(Command::Stats),
+        "recap" => Some(Command::Recap),
+        "why" => Some(Command::Why),
+        "list" => Some(Command::List),
+        "sort" => Some(Command::Sort),
+        "save-track" if !rest.is_empty() => Some(Command::SaveTrack(rest.to_string())),
+        "bring" if !rest.is_empty() => Some(Command::Bring(rest.to_string())),
+        "like" => Some(Command::Like),
+        "dislike" => Some(Command::Dislike),
+        "follow" => match rest {
+            "" => Some(Command::Follow(None)),
+            "on" => Some(Command::Follow(Some(true))),
+            "off" => Some(Command::Follow(Some(false))),
+            _ => None,
+        },
+        "detail" => Some(Command::Detail(Some(rest.to_string()).filter(|r| !r.is_empty()))),
+        "history" => match rest {
+            "clear" => Some(Command::HistoryClear),
+            "drop" => Some(Command::HistoryDrop),
+            _ => Some(Command::Unknown(input.to_string())),
+        },
+        "balance" => Some(Command::Balance(Some(rest.to_string()).filter(|r| !r.is_empty()))),
+        "party" => match rest {
+            "" | "on" => Some(Command::Party),
+            "off" => Some(Command::PartyOff),
+            _ => Some(Command::Unknown(input.to_string())),
+        },
+        "mute" | "unmute" => {
+            let (action, rest) = match rest.strip_prefix("toggle") {
+                Some(r) if cmd == "mute" && (r.is_empty() || r.starts_with(' ')) => (MuteAction::Toggle, r.trim()),
+                _ if cmd == "mute" => (MuteAction::Mute, rest),
+                _ => (MuteAction::Unmute, rest),
+            };
+            let target = Some(rest.to_string()).filter(|r| !r.is_empty());
+            Some(Command::Mute { target, action })
+        }
         _ => Some(Command::Unknown(input.to_string())),
     }
 }
 
 /// Given partial command input (without leading `:`), return ghost text to display.
-/// `playlist_names` is a list of `favorite_name` strings for fuzzy matching.
+/// `playlists` are matched by alias and `favorite_name`, like `play` itself.
 /// `speaker_names` is a list of speaker alias/names for commands that target speakers.
-pub fn autocomplete(input: &str, playlist_names: &[String], speaker_names: &[String]) -> Option<String> {
+pub fn autocomplete(input: &str, playlists: &[Playlist], speaker_names: &[String]) -> Option<String> {
     if input.is_empty() {
         return None;
     }
     // If no space yet, complete the command name
     if !input.contains(' ') {
+        // `prev` before `play`, so the ghost on a bare `p` shows what Enter does
         let commands = [
-            "play", "vol", "group all", "ungroup", "next", "prev",
+            "prev", "play", "vol", "group all", "ungroup", "next",
             "sleep", "reload", "source", "podcast refresh", "mark",
+            "pause", "resume", "toggle", "stop", "mute", "unmute", "undo", "party", "party off", "balance", "log", "stats", "recap",
+            "history clear", "history drop", "sort", "detail", "save-track", "follow", "like", "dislike", "bring",
+            "list", "resync", "solo",
         ];
         for cmd in &commands {
             if cmd.starts_with(input) && *cmd != input {
@@ -91,28 +274,52 @@ pub fn autocomplete(input: &str, playlist_names: &[String], speaker_names: &[Str
     }
     let (cmd, rest) = input.split_once(' ').unwrap();
 
-    // :play <query> — fuzzy match against playlist names
-    if (cmd == "play" || cmd == "p") && !rest.is_empty() {
-        return fuzzy_complete(rest, playlist_names);
+    // :play <query> — fuzzy match against playlist aliases and names
+    if matches!(cmd, "play" | "p" | "solo") && !rest.is_empty() {
+        return playlist_complete(rest, playlists);
+    }
+
+    // :pause / :resume / :stop / :detail / :bring <speaker>
+    if matches!(cmd, "pause" | "resume" | "stop" | "detail" | "bring") && !rest.is_empty() {
+        return fuzzy_complete(rest, speaker_names);
+    }
+
+    // :mute / :unmute <speaker|all>
+    if matches!(cmd, "mute" | "unmute") && !rest.is_empty() {
+        let mut names = speaker_names.to_vec();
+        names.push("all".to_string());
+        return fuzzy_complete(rest, &names);
     }
 
     // :vol <speaker> <number> — complete speaker name as first arg, append space for number
-    if cmd == "vol" || cmd == "volume" {
-        if !rest.contains(' ') && !rest.is_empty() {
-            if rest.parse::<u8>().is_err() {
-                let mut names = speaker_names.to_vec();
-                names.push("all".to_string());
-                if let Some(ghost) = fuzzy_complete(rest, &names) {
-                    // Append trailing space so Tab gives ":vol cthulhu " ready for number
-                    return Some(format!("{} ", ghost));
-                }
-            }
+    if (cmd == "vol" || cmd == "volume")
+        && !rest.contains(' ')
+        && !rest.is_empty()
+        && rest.parse::<u8>().is_err()
+    {
+        let mut names = speaker_names.to_vec();
+        names.push("all".to_string());
+        if let Some(ghost) = fuzzy_complete(rest, &names) {
+            // Append trailing space so Tab gives ":vol cthulhu " ready for number
+            return Some(format!("{} ", ghost));
         }
     }
 
     None
 }
 
+/// What Tab turns `current` into given its `autocomplete` ghost: a suffix is
+/// appended; a ` → <full name>` ghost replaces everything after the command word.
+pub fn apply_completion(current: &str, ghost: &str) -> String {
+    match ghost.strip_prefix(" → ") {
+        None => format!("{}{}", current, ghost),
+        Some(full) => match current.trim_start().split_once(' ') {
+            Some((cmd, _)) => format!("{} {}", cmd, full),
+            None => current.to_string(),
+        },
+    }
+}
+
 /// Fuzzy-match `query` against `candidates`, returning ghost text suffix.
 fn fuzzy_complete(query: &str, candidates: &[String]) -> Option<String> {
     let q = query.to_lowercase();
@@ -120,36 +327,129 @@ fn fuzzy_complete(query: &str, candidates: &[String]) -> Option<String> {
     if candidates.iter().any(|n| n.to_lowercase() == q) {
         return None;
     }
-    // Prefix match
-    if let Some(m) = candidates.iter().find(|n| n.to_lowercase().starts_with(&q)) {
-        let prefix_byte_len: usize = m.chars()
-            .zip(m.to_lowercase().chars())
-            .take(q.chars().count())
-            .map(|(orig_c, _)| orig_c.len_utf8())
-            .sum();
-        return Some(m[prefix_byte_len..].to_string());
+    prefix_complete(&q, candidates).or_else(|| contains_complete(&q, candidates))
+}
+
+/// Complete playlists by alias or favorite name, as `play` accepts either:
+/// alias prefix first, then favorite-name prefix, then contains.
+fn playlist_complete(query: &str, playlists: &[Playlist]) -> Option<String> {
+    let q = query.to_lowercase();
+    let aliases: Vec<String> = playlists.iter().map(|p| p.alias.clone()).collect();
+    let names: Vec<String> = playlists.iter().map(|p| p.favorite_name.clone()).collect();
+    if aliases.iter().chain(&names).any(|n| n.to_lowercase() == q) {
+        return None;
+    }
+    prefix_complete(&q, &aliases)
+        .or_else(|| prefix_complete(&q, &names))
+        .or_else(|| contains_complete(&q, &names))
+        .or_else(|| contains_complete(&q, &aliases))
+        .or_else(|| best_subsequence(&q, playlists).map(|p| format!(" → {}", p.favorite_name)))
+}
+
+/// How well `query`'s characters appear in order within `candidate`
+/// (case-insensitive, spaces in the query ignored); None if they don't.
+/// Runs of adjacent hits and hits at word starts score higher, gaps lower.
+pub fn subsequence_score(query: &str, candidate: &str) -> Option<i32> {
+    let cand: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut prev: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let i = pos + cand[pos..].iter().position(|&c| c == q)?;
+        score += 1;
+        if i == 0 || !cand[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+        match prev {
+            Some(p) if p + 1 == i => score += 2,
+            Some(p) => score -= (i - p - 1).min(3) as i32,
+            None => {}
+        }
+        prev = Some(i);
+        pos = i + 1;
     }
-    // Contains match fallback
-    if let Some(m) = candidates.iter().find(|n| n.to_lowercase().contains(&q)) {
-        return Some(format!(" → {}", m));
+    prev.map(|_| score)
+}
+
+/// The playlist whose alias or favorite name best fits `query` as a
+/// subsequence; the first listed wins a tie.
+pub fn best_subsequence<'a>(query: &str, playlists: &'a [Playlist]) -> Option<&'a Playlist> {
+    let mut best: Option<(i32, &Playlist)> = None;
+    for p in playlists {
+        let score = subsequence_score(query, &p.alias).max(subsequence_score(query, &p.favorite_name));
+        if let Some(s) = score.filter(|&s| best.is_none_or(|(b, _)| s > b)) {
+            best = Some((s, p));
+        }
     }
-    None
+    best.map(|(_, p)| p)
+}
+
+/// The rest of the first candidate starting with `q` (already lowercased).
+fn prefix_complete(q: &str, candidates: &[String]) -> Option<String> {
+    let m = candidates.iter().find(|n| n.to_lowercase().starts_with(q))?;
+    let prefix_byte_len: usize = m.chars()
+        .zip(m.to_lowercase().chars())
+        .take(q.chars().count())
+        .map(|(orig_c, _)| orig_c.len_utf8())
+        .sum();
+    Some(m[prefix_byte_len..].to_string())
+}
+
+/// Contains match fallback, shown as ` → <full name>`.
+fn contains_complete(q: &str, candidates: &[String]) -> Option<String> {
+    let m = candidates.iter().find(|n| n.to_lowercase().contains(q))?;
+    Some(format!(" → {}", m))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::PlaylistSource;
+
+    fn playlists(pairs: &[(&str, &str)]) -> Vec<Playlist> {
+        pairs.iter()
+            .map(|(alias, name)| Playlist { alias: alias.to_string(), favorite_name: name.to_string(), source: PlaylistSource::Configured })
+            .collect()
+    }
 
     #[test]
     fn test_parse_play() {
         assert_eq!(parse("play altwave"), Some(Command::Play("altwave".to_string())));
     }
 
+    #[test]
+    fn test_parse_play_by_index() {
+        assert_eq!(parse("play #1"), Some(Command::PlayIndex(1)));
+        assert_eq!(parse("p #99"), Some(Command::PlayIndex(99)));
+        // Not a number: fall back to name matching
+        assert_eq!(parse("play #1s"), Some(Command::Play("#1s".to_string())));
+    }
+
+    #[test]
+    fn test_parse_solo() {
+        assert_eq!(parse("solo altwave"), Some(Command::PlaySolo("altwave".to_string())));
+        assert!(matches!(parse("solo"), Some(Command::Unknown(_))));
+    }
+
     #[test]
     fn test_parse_volume() {
         assert_eq!(parse("vol 40"), Some(Command::Volume(None, 40)));
     }
 
+    #[test]
+    fn test_parse_volume_entry() {
+        assert_eq!(parse_volume_entry("40"), Some(VolumeEntry::Set(40)));
+        assert_eq!(parse_volume_entry("250"), Some(VolumeEntry::Set(100)));
+        assert_eq!(parse_volume_entry("+10"), Some(VolumeEntry::Change(10)));
+        assert_eq!(parse_volume_entry("-5"), Some(VolumeEntry::Change(-5)));
+        assert_eq!(parse_volume_entry("+"), Some(VolumeEntry::Change(5)));
+        assert_eq!(parse_volume_entry("-"), Some(VolumeEntry::Change(-5)));
+        assert_eq!(parse_volume_entry("m"), Some(VolumeEntry::Mute));
+        assert_eq!(parse_volume_entry(""), None);
+        assert_eq!(parse_volume_entry("4m"), None);
+        assert_eq!(parse_volume_entry("+-3"), None);
+    }
+
     #[test]
     fn test_parse_volume_with_speaker() {
         assert_eq!(parse("vol cthulhu 30"), Some(Command::Volume(Some("cthulhu".to_string()), 30)));
@@ -160,6 +460,15 @@ mod tests {
         assert_eq!(parse("vol all 30"), Some(Command::Volume(Some("all".to_string()), 30)));
     }
 
+    #[test]
+    fn test_parse_volume_steps_and_multiword_targets() {
+        assert_eq!(parse("vol upstairs +5"), Some(Command::VolumeBy(Some("upstairs".to_string()), 5)));
+        assert_eq!(parse("vol living room -10"), Some(Command::VolumeBy(Some("living room".to_string()), -10)));
+        assert_eq!(parse("vol living room 30"), Some(Command::Volume(Some("living room".to_string()), 30)));
+        assert_eq!(parse("vol +3"), Some(Command::VolumeBy(None, 3)));
+        assert_eq!(parse("vol den m"), None);
+    }
+
     #[test]
     fn test_parse_group_all() {
         assert_eq!(parse("group all"), Some(Command::GroupAll));
@@ -176,9 +485,128 @@ mod tests {
         assert_eq!(parse("sleep 0"), Some(Command::SleepCancel));
     }
 
+    #[test]
+    fn test_parse_sleep_adjust() {
+        assert_eq!(parse("sleep +10"), Some(Command::SleepAdjust(10)));
+        assert_eq!(parse("sleep -5"), Some(Command::SleepAdjust(-5)));
+        assert_eq!(parse("sleep +"), None);
+        assert_eq!(parse("sleep +10 fade"), None);
+        assert_eq!(parse("sleep 10"), Some(Command::Sleep(10)), "unsigned still sets a fresh timer");
+    }
+
+    #[test]
+    fn test_parse_sleep_modifiers() {
+        let both = SleepOpts { here: true, fade: true };
+        assert_eq!(parse("sleep 20 here fade"), Some(Command::SleepWith(20, both)));
+        assert_eq!(parse("sleep 20 fade here"), Some(Command::SleepWith(20, both)));
+        assert_eq!(parse("sleep 5 fade"), Some(Command::SleepWith(5, SleepOpts { here: false, fade: true })));
+        assert_eq!(parse("sleep 5 here"), Some(Command::SleepWith(5, SleepOpts { here: true, fade: false })));
+        assert_eq!(parse("sleep 5 loudly"), None);
+    }
+
+    #[test]
+    fn test_parse_sleep_at() {
+        assert_eq!(parse("sleep at 23:30"), Some(Command::SleepAt(23, 30)));
+        assert_eq!(parse("sleep at 7:05"), Some(Command::SleepAt(7, 5)));
+        assert_eq!(parse("sleep at 25:00"), None);
+        assert_eq!(parse("sleep at 12:60"), None);
+        assert_eq!(parse("sleep at noon"), None);
+    }
+
+    #[test]
+    fn test_parse_mute_forms() {
+        let mute = |target: Option<&str>, action| Some(Command::Mute { target: target.map(String::from), action });
+        assert_eq!(parse("mute"), mute(None, MuteAction::Mute));
+        assert_eq!(parse("mute kitchen"), mute(Some("kitchen"), MuteAction::Mute));
+        assert_eq!(parse("mute all"), mute(Some("all"), MuteAction::Mute));
+        assert_eq!(parse("unmute"), mute(None, MuteAction::Unmute));
+        assert_eq!(parse("unmute all"), mute(Some("all"), MuteAction::Unmute));
+        assert_eq!(parse("mute toggle"), mute(None, MuteAction::Toggle));
+        assert_eq!(parse("mute toggle den"), mute(Some("den"), MuteAction::Toggle));
+        // Names are resolved at execution time, so unknown ones still parse
+        assert_eq!(parse("mute mordor"), mute(Some("mordor"), MuteAction::Mute));
+        assert_eq!(parse("mute toggles"), mute(Some("toggles"), MuteAction::Mute));
+    }
+
+    #[test]
+    fn test_autocomplete_mute_speaker_or_all() {
+        let speakers = vec!["Kitchen".to_string()];
+        assert_eq!(autocomplete("mute ki", &[], &speakers), Some("tchen".to_string()));
+        assert_eq!(autocomplete("unmute a", &[], &speakers), Some("ll".to_string()));
+    }
+
+    #[test]
+    fn test_parse_party() {
+        assert_eq!(parse("party"), Some(Command::Party));
+        assert_eq!(parse("party on"), Some(Command::Party));
+        assert_eq!(parse("party off"), Some(Command::PartyOff));
+        assert_eq!(parse("party hard"), Some(Command::Unknown("party hard".to_string())));
+    }
+
+    #[test]
+    fn test_parse_balance() {
+        assert_eq!(parse("balance"), Some(Command::Balance(None)));
+        assert_eq!(parse("balance den"), Some(Command::Balance(Some("den".to_string()))));
+    }
+
+    #[test]
+    fn test_parse_log() {
+        assert_eq!(parse("log"), Some(Command::Log));
+        assert_eq!(parse("stats"), Some(Command::Stats));
+        assert_eq!(parse("recap"), Some(Command::Recap));
+        assert_eq!(parse("history clear"), Some(Command::HistoryClear));
+        assert_eq!(parse("history drop"), Some(Command::HistoryDrop));
+        assert!(matches!(parse("history"), Some(Command::Unknown(_))));
+        assert_eq!(parse("sort"), Some(Command::Sort));
+        assert_eq!(parse("detail"), Some(Command::Detail(None)));
+        assert_eq!(parse("save-track Late Night Keepers"), Some(Command::SaveTrack("Late Night Keepers".to_string())));
+        assert!(matches!(parse("save-track"), Some(Command::Unknown(_))));
+        assert_eq!(parse("bring Kitchen"), Some(Command::Bring("Kitchen".to_string())));
+        assert_eq!(parse("why"), Some(Command::Why));
+        assert_eq!(parse("list"), Some(Command::List));
+        assert!(matches!(parse("bring"), Some(Command::Unknown(_))));
+        assert_eq!(parse("detail den"), Some(Command::Detail(Some("den".to_string()))));
+    }
+
+    #[test]
+    fn test_parse_undo() {
+        assert_eq!(parse("undo"), Some(Command::Undo));
+    }
+
     #[test]
     fn test_parse_reload() {
         assert_eq!(parse("reload"), Some(Command::Reload));
+        assert_eq!(parse("resync"), Some(Command::Resync));
+    }
+
+    #[test]
+    fn test_parse_pause_resume_without_name() {
+        assert_eq!(parse("pause"), Some(Command::Pause(None)));
+        assert_eq!(parse("resume"), Some(Command::Resume(None)));
+    }
+
+    #[test]
+    fn test_parse_pause_resume_with_name() {
+        assert_eq!(parse("pause kitchen"), Some(Command::Pause(Some("kitchen".to_string()))));
+        assert_eq!(parse("resume living room"), Some(Command::Resume(Some("living room".to_string()))));
+    }
+
+    #[test]
+    fn test_parse_stop() {
+        assert_eq!(parse("stop"), Some(Command::Stop(None)));
+        assert_eq!(parse("stop den"), Some(Command::Stop(Some("den".to_string()))));
+    }
+
+    #[test]
+    fn test_parse_toggle() {
+        assert_eq!(parse("toggle"), Some(Command::Toggle));
+    }
+
+    #[test]
+    fn test_autocomplete_pause_speaker() {
+        let speakers = vec!["cthulhu".to_string(), "family".to_string()];
+        assert_eq!(autocomplete("pause fam", &[], &speakers), Some("ily".to_string()));
+        assert_eq!(autocomplete("resume cth", &[], &speakers), Some("ulhu".to_string()));
     }
 
     #[test]
@@ -197,18 +625,62 @@ mod tests {
         assert_eq!(autocomplete("sl", &[], &[]), Some("eep".to_string()));
         assert_eq!(autocomplete("re", &[], &[]), Some("load".to_string()));
         assert_eq!(autocomplete("reload", &[], &[]), None); // exact match
+        assert_eq!(autocomplete("p", &[], &[]), Some("rev".to_string()));
+        assert_eq!(autocomplete("pl", &[], &[]), Some("ay".to_string()));
     }
 
     #[test]
     fn test_autocomplete_play_fuzzy() {
-        let names = vec!["Alt Wave".to_string(), "Jazz Classics".to_string()];
+        let names = playlists(&[("aw", "Alt Wave"), ("jazz", "Jazz Classics")]);
         let result = autocomplete("play alt", &names, &[]);
         assert_eq!(result, Some(" Wave".to_string()));
     }
 
+    #[test]
+    fn test_autocomplete_play_prefers_alias_prefix() {
+        let names = playlists(&[("jz", "Jazz Classics"), ("chill", "Lo-Fi Beats")]);
+        assert_eq!(autocomplete("play ch", &names, &[]), Some("ill".to_string()));
+        assert_eq!(autocomplete("play j", &names, &[]), Some("z".to_string()));
+        assert_eq!(autocomplete("play ja", &names, &[]), Some("zz Classics".to_string()));
+        assert_eq!(autocomplete("play beat", &names, &[]), Some(" → Lo-Fi Beats".to_string()));
+        assert_eq!(autocomplete("play chill", &names, &[]), None, "a full alias is complete");
+    }
+
+    #[test]
+    fn test_subsequence_score_needs_characters_in_order() {
+        assert!(subsequence_score("jzcl", "Jazz Classics").is_some());
+        assert!(subsequence_score("lcjz", "Jazz Classics").is_none());
+        assert!(subsequence_score("", "Jazz Classics").is_none());
+        // A tighter, word-aligned match outranks a scattered one
+        assert!(subsequence_score("jc", "Jazz Classics") > subsequence_score("jc", "Jungle Disco"));
+    }
+
+    #[test]
+    fn test_autocomplete_play_falls_back_to_subsequence() {
+        let names = playlists(&[("lofi", "Lo-Fi Beats"), ("jazz", "Jazz Classics"), ("jungle", "Jungle Disco")]);
+        assert_eq!(autocomplete("play jzcl", &names, &[]), Some(" → Jazz Classics".to_string()));
+        assert_eq!(autocomplete("play jc", &names, &[]), Some(" → Jazz Classics".to_string()));
+        // Substring still wins when there is one
+        assert_eq!(autocomplete("play disco", &names, &[]), Some(" → Jungle Disco".to_string()));
+    }
+
+    #[test]
+    fn test_apply_completion() {
+        // Prefix ghosts are appended as-is
+        assert_eq!(apply_completion("play alt", "wave"), "play altwave");
+        assert_eq!(apply_completion("vol kit", "chen "), "vol kitchen ");
+        // Contains ghosts replace the whole query, however many words it has
+        assert_eq!(apply_completion("play disco", " → Jungle Disco"), "play Jungle Disco");
+        assert_eq!(apply_completion("play jazz cl", " → Jazz Classics"), "play Jazz Classics");
+        assert_eq!(apply_completion("play  lo fi  ", " → Lo-Fi Beats"), "play Lo-Fi Beats");
+        assert_eq!(apply_completion("vol kit", " → Kitchen "), "vol Kitchen ");
+        // Nothing to replace without a command word
+        assert_eq!(apply_completion("play", " → Jungle Disco"), "play");
+    }
+
     #[test]
     fn test_autocomplete_no_match() {
-        let names = vec!["Alt Wave".to_string()];
+        let names = playlists(&[("altwave", "Alt Wave")]);
         assert_eq!(autocomplete("play xyz", &names, &[]), None);
     }
 
@@ -252,11 +724,37 @@ mod tests {
     #[test]
     fn test_parse_play_alias_p() {
         assert_eq!(parse("p altwave"), Some(Command::Play("altwave".to_string())));
+        assert_eq!(parse("p jazz"), Some(Command::Play("jazz".to_string())));
+        assert_eq!(parse("p #2"), Some(Command::PlayIndex(2)));
+    }
+
+    #[test]
+    fn test_parse_like_dislike() {
+        assert_eq!(parse("like"), Some(Command::Like));
+        assert_eq!(parse("dislike"), Some(Command::Dislike));
+        assert_eq!(autocomplete("dis", &[], &[]), Some("like".to_string()));
+    }
+
+    #[test]
+    fn test_parse_follow() {
+        assert_eq!(parse("follow"), Some(Command::Follow(None)));
+        assert_eq!(parse("follow on"), Some(Command::Follow(Some(true))));
+        assert_eq!(parse("follow off"), Some(Command::Follow(Some(false))));
+        assert_eq!(parse("follow kitchen"), None);
+    }
+
+    #[test]
+    fn test_parse_bare_p_is_prev() {
+        assert_eq!(parse("p"), Some(Command::Prev));
+        assert_eq!(parse("  p  "), Some(Command::Prev));
+        assert_eq!(parse("prev"), Some(Command::Prev));
+        assert_eq!(parse("previous"), Some(Command::Prev));
+        assert_eq!(parse("play"), Some(Command::Play(String::new())));
     }
 
     #[test]
     fn test_autocomplete_p_alias_plays_fuzzy() {
-        let names = vec!["Alt Wave".to_string()];
+        let names = playlists(&[("aw", "Alt Wave")]);
         let result = autocomplete("p alt", &names, &[]);
         // "p alt" has a space so it enters the play-fuzzy path
         assert_eq!(result, Some(" Wave".to_string()));
@@ -1,52 +1,236 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
+use crate::clock::Clock;
+
+const DAY: u64 = 24 * 3600;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PlayEntry {
     pub playlist: String,
     pub played_at: u64,
+    /// Seconds listened, filled in once playback moves on. Absent for the
+    /// session still playing and for entries written before it was tracked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
 }
 
-fn now_unix() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0)
+/// Where a `resume_playlists` play left off: partway into the track `title`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResumePoint {
+    pub title: String,
+    pub position: u64,
 }
 
 pub fn history_path() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    let dir = PathBuf::from(home).join(".config/sonos-palantir");
-    fs::create_dir_all(&dir).ok();
-    dir.join("history.json")
+    crate::prefs::config_dir().join("history.json")
+}
+
+/// Resume points by playlist alias, kept beside the history.
+pub fn resume_path() -> PathBuf {
+    crate::prefs::config_dir().join("resume.json")
 }
 
 pub fn load() -> Vec<PlayEntry> {
-    fs::read_to_string(history_path())
+    load_from(&history_path())
+}
+
+fn load_from(path: &Path) -> Vec<PlayEntry> {
+    fs::read_to_string(path)
         .ok()
         .and_then(|s| serde_json::from_str(&s).ok())
         .unwrap_or_default()
 }
 
-pub fn record_play(playlist: &str) {
-    let now = now_unix();
+/// Write to a sibling temp file and rename it over `path`, so a crash
+/// mid-write can't leave half a file behind.
+fn save_to(path: &Path, value: &impl Serialize) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(value)?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, path)
+}
+
+pub fn record_play(playlist: &str, clock: &dyn Clock) {
+    let now = clock.unix_now();
     let path = history_path();
-    let mut entries: Vec<PlayEntry> = fs::read_to_string(&path)
-        .ok()
-        .and_then(|s| serde_json::from_str(&s).ok())
-        .unwrap_or_default();
-    entries.push(PlayEntry { playlist: playlist.to_string(), played_at: now });
+    let mut entries = load_from(&path);
+    entries.push(PlayEntry { playlist: playlist.to_string(), played_at: now, duration: None });
     let cutoff = now.saturating_sub(90 * 24 * 3600);
     entries.retain(|e| e.played_at > cutoff);
-    if let Ok(json) = serde_json::to_string_pretty(&entries) {
-        fs::write(&path, json).ok();
+    save_to(&path, &entries).ok();
+}
+
+/// Record how long the most recent play lasted, once it stops or is replaced.
+pub fn finish_play(listened: Duration) {
+    let path = history_path();
+    let mut entries = load_from(&path);
+    if finish_latest(&mut entries, listened) {
+        save_to(&path, &entries).ok();
     }
 }
 
-pub fn popularity_sort_from(playlists: &mut Vec<crate::api::Playlist>, entries: &[PlayEntry], now: u64) {
+/// Forget every play.
+pub fn clear() -> std::io::Result<()> {
+    save_to(&history_path(), &Vec::<PlayEntry>::new())
+}
+
+/// Remove the entry at `index` (in file order, oldest first); false if there
+/// was no such entry.
+pub fn delete(index: usize) -> std::io::Result<bool> {
+    delete_from(&history_path(), index)
+}
+
+fn delete_from(path: &Path, index: usize) -> std::io::Result<bool> {
+    let mut entries = load_from(path);
+    if index >= entries.len() {
+        return Ok(false);
+    }
+    entries.remove(index);
+    save_to(path, &entries)?;
+    Ok(true)
+}
+
+/// Set the newest entry's duration if it has none yet; false if there was nothing to finish.
+fn finish_latest(entries: &mut [PlayEntry], listened: Duration) -> bool {
+    match entries.iter_mut().max_by_key(|e| e.played_at) {
+        Some(e) if e.duration.is_none() => {
+            e.duration = Some(listened.as_secs());
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Remember where `alias` left off, replacing any earlier point.
+pub fn save_resume(alias: &str, point: ResumePoint) {
+    save_resume_to(&resume_path(), alias, point).ok();
+}
+
+/// The saved point for `alias`, forgotten as it's handed out so a play that
+/// runs to the end doesn't resume there again.
+pub fn take_resume(alias: &str) -> Option<ResumePoint> {
+    take_resume_from(&resume_path(), alias)
+}
+
+fn load_resumes(path: &Path) -> HashMap<String, ResumePoint> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_resume_to(path: &Path, alias: &str, point: ResumePoint) -> std::io::Result<()> {
+    let mut points = load_resumes(path);
+    points.insert(alias.to_string(), point);
+    save_to(path, &points)
+}
+
+fn take_resume_from(path: &Path, alias: &str) -> Option<ResumePoint> {
+    let mut points = load_resumes(path);
+    let point = points.remove(alias)?;
+    save_to(path, &points).ok();
+    Some(point)
+}
+
+/// Time spent listening across `entries`; untimed (older) entries add nothing.
+pub fn total_listening_time(entries: &[PlayEntry]) -> Duration {
+    Duration::from_secs(entries.iter().filter_map(|e| e.duration).sum())
+}
+
+/// Per-playlist listening time and play count, longest first (then by alias).
+/// Playlists with only untimed plays still appear, at zero.
+pub fn listening_by_playlist(entries: &[PlayEntry]) -> Vec<(String, Duration, usize)> {
+    let mut totals: HashMap<&str, (u64, usize)> = HashMap::new();
+    for e in entries {
+        let t = totals.entry(&e.playlist).or_default();
+        t.0 += e.duration.unwrap_or(0);
+        t.1 += 1;
+    }
+    let mut out: Vec<(String, Duration, usize)> = totals.into_iter()
+        .map(|(alias, (secs, plays))| (alias.to_string(), Duration::from_secs(secs), plays))
+        .collect();
+    out.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    out
+}
+
+/// Plays in the seven days up to `now`.
+pub fn plays_this_week(entries: &[PlayEntry], now: u64) -> usize {
+    let cutoff = now.saturating_sub(7 * DAY);
+    entries.iter().filter(|e| e.played_at > cutoff && e.played_at <= now).count()
+}
+
+/// How many different playlists `entries` covers.
+pub fn distinct_playlists(entries: &[PlayEntry]) -> usize {
+    entries.iter().map(|e| e.playlist.as_str()).collect::<HashSet<_>>().len()
+}
+
+/// The most consecutive days with at least one play, up to `now`. Days are
+/// UTC calendar days, so the count doesn't shift with the local zone (or DST).
+pub fn listening_streak(entries: &[PlayEntry], now: u64) -> u32 {
+    let mut days: Vec<u64> = entries.iter()
+        .filter(|e| e.played_at <= now)
+        .map(|e| e.played_at / DAY)
+        .collect();
+    days.sort_unstable();
+    days.dedup();
+    let (mut longest, mut run) = (0, 0);
+    for (i, day) in days.iter().enumerate() {
+        run = if i > 0 && days[i - 1] + 1 == *day { run + 1 } else { 1 };
+        longest = longest.max(run);
+    }
+    longest
+}
+
+/// Plays on each of the `days` UTC calendar days ending today (`now`'s),
+/// oldest first.
+pub fn plays_per_day(entries: &[PlayEntry], now: u64, days: usize) -> Vec<usize> {
+    let today = now / DAY;
+    let mut counts = vec![0; days];
+    for e in entries {
+        let ago = today.checked_sub(e.played_at / DAY).map(|d| d as usize);
+        if let Some(ago) = ago.filter(|&a| a < days) {
+            counts[days - 1 - ago] += 1;
+        }
+    }
+    counts
+}
+
+/// The `n` playlists played most since `since`, with their play counts
+/// (most first, then by alias).
+pub fn top_playlists_since(entries: &[PlayEntry], since: u64, n: usize) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for e in entries.iter().filter(|e| e.played_at >= since) {
+        *counts.entry(&e.playlist).or_default() += 1;
+    }
+    let mut top: Vec<(String, usize)> = counts.into_iter().map(|(a, c)| (a.to_string(), c)).collect();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top.truncate(n);
+    top
+}
+
+/// Short weekday name of the UTC day `now` falls on, `days_ago` days back.
+pub fn weekday_name(now: u64, days_ago: u64) -> &'static str {
+    // 1970-01-01 was a Thursday
+    const NAMES: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    NAMES[((now / DAY).saturating_sub(days_ago) % 7) as usize]
+}
+
+/// When the plays of `alias` that count toward its popularity happened
+/// (the same seven-day window as `popularity_sort`), newest first.
+pub fn popularity_plays(entries: &[PlayEntry], alias: &str, now: u64) -> Vec<u64> {
+    let cutoff = now.saturating_sub(7 * DAY);
+    let mut at: Vec<u64> = entries.iter()
+        .filter(|e| e.playlist == alias && e.played_at > cutoff)
+        .map(|e| e.played_at)
+        .collect();
+    at.sort_unstable_by(|a, b| b.cmp(a));
+    at
+}
+
+pub fn popularity_sort_from(playlists: &mut [crate::api::Playlist], entries: &[PlayEntry], now: u64) {
     let counts = play_counts_7d_from(entries, now);
     playlists.sort_by(|a, b| {
         let ca = counts.get(&a.alias).copied().unwrap_or(0);
@@ -55,17 +239,22 @@ pub fn popularity_sort_from(playlists: &mut Vec<crate::api::Playlist>, entries:
     });
 }
 
-pub fn popularity_sort(playlists: &mut Vec<crate::api::Playlist>) {
-    let counts = play_counts_7d();
-    playlists.sort_by(|a, b| {
-        let ca = counts.get(&a.alias).copied().unwrap_or(0);
-        let cb = counts.get(&b.alias).copied().unwrap_or(0);
-        cb.cmp(&ca).then(a.alias.cmp(&b.alias))
-    });
+pub fn popularity_sort(playlists: &mut [crate::api::Playlist], clock: &dyn Clock) {
+    popularity_sort_from(playlists, &load(), clock.unix_now());
+}
+
+/// Most recently played first; never-played playlists keep their order after them.
+pub fn recent_sort_from(playlists: &mut [crate::api::Playlist], entries: &[PlayEntry]) {
+    let mut last: HashMap<&str, u64> = HashMap::new();
+    for e in entries {
+        let t = last.entry(&e.playlist).or_default();
+        *t = (*t).max(e.played_at);
+    }
+    playlists.sort_by_key(|p| std::cmp::Reverse(last.get(p.alias.as_str()).copied()));
 }
 
-fn play_counts_7d() -> HashMap<String, usize> {
-    play_counts_7d_from(&load(), now_unix())
+pub fn recent_sort(playlists: &mut [crate::api::Playlist]) {
+    recent_sort_from(playlists, &load());
 }
 
 fn play_counts_7d_from(entries: &[PlayEntry], now: u64) -> HashMap<String, usize> {
@@ -82,16 +271,18 @@ fn play_counts_7d_from(entries: &[PlayEntry], now: u64) -> HashMap<String, usize
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api::Playlist;
+    use crate::api::{Playlist, PlaylistSource};
+
+    const NOW: u64 = 1_772_000_000;
 
     #[test]
     fn test_play_counts_7d_from_counts_recent() {
-        let now = now_unix();
+        let now = NOW;
         let entries = vec![
-            PlayEntry { playlist: "altwave".to_string(), played_at: now - 3600 },
-            PlayEntry { playlist: "altwave".to_string(), played_at: now - 3600 * 24 },
-            PlayEntry { playlist: "altwave".to_string(), played_at: now - 3600 * 24 * 10 }, // >7d
-            PlayEntry { playlist: "jazz".to_string(),    played_at: now - 3600 * 24 * 2 },
+            PlayEntry { playlist: "altwave".to_string(), played_at: now - 3600, duration: None },
+            PlayEntry { playlist: "altwave".to_string(), played_at: now - 3600 * 24, duration: None },
+            PlayEntry { playlist: "altwave".to_string(), played_at: now - 3600 * 24 * 10, duration: None }, // >7d
+            PlayEntry { playlist: "jazz".to_string(),    played_at: now - 3600 * 24 * 2, duration: None },
         ];
         let counts = play_counts_7d_from(&entries, now);
         assert_eq!(counts["altwave"], 2);
@@ -101,22 +292,185 @@ mod tests {
 
     #[test]
     fn test_popularity_sort_from_orders_by_count_desc() {
-        let now = now_unix();
+        let now = NOW;
         let entries = vec![
-            PlayEntry { playlist: "altwave".to_string(), played_at: now - 3600 },
-            PlayEntry { playlist: "altwave".to_string(), played_at: now - 7200 },
-            PlayEntry { playlist: "altwave".to_string(), played_at: now - 10800 },
-            PlayEntry { playlist: "altwave".to_string(), played_at: now - 14400 },
-            PlayEntry { playlist: "altwave".to_string(), played_at: now - 18000 },
-            PlayEntry { playlist: "jazz".to_string(),    played_at: now - 3600 },
-            PlayEntry { playlist: "jazz".to_string(),    played_at: now - 7200 },
+            PlayEntry { playlist: "altwave".to_string(), played_at: now - 3600, duration: None },
+            PlayEntry { playlist: "altwave".to_string(), played_at: now - 7200, duration: None },
+            PlayEntry { playlist: "altwave".to_string(), played_at: now - 10800, duration: None },
+            PlayEntry { playlist: "altwave".to_string(), played_at: now - 14400, duration: None },
+            PlayEntry { playlist: "altwave".to_string(), played_at: now - 18000, duration: None },
+            PlayEntry { playlist: "jazz".to_string(),    played_at: now - 3600, duration: None },
+            PlayEntry { playlist: "jazz".to_string(),    played_at: now - 7200, duration: None },
         ];
         let mut playlists = vec![
-            Playlist { alias: "jazz".to_string(),    favorite_name: "Jazz".to_string() },
-            Playlist { alias: "altwave".to_string(), favorite_name: "Alt Wave".to_string() },
+            Playlist { alias: "jazz".to_string(),    favorite_name: "Jazz".to_string(), source: PlaylistSource::Configured },
+            Playlist { alias: "altwave".to_string(), favorite_name: "Alt Wave".to_string(), source: PlaylistSource::Configured },
         ];
         popularity_sort_from(&mut playlists, &entries, now);
         assert_eq!(playlists[0].alias, "altwave");
         assert_eq!(playlists[1].alias, "jazz");
     }
+
+    #[test]
+    fn test_recent_sort_from_puts_last_played_first() {
+        let entries = vec![
+            PlayEntry { playlist: "jazz".to_string(), played_at: NOW - 7200, duration: None },
+            PlayEntry { playlist: "altwave".to_string(), played_at: NOW - 3600, duration: None },
+            PlayEntry { playlist: "jazz".to_string(), played_at: NOW - 60, duration: None },
+        ];
+        let make = |alias: &str| Playlist { alias: alias.to_string(), favorite_name: alias.to_string(), source: PlaylistSource::Configured };
+        let mut playlists = vec![make("drone"), make("altwave"), make("ambient"), make("jazz")];
+        recent_sort_from(&mut playlists, &entries);
+        let aliases: Vec<&str> = playlists.iter().map(|p| p.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["jazz", "altwave", "drone", "ambient"]);
+    }
+
+    fn timed(playlist: &str, played_at: u64, duration: Option<u64>) -> PlayEntry {
+        PlayEntry { playlist: playlist.to_string(), played_at, duration }
+    }
+
+    #[test]
+    fn test_listening_time_counts_only_timed_entries() {
+        let entries = vec![
+            timed("altwave", NOW - 7200, Some(1800)),
+            timed("altwave", NOW - 3600, None), // from before durations were tracked
+            timed("jazz", NOW - 600, Some(300)),
+            timed("drone", NOW - 500, None),
+        ];
+        assert_eq!(total_listening_time(&entries), Duration::from_secs(2100));
+        let by = listening_by_playlist(&entries);
+        assert_eq!(by[0], ("altwave".to_string(), Duration::from_secs(1800), 2));
+        assert_eq!(by[1], ("jazz".to_string(), Duration::from_secs(300), 1));
+        assert_eq!(by[2], ("drone".to_string(), Duration::ZERO, 1));
+    }
+
+    #[test]
+    fn test_headline_counts() {
+        let entries = vec![
+            timed("altwave", NOW - 8 * DAY, None),
+            timed("altwave", NOW - 2 * DAY, None),
+            timed("jazz", NOW - 60, None),
+            timed("jazz", NOW + 60, None), // clock skew: not yet
+        ];
+        assert_eq!(plays_this_week(&entries, NOW), 2);
+        assert_eq!(distinct_playlists(&entries), 2);
+        assert_eq!(distinct_playlists(&[]), 0);
+    }
+
+    #[test]
+    fn test_listening_streak_counts_consecutive_utc_days() {
+        // NOW is 06:13:20 UTC; `day(n, s)` is `s` seconds into the UTC day n days back
+        let midnight = NOW - NOW % DAY;
+        let day = |n: u64, secs: u64| midnight - n * DAY + secs;
+        assert_eq!(listening_streak(&[], NOW), 0);
+        let entries = vec![
+            // Three days running, one of them played twice
+            timed("a", day(9, 100), None),
+            timed("a", day(8, 80_000), None),
+            timed("a", day(8, 90), None),
+            timed("a", day(7, 0), None),
+            // A gap, then 23:59:59 and the next 00:00:00 — two days, side by side
+            timed("a", day(3, DAY - 1), None),
+            timed("a", day(2, 0), None),
+            timed("a", day(0, 10), None),
+        ];
+        assert_eq!(listening_streak(&entries, NOW), 3);
+        // Two plays on one day are one day
+        let same_day = vec![timed("a", day(1, 5), None), timed("a", day(1, DAY - 5), None)];
+        assert_eq!(listening_streak(&same_day, NOW), 1);
+        // Plays after `now` don't extend it
+        let ahead = vec![timed("a", day(0, 0), None), timed("a", NOW + 2 * DAY, None), timed("a", NOW + DAY, None)];
+        assert_eq!(listening_streak(&ahead, NOW), 1);
+    }
+
+    #[test]
+    fn test_popularity_plays_lists_the_counted_plays_newest_first() {
+        let entries = vec![
+            timed("altwave", NOW - 8 * DAY, None),
+            timed("altwave", NOW - 2 * DAY, None),
+            timed("jazz", NOW - 60, None),
+            timed("altwave", NOW - 3600, None),
+        ];
+        assert_eq!(popularity_plays(&entries, "altwave", NOW), vec![NOW - 3600, NOW - 2 * DAY]);
+        assert!(popularity_plays(&entries, "chill", NOW).is_empty());
+    }
+
+    #[test]
+    fn test_plays_per_day_buckets_by_utc_day_oldest_first() {
+        let midnight = NOW - NOW % DAY;
+        let entries = vec![
+            timed("a", NOW - 60, None),
+            timed("a", midnight, None),           // first second of today
+            timed("a", midnight - 1, None),       // last second of yesterday
+            timed("b", midnight - 6 * DAY, None), // a week's first day
+            timed("b", midnight - 6 * DAY - 1, None), // just outside
+            timed("b", NOW + DAY, None),          // in the future
+        ];
+        assert_eq!(plays_per_day(&entries, NOW, 7), vec![1, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(plays_per_day(&entries, NOW, 1), vec![2]);
+        let past = &entries[..5];
+        assert_eq!(top_playlists_since(past, midnight - 6 * DAY, 3), vec![("a".to_string(), 3), ("b".to_string(), 1)]);
+        assert_eq!(top_playlists_since(past, midnight, 1), vec![("a".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_weekday_name_counts_back_from_the_utc_day() {
+        assert_eq!(weekday_name(0, 0), "Thu");
+        // NOW (1_772_000_000) is Wednesday 2026-02-25, UTC
+        assert_eq!(weekday_name(NOW, 0), "Wed");
+        assert_eq!(weekday_name(NOW, 3), "Sun");
+    }
+
+    #[test]
+    fn test_old_entries_without_duration_still_load() {
+        let json = r#"[{"playlist": "altwave", "played_at": 1771990000}]"#;
+        let entries: Vec<PlayEntry> = serde_json::from_str(json).unwrap();
+        assert_eq!(entries[0].duration, None);
+    }
+
+    #[test]
+    fn test_finish_latest_times_only_the_open_newest_entry() {
+        let mut entries = vec![timed("jazz", NOW - 60, None), timed("altwave", NOW - 7200, None)];
+        assert!(finish_latest(&mut entries, Duration::from_secs(45)));
+        assert_eq!(entries[0].duration, Some(45));
+        assert_eq!(entries[1].duration, None);
+        assert!(!finish_latest(&mut entries, Duration::from_secs(90)));
+        assert_eq!(entries[0].duration, Some(45));
+    }
+
+    #[test]
+    fn test_delete_rewrites_the_file_without_the_entry() {
+        let dir = std::env::temp_dir().join(format!("palantir-history-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("history.json");
+        let entries = vec![timed("altwave", NOW - 300, Some(120)), timed("oops", NOW - 200, None), timed("jazz", NOW - 100, None)];
+        save_to(&path, &entries).unwrap();
+
+        assert!(delete_from(&path, 1).unwrap());
+        let kept: Vec<String> = load_from(&path).into_iter().map(|e| e.playlist).collect();
+        assert_eq!(kept, vec!["altwave", "jazz"]);
+        assert_eq!(load_from(&path)[0].duration, Some(120));
+        assert!(!delete_from(&path, 2).unwrap());
+        assert!(!path.with_extension("json.tmp").exists());
+
+        save_to(&path, &Vec::<PlayEntry>::new()).unwrap();
+        assert!(load_from(&path).is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_resume_points_are_kept_per_alias_and_taken_once() {
+        let dir = std::env::temp_dir().join(format!("palantir-resume-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("resume.json");
+        let point = |title: &str, position| ResumePoint { title: title.to_string(), position };
+        save_resume_to(&path, "mix", point("Essential Mix", 600)).unwrap();
+        save_resume_to(&path, "mix", point("Essential Mix", 1800)).unwrap();
+        save_resume_to(&path, "drone", point("Drone I", 90)).unwrap();
+
+        assert_eq!(take_resume_from(&path, "mix"), Some(point("Essential Mix", 1800)));
+        assert_eq!(take_resume_from(&path, "mix"), None);
+        assert_eq!(take_resume_from(&path, "drone"), Some(point("Drone I", 90)));
+        fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Everything a key can do outside command/volume input. `apply_action` in
+/// main performs these; the keymap decides which keys produce them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    CyclePanel,
+    Up,
+    Down,
+    /// Reorder the selected playlist (`playlist_sort: manual`); plain up/down elsewhere.
+    MoveUp,
+    MoveDown,
+    Select,
+    PlayPause,
+    VolumeUp,
+    VolumeDown,
+    Mute,
+    MuteAll,
+    Solo,
+    Balance,
+    EventLog,
+    Next,
+    Prev,
+    SkipForward,
+    SkipBack,
+    Group,
+    /// Add rooms to, or drop them from, the group focused in Now Playing.
+    EditGroup,
+    /// Take the selected room out of its group, or put a solo one into the biggest group.
+    JoinLeave,
+    VolumeInput,
+    Refresh,
+    DoNotDisturb,
+    Stop,
+    ToggleSource,
+    Dashboard,
+    /// Cycle the help bar: full, one line, hidden.
+    HelpBar,
+    /// Overlay the selected speaker's IP, model and other metadata.
+    Detail,
+    Command,
+    Help,
+    Episode,
+    Back,
+    /// Scroll Rooms so the selected speaker sits mid-panel.
+    CenterSelected,
+    /// Select (and center) the coordinator of the next playing group.
+    SelectPlaying,
+    /// Toggle follow mode: select whatever starts playing.
+    Follow,
+    Like,
+    Dislike,
+    /// Pause and mute everything, from any mode; `undo` restores.
+    PanicStop,
+    /// Flip between the selected room and the one selected before it.
+    SwapSpeaker,
+    /// Borderless full-screen now playing; any key leaves.
+    Zen,
+}
+
+/// Config name and stock keys for every action, in help order.
+const DEFAULTS: &[(Action, &str, &[&str])] = &[
+    (Action::Quit, "quit", &["q"]),
+    (Action::CyclePanel, "cycle_panel", &["tab"]),
+    (Action::Up, "up", &["up", "k"]),
+    (Action::Down, "down", &["down", "j"]),
+    (Action::MoveUp, "move_up", &["shift+up"]),
+    (Action::MoveDown, "move_down", &["shift+down"]),
+    (Action::Select, "select", &["enter"]),
+    (Action::PlayPause, "play_pause", &["space"]),
+    (Action::VolumeUp, "volume_up", &["+", "="]),
+    (Action::VolumeDown, "volume_down", &["-"]),
+    (Action::Mute, "mute", &["m"]),
+    (Action::MuteAll, "mute_all", &["M"]),
+    (Action::Solo, "solo", &["S"]),
+    (Action::Balance, "balance", &["B"]),
+    (Action::EventLog, "event_log", &["L"]),
+    (Action::Next, "next", &["n"]),
+    (Action::Prev, "prev", &["p"]),
+    (Action::SkipForward, "skip_forward", &["f", "right"]),
+    (Action::SkipBack, "skip_back", &["b", "left"]),
+    (Action::Group, "group", &["g"]),
+    (Action::EditGroup, "edit_group", &["G"]),
+    (Action::JoinLeave, "join_leave", &["o"]),
+    (Action::VolumeInput, "volume_input", &["v"]),
+    (Action::Refresh, "refresh", &["R", "f5"]),
+    (Action::DoNotDisturb, "do_not_disturb", &["z"]),
+    (Action::Stop, "stop", &["x"]),
+    (Action::ToggleSource, "toggle_source", &["s"]),
+    (Action::Dashboard, "dashboard", &["d"]),
+    (Action::HelpBar, "help_bar", &["h"]),
+    (Action::Detail, "detail", &["i"]),
+    (Action::Command, "command", &[":"]),
+    (Action::Help, "help", &["?"]),
+    (Action::Episode, "episode", &["e"]),
+    (Action::Back, "back", &["esc"]),
+    (Action::CenterSelected, "center", &["c"]),
+    (Action::SelectPlaying, "select_playing", &["P"]),
+    (Action::Follow, "follow", &["F"]),
+    (Action::Like, "like", &["t"]),
+    (Action::Dislike, "dislike", &["T"]),
+    (Action::PanicStop, "panic_stop", &["ctrl+x"]),
+    (Action::SwapSpeaker, "swap_speaker", &["`"]),
+    (Action::Zen, "zen", &["Z"]),
+];
+
+/// A key plus the modifiers that matter for it. Shift is folded into the
+/// character for printable keys (`J`, `+`), since terminals disagree on
+/// whether they also report it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyCombo {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyCombo {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Parse `j`, `J`, `space`, `f5`, `ctrl+d`, `shift+up`, `+` and the like.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut rest = spec.trim();
+        let mut modifiers = KeyModifiers::NONE;
+        loop {
+            let lower = rest.to_lowercase();
+            let (flag, len) = if lower.starts_with("ctrl+") && rest.len() > 5 {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift+") && rest.len() > 6 {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            modifiers |= flag;
+            rest = &rest[len..];
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                f => KeyCode::F(f.strip_prefix('f')?.parse().ok().filter(|n| (1..=12).contains(n))?),
+            },
+        };
+        Some(Self::new(code, modifiers))
+    }
+}
+
+impl From<&KeyEvent> for KeyCombo {
+    fn from(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+/// Which action each key combo triggers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: HashMap<KeyCombo, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut bindings = HashMap::new();
+        for (action, _, keys) in DEFAULTS {
+            for key in *keys {
+                bindings.insert(KeyCombo::parse(key).expect("stock binding parses"), *action);
+            }
+        }
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// The stock bindings with `overrides` (action name → keys, from config's
+    /// `keybindings`) applied. An overridden action answers only to its new
+    /// keys, which are taken from whatever held them before. Unknown actions,
+    /// unparseable keys and keys claimed twice are skipped and reported.
+    pub fn with_overrides(overrides: &HashMap<String, Vec<String>>) -> (Self, Vec<String>) {
+        let mut keymap = Self::default();
+        let mut warnings = vec![];
+        let mut claimed: HashMap<KeyCombo, &str> = HashMap::new();
+        // Sorted so warnings (and which duplicate wins) don't depend on map order
+        let mut names: Vec<&String> = overrides.keys().collect();
+        names.sort();
+        for name in names {
+            let Some((action, _, _)) = DEFAULTS.iter().find(|(_, n, _)| n == name) else {
+                warnings.push(format!("unknown action \"{}\"", name));
+                continue;
+            };
+            keymap.bindings.retain(|_, a| a != action);
+            for spec in &overrides[name] {
+                let Some(combo) = KeyCombo::parse(spec) else {
+                    warnings.push(format!("can't read key \"{}\" for {}", spec, name));
+                    continue;
+                };
+                if let Some(other) = claimed.insert(combo, name) {
+                    warnings.push(format!("\"{}\" is bound to both {} and {}", spec, other, name));
+                    claimed.insert(combo, other);
+                    continue;
+                }
+                keymap.bindings.insert(combo, *action);
+            }
+        }
+        (keymap, warnings)
+    }
+
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings.get(&KeyCombo::from(key)).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_defaults_match_stock_keys() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action(&press(KeyCode::Char('j'), KeyModifiers::NONE)), Some(Action::Down));
+        assert_eq!(keymap.action(&press(KeyCode::Char(' '), KeyModifiers::NONE)), Some(Action::PlayPause));
+        assert_eq!(keymap.action(&press(KeyCode::F(5), KeyModifiers::NONE)), Some(Action::Refresh));
+        assert_eq!(keymap.action(&press(KeyCode::Up, KeyModifiers::SHIFT)), Some(Action::MoveUp));
+        // Terminals may or may not report Shift alongside an uppercase letter
+        assert_eq!(keymap.action(&press(KeyCode::Char('R'), KeyModifiers::SHIFT)), Some(Action::Refresh));
+        assert_eq!(keymap.action(&press(KeyCode::Char('R'), KeyModifiers::NONE)), Some(Action::Refresh));
+        assert_eq!(keymap.action(&press(KeyCode::Char('j'), KeyModifiers::CONTROL)), None);
+    }
+
+    #[test]
+    fn test_parse_key_specs() {
+        let combo = |code, modifiers| Some(KeyCombo::new(code, modifiers));
+        assert_eq!(KeyCombo::parse("shift+j"), combo(KeyCode::Char('J'), KeyModifiers::NONE));
+        assert_eq!(KeyCombo::parse("Ctrl+d"), combo(KeyCode::Char('d'), KeyModifiers::CONTROL));
+        assert_eq!(KeyCombo::parse("ctrl++"), combo(KeyCode::Char('+'), KeyModifiers::CONTROL));
+        assert_eq!(KeyCombo::parse("PageDown"), combo(KeyCode::PageDown, KeyModifiers::NONE));
+        assert_eq!(KeyCombo::parse("f12"), combo(KeyCode::F(12), KeyModifiers::NONE));
+        assert_eq!(KeyCombo::parse("f13"), None);
+        assert_eq!(KeyCombo::parse("hyper"), None);
+        assert_eq!(KeyCombo::parse(""), None);
+    }
+
+    #[test]
+    fn test_overrides_replace_an_actions_keys_and_steal_from_others() {
+        let overrides = HashMap::from([
+            ("down".to_string(), vec!["J".to_string()]),
+            ("up".to_string(), vec!["K".to_string(), "k".to_string()]),
+        ]);
+        let (keymap, warnings) = Keymap::with_overrides(&overrides);
+        assert!(warnings.is_empty());
+        let key = |c| keymap.action(&press(KeyCode::Char(c), KeyModifiers::NONE));
+        assert_eq!(key('J'), Some(Action::Down));
+        assert_eq!(key('j'), None);
+        assert_eq!(key('K'), Some(Action::Up));
+        assert_eq!(key('k'), Some(Action::Up));
+        assert_eq!(keymap.action(&press(KeyCode::Up, KeyModifiers::NONE)), None);
+        assert_eq!(key('q'), Some(Action::Quit));
+    }
+
+    #[test]
+    fn test_bad_overrides_warn_and_are_skipped() {
+        let overrides = HashMap::from([
+            ("fly".to_string(), vec!["w".to_string()]),
+            ("next".to_string(), vec!["N".to_string(), "warp".to_string()]),
+            ("prev".to_string(), vec!["N".to_string()]),
+        ]);
+        let (keymap, warnings) = Keymap::with_overrides(&overrides);
+        assert_eq!(warnings, vec![
+            "unknown action \"fly\"",
+            "can't read key \"warp\" for next",
+            "\"N\" is bound to both next and prev",
+        ]);
+        assert_eq!(keymap.action(&press(KeyCode::Char('N'), KeyModifiers::SHIFT)), Some(Action::Next));
+    }
+}
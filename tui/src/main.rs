@@ -1,235 +1,745 @@
 mod api;
 mod app;
+mod clock;
 mod command;
 mod history;
+mod keymap;
+mod prefs;
 mod ui;
 
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
     execute,
 };
 use ratatui::prelude::*;
-use crate::api::{ApiClient, Speaker};
-use crate::app::App;
+use crate::api::{ApiClient, SonosApi, Speaker};
+use crate::app::{App, Linger, OsdKind};
+use crate::keymap::Action;
 
 const POLL_INTERVAL: Duration = Duration::from_secs(2);
 const TICK_RATE: Duration = Duration::from_millis(100);
+/// Once quitting, teardown gets this long before the watchdog restores the
+/// terminal and exits on its own (a second Ctrl-C skips the wait).
+const FORCE_EXIT_GRACE: Duration = Duration::from_secs(3);
+/// How long the startup probe waits for a TCP connect to a local sonosd.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+/// Minimum gap between terminal title updates, so we don't emit every tick.
+const TITLE_THROTTLE: Duration = Duration::from_secs(1);
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mini = parse_mini(args.iter().cloned())?;
+    let profile = parse_flag(args.iter().cloned(), "--profile", "a name", "--profile kitchen")?;
+    let dry_run = args.iter().any(|a| a == "--dry-run");
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    terminal.draw(|f| ui::draw_splash(f))?;
-    std::thread::sleep(std::time::Duration::from_secs(1));
+    // A glanceable pane shouldn't make you wait for the splash
+    if mini.is_none() {
+        terminal.draw(ui::draw_splash)?;
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
 
-    let result = run(&mut terminal).await;
+    let result = run(&mut terminal, mini, profile, dry_run).await;
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableBracketedPaste, DisableMouseCapture, LeaveAlternateScreen)?;
 
     result
 }
 
-async fn run(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> Result<()> {
-    let client = Arc::new(ApiClient::new());
+async fn run(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    mini: Option<String>,
+    profile: Option<String>,
+    dry_run: bool,
+) -> Result<()> {
+    let client = Arc::new(ApiClient::from_env()?);
     let mut app = App::new();
+    app.mini = mini;
+    app.dry_run = dry_run;
+    let prefs = prefs::load();
+    app.dashboard_mode = prefs.dashboard_mode;
+    app.help_bar = prefs.help_bar;
+    app.playlist_order = prefs.playlist_order;
+    app.command_history = prefs.command_history;
+    app.local_sort = prefs.playlist_sort.filter(|s| app::LOCAL_SORTS.contains(&s.as_str()));
+    if let Some(dnd) = prefs.do_not_disturb {
+        app.do_not_disturb = dnd;
+        app.do_not_disturb_saved = true;
+    }
 
     match client.get_speakers().await {
-        Ok(speakers) => app.speakers = speakers,
-        Err(_) => app.set_status("The gates of Moria are sealed. Start sonosd.", 3600),
-    }
-    if let Ok(playlists) = client.get_playlists().await {
-        app.playlists = playlists;
+        Ok(speakers) => app.set_speakers(speakers),
+        Err(e) => {
+            // A local daemon can be told apart: not running, or running but unwell
+            let addr = client.local_addr();
+            let up = match &addr {
+                Some(addr) => listening(addr).await,
+                None => false,
+            };
+            app.daemon_unreachable(&startup_failure_status(&e, addr.as_deref().map(|a| (a, up))));
+        }
     }
+    let mut playlists = client.get_playlists().await.unwrap_or_default();
     if let Ok(favs) = client.get_favorites().await {
-        let existing: std::collections::HashSet<String> = app.playlists
-            .iter()
-            .map(|p| p.favorite_name.to_lowercase())
-            .collect();
-        for title in favs {
-            if !existing.contains(&title.to_lowercase()) {
-                app.playlists.push(crate::api::Playlist {
-                    alias: title.clone(),
-                    favorite_name: title,
-                });
-            }
-        }
+        app::merge_favorites(&mut playlists, favs);
     }
 
-    if let Ok(sort) = client.get_playlist_sort().await {
-        if sort == "popularity" {
-            history::popularity_sort(&mut app.playlists);
-        }
-    }
+    let config = client.get_config().await.unwrap_or_default();
+    app.apply_config(&config);
+    app.set_playlists(playlists);
 
     // Load podcasts
     if let Ok(podcasts) = client.get_podcasts().await {
         app.podcasts = podcasts;
     }
-    // Load skip config
-    if let Ok((fwd, back)) = client.get_skip_config().await {
-        app.skip_forward = fwd;
-        app.skip_back = back;
+    // Older daemons have no /capabilities; features are then learned from 404s
+    app.capabilities = client.capabilities().await.ok();
+
+    // A mini pane is pinned to its speaker and keeps no session
+    let profile = prefs::session_profile(profile.as_deref(), |name| std::env::var(name).ok());
+    if app.mini.is_none() {
+        app.restore_session(&prefs::load_session(&profile), config.default_speaker.is_empty());
+        select_default_speaker(&mut app, &config.default_speaker);
     }
+    run_startup(&mut app, &*client, &config.on_startup).await;
 
-    // Background refresh — never blocks the event loop
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<Vec<Speaker>>(1);
+    // Background refresh — never blocks the event loop. A mini pane whose
+    // speaker was found polls just that one. Failures are sent too, so an
+    // outage (and the recovery from it) shows up in the status line.
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<(Instant, Result<Vec<Speaker>>)>(1);
     let refresh_client = Arc::clone(&client);
+    let only = app.mini_speaker().map(|s| s.id());
     tokio::spawn(async move {
         loop {
             tokio::time::sleep(POLL_INTERVAL).await;
-            if let Ok(speakers) = refresh_client.get_speakers().await {
-                let _ = tx.send(speakers).await;
-            }
+            let started = Instant::now();
+            let polled = match &only {
+                Some(id) => refresh_client.get_speaker(id).await,
+                None => refresh_client.get_speakers().await,
+            };
+            let _ = tx.send((started, polled)).await;
         }
     });
 
+    let mut last_title = String::new();
+    let mut last_title_at = app.clock.now();
+
     loop {
+        app.note_selection();
         terminal.draw(|f| ui::draw(f, &app))?;
 
+        if app.terminal_title && app.clock.now().duration_since(last_title_at) >= TITLE_THROTTLE {
+            let title = app.title_text();
+            if title != last_title {
+                execute!(terminal.backend_mut(), SetTitle(&title))?;
+                last_title = title;
+            }
+            last_title_at = app.clock.now();
+        }
+
         // Apply any fresh speaker data without blocking
-        if let Ok(speakers) = rx.try_recv() {
-            app.speakers = speakers;
+        if let Ok((fetched_at, polled)) = rx.try_recv() {
+            apply_poll(&mut app, &*client, fetched_at, polled).await;
+            let resume = app.resume_point();
+            finish_listening(app.listening_ended(), resume);
+            if let Some((id, position)) = app.resume_due() {
+                let result = client.seek(&id, position).await;
+                if !lacks(&mut app, "seek", &result) && result.is_ok() {
+                    app.set_status(format!("Picking up the tale at {}.", ui::format_time(position)), Linger::Normal);
+                }
+            }
+        }
+
+        app.expire_osd();
+        for (id, volume) in app.wheel_volumes_due() {
+            let _ = client.set_volume(&id, volume).await;
+            log_volume(&mut app, &id, volume);
         }
 
         // Check sleep timer expiry
         if let Some(sleep_until) = app.sleep_until {
-            if std::time::Instant::now() >= sleep_until {
+            if app.clock.now() >= sleep_until {
                 app.sleep_until = None;
-                for sp in &app.speakers {
-                    let id = sp.alias.as_deref().unwrap_or(&sp.name).to_string();
-                    let _ = client.pause(&id).await;
+                let ids = app.all_targets();
+                app.remember_sleep_paused(&ids);
+                for id in &ids {
+                    let _ = client.pause(id).await;
                 }
-                app.set_status("The Fellowship rests. All speakers paused.", 5);
+                app.set_status("The Fellowship rests. All speakers paused.", Linger::Long);
             }
         }
+        // Look ahead in the queue only when a track is about to end
+        if let Some(id) = app.next_up_request() {
+            let current_title = app.selected_speaker()
+                .and_then(|s| s.track.as_ref())
+                .map(|t| t.title.clone())
+                .unwrap_or_default();
+            let queue = client.get_queue(&id, 1).await;
+            if queue.as_ref().is_err_and(api::is_unsupported) {
+                app.mark_unsupported("queue");
+            }
+            let track = queue.ok().and_then(|q| q.into_iter().next());
+            app.next_up = Some(app::NextUp { speaker: id, current_title, track });
+        }
+
+        let actions = app.tick_sleep_timers();
+        send_sleep_actions(&*client, actions).await;
 
         if event::poll(TICK_RATE)? {
-            if let Event::Key(key) = event::read()? {
-                handle_key(&mut app, &client, key).await?;
+            match event::read()? {
+                // A key or click that ends the screensaver only wakes the screen
+                Event::Key(_) | Event::Mouse(_) if app.screensaver_active() => app.last_activity = app.clock.now(),
+                Event::Key(key) => {
+                    app.last_activity = app.clock.now();
+                    handle_key(&mut app, &*client, key).await?
+                }
+                Event::Mouse(mouse) => {
+                    app.last_activity = app.clock.now();
+                    handle_mouse(&mut app, &*client, mouse).await
+                }
+                Event::Paste(text) => {
+                    app.last_activity = app.clock.now();
+                    handle_paste(&mut app, &text);
+                }
+                // Repaint from scratch at the new size right away instead of
+                // diffing against a buffer laid out for the old one
+                Event::Resize(_, _) => {
+                    terminal.autoresize()?;
+                    terminal.clear()?;
+                }
+                _ => {}
             }
         }
 
         if app.should_quit {
+            arm_force_exit();
+            if app.mini.is_none() {
+                prefs::save_session(&profile, &app.session());
+            }
+            let resume = app.resume_point();
+            finish_listening(app.end_listening(), resume);
             break;
         }
     }
 
+    if !last_title.is_empty() {
+        execute!(terminal.backend_mut(), SetTitle(app::DEFAULT_TITLE))?;
+    }
+
     Ok(())
 }
 
-async fn execute_command(app: &mut App, client: &ApiClient, input: &str) -> Result<()> {
+/// Status for a daemon we couldn't talk to: wrong credentials or not running.
+fn unreachable_status(e: &anyhow::Error) -> &'static str {
+    if api::is_unauthorized(e) {
+        "Speak, friend, and enter — sonosd refused our credentials. Check SONOSD_TOKEN / SONOSD_USER."
+    } else {
+        "The gates of Moria are sealed. Start sonosd."
+    }
+}
+
+/// Whether anything accepts a TCP connection at `addr` within `PROBE_TIMEOUT`.
+async fn listening(addr: &str) -> bool {
+    matches!(tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(addr)).await, Ok(Ok(_)))
+}
+
+/// Why the first `get_speakers` failed. `probe` is a local daemon's address
+/// and whether anything listens there; remote daemons get the usual message.
+fn startup_failure_status(e: &anyhow::Error, probe: Option<(&str, bool)>) -> String {
+    match probe {
+        _ if api::is_unauthorized(e) => unreachable_status(e).to_string(),
+        Some((addr, false)) => format!("Nothing is listening on {} — is sonosd running? Start it with `sonosd`.", addr),
+        Some((addr, true)) => format!("Something answers on {}, but not as sonosd should: {}", addr, e),
+        None => unreachable_status(e).to_string(),
+    }
+}
+
+/// Take in one background poll. Failures count towards an outage; the first
+/// success after one re-fetches what a restarted sonosd may have changed.
+async fn apply_poll(app: &mut App, client: &impl SonosApi, fetched_at: Instant, polled: Result<Vec<Speaker>>) {
+    let speakers = match polled {
+        Ok(speakers) => speakers,
+        Err(e) => {
+            app.poll_failed(unreachable_status(&e));
+            return;
+        }
+    };
+    if app.poll_succeeded() {
+        if let Ok(config) = client.get_config().await {
+            app.apply_config(&config);
+        }
+        app.capabilities = client.capabilities().await.ok();
+        if let Ok(mut playlists) = client.get_playlists().await {
+            if let Ok(favs) = client.get_favorites().await {
+                app::merge_favorites(&mut playlists, favs);
+            }
+            app.set_playlists(playlists);
+        }
+    }
+    app.apply_polled(fetched_at, speakers);
+    app.confirm_start(fetched_at);
+}
+
+/// `resync`: have sonosd rediscover speakers (daemons without `/resync` only
+/// reload config), re-fetch everything, and log what had drifted.
+async fn resync(app: &mut App, client: &impl SonosApi) {
+    if app.supports("resync") {
+        let result = client.resync().await;
+        if result.as_ref().is_err_and(api::is_unsupported) {
+            app.mark_unsupported("resync");
+        }
+    }
+    if !app.supports("resync") {
+        let _ = client.reload().await;
+    }
+    let before = app.speakers.clone();
+    let started = app.clock.now();
+    match client.get_speakers().await {
+        Ok(speakers) => {
+            app.set_speakers(speakers);
+            app.refreshed_at = Some(started);
+        }
+        Err(e) => {
+            app.set_status(unreachable_status(&e), Linger::Error);
+            return;
+        }
+    }
+    if let Ok(config) = client.get_config().await {
+        app.apply_config(&config);
+    }
+    app.capabilities = client.capabilities().await.ok();
+    if let Ok(mut playlists) = client.get_playlists().await {
+        if let Ok(favs) = client.get_favorites().await {
+            app::merge_favorites(&mut playlists, favs);
+        }
+        app.set_playlists(playlists);
+    }
+    let changes = app::describe_resync(&before, &app.speakers);
+    for change in &changes {
+        app.log_event(format!("Resync: {}", change));
+    }
+    match changes.as_slice() {
+        [] => app.set_status(format!("All is as it was — {} rooms, nothing had drifted.", app.speakers.len()), Linger::Normal),
+        [one] => app.set_status(format!("Resynced: {}", one), Linger::Long),
+        many => app.set_status(format!("Resynced: {} things had drifted — see the log (L).", many.len()), Linger::Long),
+    }
+}
+
+/// Fetch speakers and playlists right now, out of band from the poller.
+async fn refresh(app: &mut App, client: &impl SonosApi) {
+    let started = app.clock.now();
+    match client.get_speakers().await {
+        Ok(speakers) => {
+            app.set_speakers(speakers);
+            app.refreshed_at = Some(started);
+        }
+        Err(e) => {
+            app.set_status(unreachable_status(&e), Linger::Error);
+            return;
+        }
+    }
+    if let Ok(mut playlists) = client.get_playlists().await {
+        if let Ok(favs) = client.get_favorites().await {
+            app::merge_favorites(&mut playlists, favs);
+        }
+        app.set_playlists(playlists);
+    }
+    app.set_status("Refreshed.", Linger::Brief);
+}
+
+async fn send_sleep_actions(client: &impl SonosApi, actions: Vec<app::SleepAction>) {
+    for action in actions {
+        match action {
+            app::SleepAction::SetVolume(id, v) => { let _ = client.set_volume(&id, v).await; }
+            app::SleepAction::Pause(id) => { let _ = client.pause(&id).await; }
+        }
+    }
+}
+
+/// Read `--mini <speaker>` from the command line; other arguments are ignored.
+fn parse_mini(args: impl Iterator<Item = String>) -> Result<Option<String>> {
+    parse_flag(args, "--mini", "a speaker name", "--mini \"Living Room\"")
+}
+
+/// The value after `flag` on the command line, if the flag is there; it must
+/// have one (`what`, as `example` shows).
+fn parse_flag(mut args: impl Iterator<Item = String>, flag: &str, what: &str, example: &str) -> Result<Option<String>> {
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return match args.next() {
+                Some(value) => Ok(Some(value)),
+                None => Err(anyhow::anyhow!("{} needs {}, e.g. {}", flag, what, example)),
+            };
+        }
+    }
+    Ok(None)
+}
+
+/// The few actions a `--mini` pane answers to: play/pause, skip, volume, quit.
+fn is_mini_action(action: Option<Action>) -> bool {
+    matches!(
+        action,
+        Some(Action::PlayPause | Action::Next | Action::Prev | Action::VolumeUp | Action::VolumeDown | Action::Quit)
+    )
+}
+
+/// Bracketed paste: the text goes into whichever prompt is open, its lines
+/// joined by spaces and control characters dropped, so a pasted newline never
+/// submits. Volume input keeps only digits, up to its three. Ignored with no prompt open.
+fn handle_paste(app: &mut App, text: &str) {
+    let joined = || text.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join(" ");
+    if app.history_search.is_some() {
+        let joined = joined();
+        app.edit_history_query(|q| q.extend(joined.chars().filter(|c| !c.is_control())));
+    } else if let Some(input) = app.command_input.as_mut() {
+        input.extend(joined().chars().filter(|c| !c.is_control()));
+    } else if let Some(input) = app.volume_input.as_mut() {
+        let room = if input == "m" { 0 } else { 3usize.saturating_sub(input.trim_start_matches(['+', '-']).len()) };
+        input.extend(text.chars().filter(char::is_ascii_digit).take(room));
+    }
+}
+
+/// Run a line typed (or found with Ctrl-R) at the `:` prompt, and remember it.
+async fn run_from_prompt(app: &mut App, client: &impl SonosApi, line: &str) -> Result<()> {
+    if app.remember_command(line) {
+        let history = app.command_history.clone();
+        prefs::update(|p| p.command_history = history);
+    }
+    execute_command(app, client, line, app.dry_run).await
+}
+
+fn is_ctrl_c(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(std::io::stdout(), DisableBracketedPaste, DisableMouseCapture, LeaveAlternateScreen);
+}
+
+/// Watchdog for a hung shutdown: force the terminal back to normal and exit
+/// after `FORCE_EXIT_GRACE`, or at once on another Ctrl-C. A clean exit ends
+/// the process first, taking this thread with it.
+fn arm_force_exit() {
+    std::thread::spawn(|| {
+        let deadline = Instant::now() + FORCE_EXIT_GRACE;
+        while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+            if let Ok(true) = event::poll(left.min(TICK_RATE)) {
+                if let Ok(Event::Key(key)) = event::read() {
+                    if is_ctrl_c(&key) {
+                        break;
+                    }
+                }
+            }
+        }
+        restore_terminal();
+        std::process::exit(130);
+    });
+}
+
+/// Start on config's `default_speaker`, so `play` (and `on_startup`) target
+/// it until the user picks another room. Says which room was chosen.
+fn select_default_speaker(app: &mut App, name: &str) {
+    if name.is_empty() || app.speakers.is_empty() {
+        return;
+    }
+    let found = app.select_speaker_named(name);
+    let Some(chosen) = app.speaker_name() else { return };
+    if found {
+        app.set_status(format!("{} awaits your command.", chosen), Linger::Normal);
+    } else {
+        app.set_status(format!("{} is not found in this network — {} answers instead.", name, chosen), Linger::Long);
+    }
+}
+
+/// Run the configured `on_startup` command line once, after the first fetch.
+/// Never fatal: problems land in the status line.
+async fn run_startup(app: &mut App, client: &impl SonosApi, command: &str) {
+    if command.trim().is_empty() {
+        return;
+    }
+    if app.speakers.is_empty() {
+        // Keep a "start sonosd" message if that's why there's nobody to command
+        if app.status_message.is_none() {
+            app.set_status("The startup rite found no speakers to stir.", Linger::Long);
+        }
+        return;
+    }
+    if let Err(e) = execute_command(app, client, command, app.dry_run).await {
+        app.set_status(format!("The startup rite faltered: {}", e), Linger::Error);
+    }
+}
+
+/// Run a command line; `;` chains several, e.g. `mute all; unmute kitchen`.
+/// With `dry_run`, or for a step asked as a question (`play? jazz`), nothing
+/// is sent: the status line says what would have happened instead.
+async fn execute_command(app: &mut App, client: &impl SonosApi, input: &str, dry_run: bool) -> Result<()> {
+    let mut would = Vec::new();
+    for part in input.split(';').filter(|p| !p.trim().is_empty()) {
+        match as_question(part) {
+            Some(step) => would.push(dry_run_step(app, &step)),
+            None if dry_run => would.push(dry_run_step(app, part)),
+            None => execute_one(app, client, part).await?,
+        }
+    }
+    if !would.is_empty() {
+        app.set_status(format!("Would {}.", would.join("; then ")), Linger::Long);
+    }
+    Ok(())
+}
+
+/// `play? jazz` → `play jazz`: a command word ending in `?` asks what it would do.
+fn as_question(part: &str) -> Option<String> {
+    let part = part.trim();
+    let (word, rest) = part.split_once(' ').unwrap_or((part, ""));
+    let word = word.strip_suffix('?').filter(|w| !w.is_empty())?;
+    Some(format!("{} {}", word, rest).trim_end().to_string())
+}
+
+/// What one command would do, resolved the way `execute_one` resolves it.
+fn dry_run_step(app: &App, input: &str) -> String {
+    use command::Command;
+    let speaker_names = |ids: &[String]| {
+        ids.iter()
+            .map(|id| app.speakers.iter().find(|s| s.id() == *id).map_or(id.as_str(), |s| s.display_name()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match command::parse(input) {
+        Some(Command::Play(name)) => match app.resolve_play(&name) {
+            Some((_, _, Some(alias))) if app.play_target(&name).is_none() && !app.play_group_targets().is_empty() => {
+                let ids = app.play_group_targets();
+                format!("group {}, then play {} on {}", speaker_names(&ids), alias, speaker_names(&ids[..1]))
+            }
+            Some((_, target, Some(alias))) => format!("play {} on {}", alias, target),
+            Some((_, _, None)) => format!("play nothing — no playlist answers to \"{}\"", name),
+            None => "play nothing — there is no speaker to play on".to_string(),
+        },
+        Some(Command::PlayIndex(n)) => match (n.checked_sub(1).and_then(|i| app.playlists.get(i)), app.speaker_name()) {
+            (Some(pl), Some(target)) => format!("play {} on {}", pl.alias, target),
+            (None, _) => format!("play nothing — there is no #{}", n),
+            (_, None) => "play nothing — there is no speaker to play on".to_string(),
+        },
+        Some(Command::Volume(target, v)) => match volume_targets(app, target.as_deref()) {
+            ids if ids.is_empty() => "set no volume — there is no speaker selected".to_string(),
+            ids => format!("set {} to volume {}", speaker_names(&ids), v),
+        },
+        Some(Command::VolumeBy(target, step)) => match volume_targets(app, target.as_deref()) {
+            ids if ids.is_empty() => "set no volume — there is no speaker selected".to_string(),
+            ids => format!("step {} by {:+}", speaker_names(&ids), step),
+        },
+        Some(_) => format!("run `{}`", input.trim()),
+        None => format!("do nothing — `{}` is not a command", input.trim()),
+    }
+}
+
+/// Where a `vol` status says the change went, after "Volume set to 30":
+/// nothing for the selection, " on 3 speakers in upstairs" for a zone
+/// (noting a room it shadows), else " on <name>".
+fn volume_scope(app: &App, target: Option<&str>, count: usize) -> String {
+    match target {
+        None => String::new(),
+        Some("all") => " on all speakers".to_string(),
+        Some(name) if app.zone_members(name).is_some() => {
+            let shadowed = app.speakers.iter().any(|s| s.display_name().eq_ignore_ascii_case(name) || s.name.eq_ignore_ascii_case(name));
+            let speakers = if count == 1 { "speaker" } else { "speakers" };
+            let note = if shadowed { " (the zone, not the room of that name)" } else { "" };
+            format!(" on {} {} in {}{}", count, speakers, name, note)
+        }
+        Some(name) => format!(" on {}", name),
+    }
+}
+
+/// Which speakers `vol [target]` sets: the selected one, everyone, the
+/// group of a zone from config (a zone wins over a room of the same name),
+/// or every room answering to the name (passed on as-is for the daemon to
+/// resolve if we don't know it).
+fn volume_targets(app: &App, target: Option<&str>) -> Vec<String> {
+    match target {
+        None => app.speaker_id().into_iter().collect(),
+        Some("all") => app.all_targets(),
+        Some(name) if app.zone_members(name).is_some() => app.zone_members(name).unwrap_or_default(),
+        Some(name) => {
+            let matched: Vec<String> = app.speakers.iter()
+                .filter(|s| s.display_name() == name || s.name == name)
+                .map(|s| s.id())
+                .collect();
+            if matched.is_empty() { vec![name.to_string()] } else { matched }
+        }
+    }
+}
+
+async fn execute_one(app: &mut App, client: &impl SonosApi, input: &str) -> Result<()> {
     use command::Command;
     match command::parse(input) {
         Some(Command::Play(name)) => {
-            if let Some(id) = app.speaker_id() {
-                let playlist = app.playlists.iter().find(|p| {
-                    p.alias.to_lowercase().contains(&name.to_lowercase())
-                        || p.favorite_name.to_lowercase().contains(&name.to_lowercase())
-                });
-                if let Some(pl) = playlist {
-                    let alias = pl.alias.clone();
-                    let _ = client.play(&id, &alias).await;
-                    history::record_play(&alias);
-                    app.set_status(format!("Playing {} on {}", alias, id), 3);
-                } else {
-                    app.set_status("Not all those who wander are found in this network.", 4);
-                }
+            // `play <zone or room> <playlist>` goes to that group's coordinator
+            let Some((id, target, alias)) = app.resolve_play(&name) else { return Ok(()) };
+            let Some(alias) = alias else {
+                app.set_status("Not all those who wander are found in this network.", Linger::Long);
+                return Ok(());
+            };
+            // A named zone or room is explicit; anything else gathers `play_group`
+            let gather = app.play_target(&name).is_none();
+            start_play(app, client, id, target, &alias, gather).await;
+        }
+        Some(Command::PlaySolo(name)) => {
+            let Some(alias) = app.find_playlist(&name).map(|pl| pl.alias.clone()) else {
+                app.set_status("Not all those who wander are found in this network.", Linger::Long);
+                return Ok(());
+            };
+            if let (Some(id), Some(speaker_name)) = (app.speaker_id(), app.speaker_name()) {
+                start_play(app, client, id, speaker_name, &alias, false).await;
             }
         }
-        Some(Command::Volume(target, v)) => {
-            let ids: Vec<String> = match target.as_deref() {
-                None => app.speaker_id().into_iter().collect(),
-                Some("all") => app.speakers.iter()
-                    .map(|s| s.alias.as_deref().unwrap_or(&s.name).to_string())
-                    .collect(),
-                Some(name) => vec![name.to_string()],
+        Some(Command::PlayIndex(n)) => {
+            let Some(pl) = n.checked_sub(1).and_then(|i| app.playlists.get(i)) else {
+                app.set_status(format!("The library holds but {} scrolls — there is no #{}.", app.playlists.len(), n), Linger::Long);
+                return Ok(());
             };
+            let alias = pl.alias.clone();
+            if let (Some(id), Some(speaker_name)) = (app.speaker_id(), app.speaker_name()) {
+                start_play(app, client, id, speaker_name, &alias, true).await;
+            }
+        }
+        Some(Command::Volume(target, v)) => {
+            let ids = volume_targets(app, target.as_deref());
             if !ids.is_empty() {
                 for id in &ids {
                     let _ = client.set_volume(id, v).await;
                 }
                 for sp in &mut app.speakers {
-                    let sp_id = sp.alias.as_deref().unwrap_or(&sp.name).to_string();
-                    if ids.contains(&sp_id) {
+                    if ids.contains(&sp.id()) {
                         sp.volume = v;
                     }
                 }
                 let status = if v == 100 {
                     "You shall not pass... 100.".to_string()
                 } else {
-                    match target.as_deref() {
-                        None => format!("Volume set to {}.", v),
-                        Some("all") => format!("Volume set to {} on all speakers.", v),
-                        Some(name) => format!("Volume set to {} on {}.", v, name),
-                    }
+                    format!("Volume set to {}{}.", v, volume_scope(app, target.as_deref(), ids.len()))
                 };
-                app.set_status(status, 2);
+                app.set_status(status, Linger::Brief);
+            }
+        }
+        Some(Command::VolumeBy(target, step)) => {
+            // Each speaker moves from its own level, so only ones we know can step
+            let mut stepped = 0;
+            for id in volume_targets(app, target.as_deref()) {
+                let Some(sp) = app.speakers.iter().find(|s| s.id() == id) else { continue };
+                let volume = (sp.volume as i16 + step).clamp(0, 100) as u8;
+                let _ = client.set_volume(&id, volume).await;
+                app.set_volume(&id, volume);
+                stepped += 1;
+            }
+            if stepped == 0 {
+                app.set_status("Not all those who wander are found in this network.", Linger::Long);
+            } else {
+                let way = if step < 0 { "down" } else { "up" };
+                let scope = volume_scope(app, target.as_deref(), stepped);
+                app.set_status(format!("Volume {} {}{}.", way, step.unsigned_abs(), scope), Linger::Brief);
             }
         }
         Some(Command::GroupAll) => {
-            let _ = client.group_all().await;
-            app.set_status("The fellowship is assembled.", 3);
+            group_all(app, client).await;
         }
         Some(Command::Ungroup) => {
             let _ = client.ungroup_all().await;
-            app.set_status("The company is scattered to the winds.", 3);
+            app.set_status("The company is scattered to the winds.", Linger::Normal);
         }
         Some(Command::Next) => {
             if let Some(id) = app.speaker_id() {
                 match client.next(&id).await {
-                    Ok(()) => app.set_status("Onward, into shadow.", 2),
-                    Err(_) => app.set_status("The road goes ever on — but not to the next track.", 3),
+                    Ok(()) => app.set_status("Onward, into shadow.", Linger::Brief),
+                    Err(_) => app.set_status("The road goes ever on — but not to the next track.", Linger::Error),
                 }
             }
         }
         Some(Command::Prev) => {
             if let Some(id) = app.speaker_id() {
                 match client.previous(&id).await {
-                    Ok(()) => app.set_status("Back to the beginning.", 2),
-                    Err(_) => app.set_status("The road goes ever on — but not to the previous track.", 3),
+                    Ok(()) => app.set_status("Back to the beginning.", Linger::Brief),
+                    Err(_) => app.set_status("The road goes ever on — but not to the previous track.", Linger::Error),
                 }
             }
         }
         Some(Command::Sleep(mins)) => {
             app.sleep_until = Some(
-                std::time::Instant::now()
-                    + std::time::Duration::from_secs(mins as u64 * 60)
+                app.clock.now() + std::time::Duration::from_secs(mins as u64 * 60)
             );
         }
+        Some(Command::SleepAt(hour, minute)) => {
+            let secs = clock::seconds_until(app.clock.local_seconds_of_day(), hour, minute);
+            app.sleep_until = Some(app.clock.now() + std::time::Duration::from_secs(secs));
+            app.set_status(format!("The Palantir will dim at {}:{:02}.", hour, minute), Linger::Normal);
+        }
+        Some(Command::SleepWith(mins, opts)) => {
+            let target = if opts.here {
+                match app.selected_speaker() {
+                    Some(sp) => Some((app.coordinator_of(sp).id(), sp.display_name().to_string())),
+                    None => {
+                        app.set_status("Not all those who wander are found in this network.", Linger::Long);
+                        return Ok(());
+                    }
+                }
+            } else {
+                None
+            };
+            let (target, label) = match target {
+                Some((id, name)) => (Some(id), name),
+                None => (None, "All".to_string()),
+            };
+            app.set_status(
+                format!("{} will sleep in {} min{}.", label, mins, if opts.fade { ", fading" } else { "" }),
+                Linger::Normal,
+            );
+            app.sleep_timers.push(app::SleepTimer {
+                target,
+                label,
+                until: app.clock.now() + std::time::Duration::from_secs(mins as u64 * 60),
+                fade: opts.fade,
+                faded_from: vec![],
+            });
+        }
+        Some(Command::SleepAdjust(mins)) => match app.adjust_sleep(mins) {
+            Some(left) => app.set_status(
+                format!("The Palantir will dim in {} min.", left.as_secs().div_ceil(60)),
+                Linger::Normal,
+            ),
+            None => app.set_status("No slumber is coming to lengthen or shorten — set one with :sleep <mins>.", Linger::Normal),
+        },
         Some(Command::SleepCancel) => {
-            app.sleep_until = None;
-            app.set_status("The Palantir's dream is dispelled — sleep cancelled.", 3);
+            let restores = app.cancel_sleep();
+            send_sleep_actions(client, restores).await;
+            app.set_status("The Palantir's dream is dispelled — sleep cancelled.", Linger::Normal);
         }
         Some(Command::Reload) => {
             let _ = client.reload().await;
-            if let Ok(playlists) = client.get_playlists().await {
-                app.playlists = playlists;
-            }
-            if let Ok(favs) = client.get_favorites().await {
-                let existing: std::collections::HashSet<String> = app.playlists
-                    .iter()
-                    .map(|p| p.favorite_name.to_lowercase())
-                    .collect();
-                for title in favs {
-                    if !existing.contains(&title.to_lowercase()) {
-                        app.playlists.push(crate::api::Playlist {
-                            alias: title.clone(),
-                            favorite_name: title,
-                        });
-                    }
+            if let Ok(mut playlists) = client.get_playlists().await {
+                if let Ok(favs) = client.get_favorites().await {
+                    app::merge_favorites(&mut playlists, favs);
                 }
+                app.set_playlists(playlists);
             }
-            app.set_status("The scrolls are refreshed. Reloaded config.yaml.", 3);
+            if let Ok(config) = client.get_config().await {
+                app.apply_config(&config);
+            }
+            app.set_status("The scrolls are refreshed. Reloaded config.yaml.", Linger::Normal);
         }
+        Some(Command::Resync) => resync(app, client).await,
         Some(Command::Source) => {
             app.toggle_source();
         }
@@ -238,7 +748,7 @@ async fn execute_command(app: &mut App, client: &ApiClient, input: &str) -> Resu
             if let Ok(podcasts) = client.get_podcasts().await {
                 app.podcasts = podcasts;
             }
-            app.set_status("The distant voices are refreshed — feeds updated.", 3);
+            app.set_status("The distant voices are refreshed — feeds updated.", Linger::Normal);
         }
         Some(Command::Mark) => {
             if let Some(ep) = app.selected_episode() {
@@ -255,22 +765,715 @@ async fn execute_command(app: &mut App, client: &ApiClient, input: &str) -> Resu
                 }
                 app.set_status(
                     if new_played { "Marked as heard." } else { "Marked as unheard." },
-                    2,
+                    Linger::Brief,
                 );
             }
         }
-        Some(Command::Unknown(_)) | None => {
-            app.set_status("Speak, friend — but speak clearly.", 3);
+        Some(Command::Pause(target)) => {
+            set_play_state(app, client, target.as_deref(), Transport::Pause).await;
+        }
+        Some(Command::Resume(target)) => {
+            set_play_state(app, client, target.as_deref(), Transport::Resume).await;
+        }
+        Some(Command::Stop(target)) => {
+            set_play_state(app, client, target.as_deref(), Transport::Stop).await;
+        }
+        Some(Command::Toggle) => {
+            match app.selected_speaker().map(|s| s.state == "PLAYING") {
+                Some(true) => set_play_state(app, client, None, Transport::Pause).await,
+                Some(false) => set_play_state(app, client, None, Transport::Resume).await,
+                None => app.set_status("No voice answers — there is no speaker to wake.", Linger::Normal),
+            }
+        }
+        Some(Command::Undo) if app.silenced.is_some() => {
+            let silenced = app.silenced.take().unwrap_or_default();
+            for id in &silenced.unmuted {
+                let _ = client.set_mute(id, false).await;
+                app.set_muted(id, false);
+            }
+            for id in &silenced.playing {
+                let _ = client.resume(id).await;
+                app.set_group_state(id, "PLAYING");
+            }
+            app.set_status("The silence is lifted — all is as it was.", Linger::Normal);
+        }
+        Some(Command::Undo) => {
+            let woken = std::mem::take(&mut app.sleep_paused);
+            if woken.is_empty() {
+                app.set_status("Nothing to undo — no slumber has fallen.", Linger::Normal);
+            } else {
+                for id in &woken {
+                    let _ = client.resume(id).await;
+                    app.set_group_state(id, "PLAYING");
+                }
+                app.set_status(format!("The Palantir's slumber is undone — {} awaken.", woken.len()), Linger::Normal);
+            }
+        }
+        Some(Command::Party) => party(app, client).await,
+        Some(Command::PartyOff) => {
+            let _ = client.ungroup_all().await;
+            let restores = app.end_party();
+            for (id, v) in &restores {
+                let _ = client.set_volume(id, *v).await;
+            }
+            app.set_status("The feast is over — the company goes home, voices lowered.", Linger::Normal);
+        }
+        Some(Command::Mute { target, action }) => {
+            set_mute_state(app, client, target.as_deref(), action).await;
+        }
+        Some(Command::Balance(target)) => balance(app, client, target.as_deref()).await,
+        Some(Command::Log) => app.toggle_log(),
+        Some(Command::Stats) => app.stats = Some(history::load()),
+        Some(Command::Recap) => app.recap = Some(history::load()),
+        Some(Command::Why) => match app.selected_playlist() {
+            Some(p) => app.why = Some((p.alias.clone(), history::load())),
+            None => app.set_status("No scroll is chosen to question.", Linger::Normal),
+        },
+        Some(Command::List) => app.roster = Some(0),
+        Some(Command::Like) => rate_track(app, client, api::Thumb::Up).await,
+        Some(Command::Dislike) => rate_track(app, client, api::Thumb::Down).await,
+        Some(Command::Follow(on)) => set_follow(app, on.unwrap_or(!app.follow_mode)),
+        Some(Command::Detail(target)) => {
+            let speaker = match target.as_deref() {
+                Some(q) => app.find_speaker(q),
+                None => app.selected_speaker(),
+            };
+            match speaker.map(|s| s.id()) {
+                Some(id) => app.detail = Some(id),
+                None => app.set_status("Not all those who wander are found in this network.", Linger::Long),
+            }
+        }
+        Some(Command::SaveTrack(playlist)) => {
+            let Some(id) = app.selected_speaker().map(|sp| app.coordinator_of(sp).id()) else {
+                app.set_status("Not all those who wander are found in this network.", Linger::Long);
+                return Ok(());
+            };
+            if !require(app, "playlist_add") {
+                return Ok(());
+            }
+            let result = client.add_track_to_playlist(&id, &playlist).await;
+            if lacks(app, "playlist_add", &result) {
+                return Ok(());
+            }
+            match result {
+                Ok(title) => app.set_status(format!("Inscribed in {}: {}", playlist, title), Linger::Long),
+                Err(e) if api::is_no_track(&e) => {
+                    app.set_status("This music flows like the Anduin — there is no single track to keep.", Linger::Long)
+                }
+                Err(e) => app.set_status(format!("The way is shut: {}", e), Linger::Error),
+            }
+        }
+        Some(Command::Sort) => {
+            app.cycle_sort();
+            let local = app.local_sort.clone();
+            prefs::update(|p| p.playlist_sort = local);
+            if app.local_sort.is_none() {
+                // Back to the daemon's order: it may have changed since startup
+                if let Ok(config) = client.get_config().await {
+                    app.apply_config(&config);
+                }
+            }
+            let whose = if app.local_sort.is_some() { "local" } else { "from sonosd" };
+            app.set_status(format!("The scrolls are ordered anew: {} ({}).", app.active_sort(), whose), Linger::Normal);
+        }
+        Some(Command::HistoryClear) => {
+            if app.confirm_history_clear() {
+                match history::clear() {
+                    Ok(()) => {
+                        app.history_changed();
+                        app.set_status("The annals are burned — no play is remembered.", Linger::Normal);
+                    }
+                    Err(e) => app.set_status(format!("The annals would not burn: {}", e), Linger::Error),
+                }
+            }
+        }
+        Some(Command::HistoryDrop) => {
+            let entries = history::load();
+            match entries.last().map(|e| e.playlist.clone()) {
+                None => app.set_status("The annals are already empty.", Linger::Normal),
+                Some(alias) => match history::delete(entries.len() - 1) {
+                    Ok(_) => {
+                        app.history_changed();
+                        app.set_status(format!("Struck from the annals: {}", alias), Linger::Normal);
+                    }
+                    Err(e) => app.set_status(format!("The annals would not yield: {}", e), Linger::Error),
+                },
+            }
+        }
+        Some(Command::Bring(name)) => bring(app, client, &name).await,
+        Some(Command::Unknown(_)) | None => {
+            app.set_status("Speak, friend — but speak clearly.", Linger::Normal);
+        }
+    }
+    Ok(())
+}
+
+/// `bring <speaker>`: that room takes up the selection's music (`bring_source`).
+/// It joins a playing group; beside a solo room playing a known playlist it
+/// starts the same playlist on its own instead.
+async fn bring(app: &mut App, client: &impl SonosApi, name: &str) {
+    let Some(target) = app.find_speaker(name) else {
+        app.set_status("Not all those who wander are found in this network.", Linger::Long);
+        return;
+    };
+    let (id, target_name) = (target.id(), target.display_name().to_string());
+    let Some(source) = app.bring_source() else {
+        app.set_status("No music stirs in any hall — there is nothing to bring.", Linger::Normal);
+        return;
+    };
+    let (coord_id, coord_name) = (source.id(), source.display_name().to_string());
+    if app.in_group_of(target, source) {
+        app.set_status(format!("{} already hears {}'s song.", target_name, coord_name), Linger::Normal);
+        return;
+    }
+    let solo = !app.speakers.iter().any(|s| s.id() != coord_id && app.in_group_of(s, source));
+    let playlist = app.listening.as_ref().filter(|l| l.speaker == coord_id).map(|l| l.playlist.clone());
+    match playlist.filter(|_| solo) {
+        Some(alias) => match client.play(&id, &alias).await {
+            Ok(_) => app.set_status(format!("{} takes up {} beside {}.", target_name, alias, coord_name), Linger::Normal),
+            Err(e) => app.set_status(format!("The way is shut: {}", e), Linger::Error),
+        },
+        None => {
+            let _ = client.group(&[coord_id.clone(), id.clone()]).await;
+            app.set_membership(&id, Some(&coord_id));
+            app.set_status(format!("The music follows you — {} joins {}'s fellowship.", target_name, coord_name), Linger::Normal);
+        }
+    }
+}
+
+/// Record the end of a timed play: how long it lasted and, for
+/// `resume_playlists`, where it left off.
+fn finish_listening(listened: Option<Duration>, resume: Option<(String, history::ResumePoint)>) {
+    let Some(listened) = listened else { return };
+    history::finish_play(listened);
+    if let Some((alias, point)) = resume {
+        history::save_resume(&alias, point);
+    }
+}
+
+/// Start `alias` on speaker `id`, then apply its preferred volume if configured.
+/// Only an accepted play counts toward history;
+/// a daemon rejection (usually a favorite renamed since we last loaded) is shown instead.
+async fn play_playlist(app: &mut App, client: &impl SonosApi, id: &str, speaker_name: &str, alias: &str) {
+    // Read before playing replaces the track the previous play stopped on
+    let resume = app.resume_point();
+    match client.play(id, alias).await {
+        Ok(()) => {
+            finish_listening(app.begin_listening(id, alias), resume);
+            history::record_play(alias, &*app.clock);
+            if app.resume_playlists.iter().any(|a| a == alias) && app.supports("seek") {
+                if let Some(point) = history::take_resume(alias) {
+                    app.await_resume(id, alias, point);
+                }
+            }
+            app.wake_from_sleep(id); // new music replaces whatever the timer paused
+            let volume = app.playlist_volume(alias);
+            if let Some(vol) = volume {
+                let _ = client.set_volume(id, vol).await;
+                app.set_volume(id, vol);
+            }
+            // The next polls confirm the start without holding up the UI
+            app.await_start(id, speaker_name, alias, volume);
+        }
+        Err(e) if api::is_unauthorized(&e) => app.set_status(unreachable_status(&e), Linger::Error),
+        Err(e) => app.set_status(format!("The way is shut: {}. Try :reload.", e), Linger::Error),
+    }
+}
+
+/// The `party` macro: gather `party_targets`, start `party.playlist` on the
+/// leader, then set `party.volume` everywhere — each step optional.
+async fn party(app: &mut App, client: &impl SonosApi) {
+    let ids = app.party_targets();
+    let Some(leader) = ids.first().cloned() else {
+        app.set_status("No guests answer the invitation — the party is cancelled.", Linger::Normal);
+        return;
+    };
+    if ids.len() > 1 {
+        let _ = client.group(&ids).await;
+        app.set_status("The company gathers…", Linger::Brief);
+    }
+    if let Some(alias) = app.party.playlist.clone() {
+        let name = app.speakers.iter().find(|s| s.id() == leader)
+            .map(|s| s.display_name().to_string())
+            .unwrap_or_default();
+        play_playlist(app, client, &leader, &name, &alias).await;
+    }
+    if let Some(volume) = app.party.volume {
+        for (id, v) in app.begin_party(&ids, volume) {
+            let _ = client.set_volume(&id, v).await;
+        }
+    }
+    app.set_status(format!("A long-expected party! {} speakers join the revels.", ids.len()), Linger::Long);
+}
+
+/// Start `alias` on `id`, or, with `gather` and `play_group` set, on the
+/// first online room of that set once it's gathered. A repeat within the
+/// play cooldown does nothing, and gathers nobody.
+async fn start_play(app: &mut App, client: &impl SonosApi, id: String, name: String, alias: &str, gather: bool) {
+    let (id, name) = match app.play_group_leader().filter(|_| gather) {
+        Some(leader) => leader,
+        None => (id, name),
+    };
+    if !app.try_begin_play(&id) {
+        return;
+    }
+    if gather {
+        gather_play_group(app, client).await;
+    }
+    play_playlist(app, client, &id, &name, alias).await;
+}
+
+/// Group the online `play_group` rooms under the first, staging the status;
+/// no call when fewer than two answer or they already stand together.
+async fn gather_play_group(app: &mut App, client: &impl SonosApi) {
+    let ids = app.play_group_targets();
+    let Some(leader) = ids.first().cloned() else { return };
+    let gathered = ids.iter().all(|member| {
+        app.speakers.iter().find(|s| s.id() == *member).is_some_and(|s| app.coordinator_of(s).id() == leader)
+    });
+    if ids.len() > 1 && !gathered {
+        let _ = client.group(&ids).await;
+        for member in &ids {
+            app.set_membership(member, Some(&leader));
+        }
+        app.set_status(format!("The company gathers — {} rooms join the fellowship…", ids.len()), Linger::Brief);
+    }
+}
+
+/// `t`/`T`, `like`/`dislike`: rate the selected group's track. Services that
+/// skip a disliked track do so themselves; the next poll shows what's on.
+/// A daemon without `/rate` gets the same answer as a service without ratings.
+async fn rate_track(app: &mut App, client: &impl SonosApi, thumb: api::Thumb) {
+    const NO_JUDGMENT: &str = "This realm knows no judgment of song.";
+    let Some(id) = app.selected_speaker().map(|sp| app.coordinator_of(sp).id()) else {
+        app.set_status("Not all those who wander are found in this network.", Linger::Long);
+        return;
+    };
+    if !app.supports("rate") {
+        app.set_status(NO_JUDGMENT, Linger::Long);
+        return;
+    }
+    match client.rate(&id, thumb).await {
+        Ok(()) => {
+            let title = app.set_rating(&id, thumb.as_str()).unwrap_or_default();
+            match thumb {
+                api::Thumb::Up => app.set_status(format!("A song worth singing: {}", title), Linger::Normal),
+                api::Thumb::Down => app.set_status(format!("Cast into the fire: {}", title), Linger::Normal),
+            }
+        }
+        Err(e) if api::is_unsupported(&e) => {
+            app.mark_unsupported("rate");
+            app.set_status(NO_JUDGMENT, Linger::Long);
+        }
+        Err(e) if api::is_no_rating(&e) => app.set_status(NO_JUDGMENT, Linger::Long),
+        Err(e) => app.set_status(format!("The way is shut: {}", e), Linger::Error),
+    }
+}
+
+/// `F` / `follow`: turn follow mode on or off, saying which.
+fn set_follow(app: &mut App, on: bool) {
+    app.follow_mode = on;
+    if on {
+        app.set_status("The Palantir will turn to wherever music wakes.", Linger::Normal);
+    } else {
+        app.set_status("The Palantir holds its gaze.", Linger::Normal);
+    }
+}
+
+/// Group editor `space`/`enter`: pull the highlighted room into the edited
+/// group, or send it off on its own if it's already a member.
+async fn toggle_group_member(app: &mut App, client: &impl SonosApi) {
+    let Some((coordinator, sp)) = app.group_edit_pick() else {
+        app.set_status("Not all those who wander are found in this network.", Linger::Long);
+        return;
+    };
+    let (coord_id, coord_name) = (coordinator.id(), coordinator.display_name().to_string());
+    let (id, name) = (sp.id(), sp.display_name().to_string());
+    if id == coord_id {
+        app.set_status(format!("{} leads this fellowship and cannot leave it.", name), Linger::Normal);
+    } else if app.in_group_of(sp, coordinator) {
+        let _ = client.ungroup(&id).await;
+        app.set_membership(&id, None);
+        app.set_status(format!("{} departs from {}'s fellowship.", name, coord_name), Linger::Normal);
+    } else {
+        let _ = client.group(&[coord_id.clone(), id.clone()]).await;
+        app.set_membership(&id, Some(&coord_id));
+        app.set_status(format!("{} joins {}'s fellowship.", name, coord_name), Linger::Normal);
+    }
+}
+
+/// `o`: the selected room alone changes sides. A follower leaves its group;
+/// a solo room joins `App::join_target`. A coordinator stays, as in `G`.
+async fn join_or_leave(app: &mut App, client: &impl SonosApi) {
+    let Some(sp) = app.selected_speaker() else { return };
+    let coord = app.coordinator_of(sp);
+    let (id, name) = (sp.id(), sp.display_name().to_string());
+    if app.group_size(coord) > 1 {
+        let coord_name = coord.display_name().to_string();
+        if coord.id() == id {
+            app.set_status(format!("{} leads this fellowship and cannot leave it.", name), Linger::Normal);
+            return;
+        }
+        let _ = client.ungroup(&id).await;
+        app.set_membership(&id, None);
+        app.set_status(format!("{} departs from {}'s fellowship and walks alone.", name, coord_name), Linger::Normal);
+        return;
+    }
+    let Some(target) = app.join_target(sp) else {
+        app.set_status(format!("There is no fellowship for {} to join — g gathers everyone.", name), Linger::Normal);
+        return;
+    };
+    let (coord_id, coord_name) = (target.id(), target.display_name().to_string());
+    let _ = client.group(&[coord_id.clone(), id.clone()]).await;
+    app.set_membership(&id, Some(&coord_id));
+    let whole = if app.grouping_state() == app::GroupingState::Full { " The fellowship stands as one." } else { "" };
+    app.set_status(format!("{} joins {}'s fellowship.{}", name, coord_name, whole), Linger::Normal);
+}
+
+/// Group everyone, or just the configured `group_all_speakers` that are online.
+async fn group_all(app: &mut App, client: &impl SonosApi) {
+    let state = app.grouping_state();
+    if state == app::GroupingState::Full {
+        app.set_status("Already assembled — the fellowship stands as one.", Linger::Normal);
+        return;
+    }
+    match app.group_targets() {
+        None => {
+            let _ = client.group_all().await;
+        }
+        Some(ids) if ids.len() < 2 => {
+            app.set_status("Too few of the company answer the call to form a fellowship.", Linger::Normal);
+            return;
+        }
+        Some(ids) => {
+            let _ = client.group(&ids).await;
+        }
+    }
+    if state == app::GroupingState::Partial {
+        app.set_status("The stragglers are gathered — the fellowship is assembled.", Linger::Normal);
+    } else {
+        app.set_status("The fellowship is assembled.", Linger::Normal);
+    }
+}
+
+/// Shown when a feature needs an endpoint this daemon doesn't have.
+const TOO_OLD: &str = "Your sonosd is too old for that spell.";
+
+/// Gate an optional daemon feature, explaining why nothing happens if it's absent.
+fn require(app: &mut App, feature: &str) -> bool {
+    if app.supports(feature) {
+        return true;
+    }
+    app.set_status(TOO_OLD, Linger::Normal);
+    false
+}
+
+/// True if `result` shows the daemon lacks `feature`, which is then remembered.
+fn lacks<T>(app: &mut App, feature: &str, result: &Result<T>) -> bool {
+    if !result.as_ref().is_err_and(api::is_unsupported) {
+        return false;
+    }
+    app.mark_unsupported(feature);
+    app.set_status(TOO_OLD, Linger::Normal);
+    true
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Transport {
+    Pause,
+    Resume,
+    Stop,
+}
+
+/// Pause, resume or stop `target` (fuzzy-matched; the selected speaker if None),
+/// sending the command to its group coordinator.
+async fn set_play_state(app: &mut App, client: &impl SonosApi, target: Option<&str>, action: Transport) {
+    if matches!(action, Transport::Stop) && !require(app, "stop") {
+        return;
+    }
+    if target == Some("all") {
+        // One call per group: its coordinator, or the speaker itself if alone
+        let ids: Vec<String> = app.speakers.iter()
+            .filter(|s| !app.excludes(s) && app.coordinator_of(s).id() == s.id())
+            .map(|s| s.id())
+            .collect();
+        for id in &ids {
+            match action {
+                Transport::Resume => { let _ = client.resume(id).await; }
+                Transport::Pause => { let _ = client.pause(id).await; }
+                Transport::Stop => {
+                    if lacks(app, "stop", &client.stop(id).await) {
+                        return;
+                    }
+                }
+            }
+        }
+        let (state, msg) = match action {
+            Transport::Resume => ("PLAYING", "The halls ring with song once more."),
+            Transport::Pause => ("PAUSED_PLAYBACK", "The Fellowship rests. All speakers paused."),
+            Transport::Stop => ("STOPPED", "All music in Middle-earth has ended."),
+        };
+        for id in &ids {
+            app.set_group_state(id, state);
+        }
+        app.set_status(msg, Linger::Brief);
+        return;
+    }
+    let speaker = match target {
+        None => app.selected_speaker(),
+        Some(name) => app.find_speaker(name),
+    };
+    let Some(speaker) = speaker else {
+        app.set_status("Not all those who wander are found in this network.", Linger::Long);
+        return;
+    };
+    let id = app.coordinator_of(speaker).id();
+    let name = speaker.display_name().to_string();
+    match action {
+        Transport::Resume => {
+            let _ = client.resume(&id).await;
+            app.set_group_state(&id, "PLAYING");
+            if app.wake_from_sleep(&id) {
+                app.set_status(format!("Rousing {} from the Palantir's slumber.", name), Linger::Normal);
+            } else {
+                app.set_status(format!("{} sings again.", name), Linger::Brief);
+            }
+        }
+        Transport::Pause => {
+            let _ = client.pause(&id).await;
+            app.set_group_state(&id, "PAUSED_PLAYBACK");
+            app.set_status(format!("Silence falls over {}.", name), Linger::Brief);
+        }
+        Transport::Stop => {
+            if lacks(app, "stop", &client.stop(&id).await) {
+                return;
+            }
+            app.set_group_state(&id, "STOPPED");
+            app.set_status(format!("The music of {} has ended.", name), Linger::Brief);
+        }
+    }
+}
+
+/// `ctrl+x`: pause every group and mute every speaker, no questions asked.
+async fn panic_stop(app: &mut App, client: &impl SonosApi) {
+    let (groups, speakers) = app.silence_everything();
+    for id in &groups {
+        let _ = client.pause(id).await;
+    }
+    if app.supports("mute") {
+        for id in &speakers {
+            let _ = client.set_mute(id, true).await;
+        }
+    }
+    app.set_status("Everything silenced. :undo brings it back.", Linger::Long);
+}
+
+/// Mute, unmute, or toggle the named speaker, `all`, or the selected speaker,
+/// updating the UI before the next poll confirms it.
+async fn set_mute_state(app: &mut App, client: &impl SonosApi, target: Option<&str>, action: command::MuteAction) {
+    use command::MuteAction;
+    if !require(app, "mute") {
+        return;
+    }
+    if target == Some("all") {
+        // Toggling everything mutes unless everything is already muted
+        let ids = app.all_targets();
+        let muted = match action {
+            MuteAction::Mute => true,
+            MuteAction::Unmute => false,
+            MuteAction::Toggle => app.speakers.iter().any(|s| ids.contains(&s.id()) && !s.muted),
+        };
+        if app.do_not_disturb.is_empty() {
+            if lacks(app, "mute", &client.set_mute("all", muted).await) {
+                return;
+            }
+        } else {
+            for id in &ids {
+                let _ = client.set_mute(id, muted).await;
+            }
+        }
+        for id in &ids {
+            app.set_muted(id, muted);
+        }
+        app.solo_restore = None;
+        if muted {
+            app.set_status("A great silence falls over Middle-earth.", Linger::Brief);
+        } else {
+            app.set_status("The halls ring with song once more.", Linger::Brief);
+        }
+        return;
+    }
+    let speaker = match target {
+        None => app.selected_speaker(),
+        Some(name) => app.find_speaker(name),
+    };
+    let Some(speaker) = speaker else {
+        app.set_status("Not all those who wander are found in this network.", Linger::Long);
+        return;
+    };
+    let id = speaker.id();
+    let name = speaker.display_name().to_string();
+    let muted = match action {
+        MuteAction::Mute => true,
+        MuteAction::Unmute => false,
+        MuteAction::Toggle => !speaker.muted,
+    };
+    if lacks(app, "mute", &client.set_mute(&id, muted).await) {
+        return;
+    }
+    app.set_muted(&id, muted);
+    if muted {
+        app.set_status(format!("Silence falls upon {}.", name), Linger::Brief);
+    } else {
+        app.set_status(format!("{} finds its voice again.", name), Linger::Brief);
+    }
+}
+
+/// `balance` / `B`: bring every member of a group to the group's average volume.
+async fn balance(app: &mut App, client: &impl SonosApi, target: Option<&str>) {
+    let speaker = match target {
+        None => app.selected_speaker(),
+        Some(name) => app.find_speaker(name),
+    };
+    let Some(speaker) = speaker else {
+        app.set_status("Not all those who wander are found in this network.", Linger::Long);
+        return;
+    };
+    let name = speaker.display_name().to_string();
+    let Some((ids, average)) = app.balance_plan(speaker) else {
+        app.set_status(format!("{} stands alone — there is nothing to balance.", name), Linger::Normal);
+        return;
+    };
+    for id in &ids {
+        let _ = client.set_volume(id, average).await;
+        app.set_volume(id, average);
+    }
+    app.set_status(format!("The voices of {}'s fellowship are made even at {}.", name, average), Linger::Normal);
+}
+
+/// Volume keys flash the OSD rather than a status, so note them in the log directly.
+fn log_volume(app: &mut App, id: &str, volume: u8) {
+    let name = app.speakers.iter().find(|s| s.id() == id).map(|s| s.display_name().to_string());
+    app.log_event(format!("{} volume {}", name.as_deref().unwrap_or(id), volume));
+}
+
+/// `+`/`-`: step the selected speaker's volume — or the whole zone's, when
+/// Now Playing is focused on a group.
+async fn nudge_volume(app: &mut App, client: &impl SonosApi, step: i16) {
+    let bump = |v: u8| (v as i16 + step).clamp(0, 100) as u8;
+    // Without /group_volume, fall back to stepping the selected speaker alone
+    if let Some(coord) = app.focused_group().filter(|_| app.supports("group_volume")) {
+        let Some(current) = app.group_volume(&coord) else { return };
+        let new_vol = bump(current);
+        if client.set_group_volume(&coord, new_vol).await.is_err_and(|e| api::is_unsupported(&e)) {
+            app.mark_unsupported("group_volume");
+        } else {
+            app.set_group_volume(&coord, new_vol);
+            app.show_osd(OsdKind::Volume, new_vol);
+            log_volume(app, &coord, new_vol);
+            return;
+        }
+    }
+    if let Some(sp) = app.selected_speaker() {
+        let id = sp.id();
+        let new_vol = bump(sp.volume);
+        let _ = client.set_volume(&id, new_vol).await;
+        app.set_volume(&id, new_vol);
+        app.show_osd(OsdKind::Volume, new_vol);
+        log_volume(app, &id, new_vol);
+    }
+}
+
+/// A left click on a Now Playing progress bar seeks to that point in the
+/// track. The wheel turns the Rooms speaker under the pointer up or down, or
+/// scrolls the Playlists panel.
+async fn handle_mouse(app: &mut App, client: &impl SonosApi, mouse: MouseEvent) {
+    let step = match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => None,
+        MouseEventKind::ScrollUp => Some(app::WHEEL_STEP),
+        MouseEventKind::ScrollDown => Some(-app::WHEEL_STEP),
+        _ => return,
+    };
+    if let Some(step) = step {
+        if let Some(id) = app.room_row_at(mouse.column, mouse.row) {
+            if let Some(volume) = app.wheel_volume(&id, step) {
+                let _ = client.set_volume(&id, volume).await;
+                log_volume(app, &id, volume);
+            }
+            if let Some(sp) = app.speakers.iter().find(|s| s.id() == id) {
+                app.show_osd(OsdKind::Volume, sp.volume);
+            }
+        } else if app.playlists_area.get().contains(Position { x: mouse.column, y: mouse.row }) {
+            app.step_source_list(step < 0);
+        }
+        return;
+    }
+    let Some((id, position)) = app.seek_target_at(mouse.column, mouse.row) else { return };
+    let result = client.seek(&id, position).await;
+    if lacks(app, "seek", &result) {
+        return;
+    }
+    if result.is_ok() {
+        if let Some(track) = app.speakers.iter_mut().find(|s| s.id() == id).and_then(|s| s.track.as_mut()) {
+            track.position = position;
         }
     }
-    Ok(())
 }
 
-async fn handle_key(app: &mut App, client: &ApiClient, key: KeyEvent) -> Result<()> {
+async fn handle_key(app: &mut App, client: &impl SonosApi, key: KeyEvent) -> Result<()> {
+    // Raw mode swallows SIGINT, so Ctrl-C arrives as a key — quit via normal teardown
+    if is_ctrl_c(&key) {
+        app.should_quit = true;
+        return Ok(());
+    }
+    let action = app.keymap.action(&key);
+    // The panic stop works from anywhere: prompts, overlays, mini mode
+    if action == Some(Action::PanicStop) {
+        panic_stop(app, client).await;
+        return Ok(());
+    }
+    // A mini pane whose speaker is missing must not steer whichever room is first
+    if app.mini.is_some() && !(is_mini_action(action) && (app.mini_speaker().is_some() || action == Some(Action::Quit))) {
+        return Ok(());
+    }
+
+    // Ctrl-R search takes over the command prompt until it runs, accepts or cancels
+    if app.history_search.is_some() {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('r') if ctrl => app.search_history(),
+            KeyCode::Char(c) if !ctrl => app.edit_history_query(|q| q.push(c)),
+            KeyCode::Backspace => app.edit_history_query(|q| {
+                q.pop();
+            }),
+            KeyCode::Tab | KeyCode::Right => {
+                let found = app.history_match().map(str::to_string);
+                app.cancel_history_search();
+                if let Some(line) = found {
+                    app.command_input = Some(line);
+                }
+            }
+            KeyCode::Enter => match app.history_match().map(str::to_string) {
+                Some(line) => {
+                    app.history_search = None;
+                    app.command_input = None;
+                    run_from_prompt(app, client, &line).await?;
+                }
+                None => app.cancel_history_search(),
+            },
+            KeyCode::Esc => app.cancel_history_search(),
+            _ => {}
+        }
+        return Ok(());
+    }
+
     // Command mode intercepts all keys
     if app.command_input.is_some() {
         match key.code {
-            KeyCode::Char(c) if !key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.search_history();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
                 app.command_input.as_mut().unwrap().push(c);
             }
             KeyCode::Backspace => {
@@ -282,31 +1485,18 @@ async fn handle_key(app: &mut App, client: &ApiClient, key: KeyEvent) -> Result<
                 }
             }
             KeyCode::Tab => {
-                let playlist_names: Vec<String> = app.playlists
-                    .iter()
-                    .map(|p| p.favorite_name.clone())
-                    .collect();
                 let speaker_names: Vec<String> = app.speakers
                     .iter()
-                    .map(|s| s.alias.as_deref().unwrap_or(&s.name).to_string())
+                    .map(|s| s.display_name().to_string())
                     .collect();
                 let current = app.command_input.as_ref().unwrap().clone();
-                if let Some(ghost) = command::autocomplete(&current, &playlist_names, &speaker_names) {
-                    if ghost.starts_with(" → ") {
-                        // contains-match ghost: replace query with full name
-                        let parts: Vec<&str> = current.splitn(2, ' ').collect();
-                        if parts.len() == 2 {
-                            let completed = format!("{} {}", parts[0], &ghost[" → ".len()..]);
-                            *app.command_input.as_mut().unwrap() = completed;
-                        }
-                    } else {
-                        app.command_input.as_mut().unwrap().push_str(&ghost);
-                    }
+                if let Some(ghost) = command::autocomplete(&current, &app.playlists, &speaker_names) {
+                    app.command_input = Some(command::apply_completion(&current, &ghost));
                 }
             }
             KeyCode::Enter => {
                 if let Some(input) = app.command_input.take() {
-                    execute_command(app, client, &input).await?;
+                    run_from_prompt(app, client, &input).await?;
                 }
             }
             KeyCode::Esc => {
@@ -317,26 +1507,87 @@ async fn handle_key(app: &mut App, client: &ApiClient, key: KeyEvent) -> Result<
         return Ok(());
     }
 
+    // Zen mode and the stats, recap, why and speaker detail overlays close on any key
+    if app.zen || app.stats.is_some() || app.recap.is_some() || app.why.is_some() || app.detail.is_some() {
+        app.zen = false;
+        app.stats = None;
+        app.recap = None;
+        app.why = None;
+        app.detail = None;
+        return Ok(());
+    }
+
+    // The speaker table scrolls; any other key closes it
+    if app.roster.is_some() {
+        match (key.code, app.keymap.action(&key)) {
+            (KeyCode::PageUp, _) => app.scroll_roster(-10),
+            (KeyCode::PageDown, _) => app.scroll_roster(10),
+            (_, Some(Action::Up)) => app.scroll_roster(-1),
+            (_, Some(Action::Down)) => app.scroll_roster(1),
+            _ => app.roster = None,
+        }
+        return Ok(());
+    }
+
+    // The event log overlay scrolls until dismissed
+    if app.log_open {
+        match (key.code, app.keymap.action(&key)) {
+            (KeyCode::PageUp, _) => app.scroll_log(10),
+            (KeyCode::PageDown, _) => app.scroll_log(-10),
+            (_, Some(Action::Up)) => app.scroll_log(1),
+            (_, Some(Action::Down)) => app.scroll_log(-1),
+            (_, Some(Action::Back | Action::EventLog)) => app.toggle_log(),
+            _ => {}
+        }
+        return Ok(());
+    }
+
+    // The group editor picks rooms until dismissed
+    if app.group_edit.is_some() {
+        match app.keymap.action(&key) {
+            Some(Action::Up) => app.move_group_edit(-1),
+            Some(Action::Down) => app.move_group_edit(1),
+            Some(Action::Select | Action::PlayPause) => toggle_group_member(app, client).await,
+            Some(Action::Back | Action::EditGroup) => app.group_edit = None,
+            _ => {}
+        }
+        return Ok(());
+    }
+
     // Volume input mode intercepts all keys
     if app.volume_input.is_some() {
         match key.code {
             KeyCode::Char(c) if c.is_ascii_digit() => {
                 let input = app.volume_input.as_mut().unwrap();
-                if input.len() < 3 {
+                let digits = input.trim_start_matches(['+', '-']).len();
+                if digits < 3 && input != "m" {
                     input.push(c);
                 }
             }
+            // A leading sign steps, a lone `m` mutes
+            KeyCode::Char(c @ ('+' | '-' | 'm')) if app.volume_input.as_ref().is_some_and(|i| i.is_empty()) => {
+                app.volume_input.as_mut().unwrap().push(c);
+            }
             KeyCode::Backspace => {
                 app.volume_input.as_mut().unwrap().pop();
             }
             KeyCode::Enter => {
                 if let Some(input) = app.volume_input.take() {
-                    // Empty or non-numeric input silently cancels (same as Esc)
-                    if let Ok(vol) = input.parse::<u8>() {
-                        let vol = vol.min(100);
-                        if let Some(id) = app.speaker_id() {
-                            let _ = client.set_volume(&id, vol).await;
+                    // Empty or unreadable input silently cancels (same as Esc)
+                    match command::parse_volume_entry(&input) {
+                        Some(command::VolumeEntry::Set(vol)) => {
+                            if let Some(id) = app.speaker_id() {
+                                let _ = client.set_volume(&id, vol).await;
+                                app.set_volume(&id, vol);
+                                app.show_osd(OsdKind::Volume, vol);
+                                log_volume(app, &id, vol);
+                            }
                         }
+                        Some(command::VolumeEntry::Change(step)) => nudge_volume(app, client, step).await,
+                        Some(command::VolumeEntry::Mute) if app.selected_speaker().is_some() => {
+                            set_mute_state(app, client, None, command::MuteAction::Toggle).await;
+                        }
+                        _ => {}
                     }
                 }
             }
@@ -348,18 +1599,50 @@ async fn handle_key(app: &mut App, client: &ApiClient, key: KeyEvent) -> Result<
         return Ok(());
     }
 
-    match key.code {
-        KeyCode::Char('q') => app.should_quit = true,
-        KeyCode::Tab => app.cycle_panel(),
+    let Some(action) = action else {
+        // Digits always jump to a speaker; they aren't remappable
+        if let KeyCode::Char(c @ '1'..='9') = key.code {
+            app.jump_to_speaker(c as usize - '0' as usize);
+        }
+        return Ok(());
+    };
+    apply_action(app, client, action).await
+}
+
+/// Carry out `action` for the selected speaker / panel — the behavior behind
+/// every remappable key, independent of which key (or other input) asked.
+async fn apply_action(app: &mut App, client: &impl SonosApi, action: Action) -> Result<()> {
+    match action {
+        Action::Quit => app.request_quit(),
+        Action::CyclePanel if !app.dashboard_mode => app.cycle_panel(),
 
-        KeyCode::Up | KeyCode::Char('k') => app.prev_in_list(),
-        KeyCode::Down | KeyCode::Char('j') => app.next_in_list(),
+        Action::MoveUp | Action::MoveDown
+            if app.active_panel == app::Panel::Playlists
+                && app.source_mode == app::SourceMode::Playlists =>
+        {
+            let delta = if action == Action::MoveUp { -1 } else { 1 };
+            if app.move_playlist(delta) {
+                let order = app.playlist_order.clone();
+                prefs::update(|p| p.playlist_order = order);
+                if app.active_sort() != "manual" {
+                    app.set_status("Order remembered — set playlist_sort: manual to keep it.", Linger::Normal);
+                }
+            }
+        }
+        Action::Up | Action::MoveUp => app.prev_in_list(),
+        Action::Down | Action::MoveDown => app.next_in_list(),
 
-        KeyCode::Enter => {
-            if app.source_mode == crate::app::SourceMode::Podcasts && app.active_panel == crate::app::Panel::Playlists {
+        Action::Select => {
+            if app.active_panel == crate::app::Panel::NowPlaying {
+                app.expanded_now_playing = !app.expanded_now_playing;
+            } else if app.source_mode == crate::app::SourceMode::Podcasts && app.active_panel == crate::app::Panel::Playlists {
                 if app.podcast_drill {
                     // Play the selected episode
                     if let (Some(speaker_id), Some(episode)) = (app.speaker_id(), app.selected_episode()) {
+                        let episode = episode.clone();
+                        if !app.try_begin_play(&speaker_id) {
+                            return Ok(());
+                        }
                         let title = episode.title.clone();
                         let url = episode.url.clone();
                         let ep_id = episode.id.clone();
@@ -369,7 +1652,7 @@ async fn handle_key(app: &mut App, client: &ApiClient, key: KeyEvent) -> Result<
                             let _ = client.seek(&speaker_id, position).await;
                         }
                         app.current_episode_id = Some(ep_id);
-                        app.set_status(format!("Playing: {}", title), 3);
+                        app.set_status(format!("Playing: {}", title), Linger::Normal);
                     }
                 } else {
                     // Drill into episode list
@@ -385,20 +1668,26 @@ async fn handle_key(app: &mut App, client: &ApiClient, key: KeyEvent) -> Result<
             } else if let (Some(speaker_id), Some(playlist)) =
                 (app.speaker_id(), app.selected_playlist())
             {
-                let _ = client.play(&speaker_id, &playlist.alias).await;
-                history::record_play(&playlist.alias);
-                app.set_status(format!("Playing {} on {}", playlist.alias, speaker_id), 3);
+                let alias = playlist.alias.clone();
+                let speaker_name = app.speaker_name().unwrap_or_default();
+                start_play(app, client, speaker_id, speaker_name, &alias, true).await;
             }
         }
 
-        KeyCode::Char(' ') => {
+        Action::PlayPause => {
             if let Some(sp) = app.selected_speaker() {
-                let id = sp.alias.as_deref().unwrap_or(&sp.name).to_string();
+                let id = sp.id();
                 let is_playing = sp.state == "PLAYING";
                 let position = sp.track.as_ref().map(|t| t.position).unwrap_or(0);
+                let name = sp.display_name().to_string();
                 match sp.state.as_str() {
                     "PLAYING" => { let _ = client.pause(&id).await; }
-                    _ => { let _ = client.resume(&id).await; }
+                    _ => {
+                        let _ = client.resume(&id).await;
+                        if app.wake_from_sleep(&id) {
+                            app.set_status(format!("Rousing {} from the Palantir's slumber.", name), Linger::Normal);
+                        }
+                    }
                 }
                 // Save podcast progress on pause
                 if is_playing {
@@ -409,90 +1698,158 @@ async fn handle_key(app: &mut App, client: &ApiClient, key: KeyEvent) -> Result<
             }
         }
 
-        KeyCode::Char('+') | KeyCode::Char('=') => {
-            if let Some(sp) = app.selected_speaker() {
-                let id = sp.alias.as_deref().unwrap_or(&sp.name).to_string();
-                let new_vol = (sp.volume + 5).min(100);
-                let _ = client.set_volume(&id, new_vol).await;
+        Action::VolumeUp => nudge_volume(app, client, 5).await,
+        Action::VolumeDown => nudge_volume(app, client, -5).await,
+
+        Action::Mute if app.selected_speaker().is_some() => {
+            set_mute_state(app, client, None, command::MuteAction::Toggle).await;
+        }
+        Action::Balance => balance(app, client, None).await,
+        Action::EventLog => app.toggle_log(),
+        Action::CenterSelected => {
+            if let Some(name) = app.speaker_name() {
+                app.center_rooms.set(true);
+                app.set_status(format!("{} stands at the heart of the Rooms.", name), Linger::Brief);
             }
         }
-        KeyCode::Char('-') => {
-            if let Some(sp) = app.selected_speaker() {
-                let id = sp.alias.as_deref().unwrap_or(&sp.name).to_string();
-                let new_vol = sp.volume.saturating_sub(5);
-                let _ = client.set_volume(&id, new_vol).await;
+        Action::SelectPlaying => match app.select_playing() {
+            Some(name) => app.set_status(format!("The Palantir turns to {}.", name), Linger::Brief),
+            None => app.set_status("No music stirs in any hall.", Linger::Brief),
+        },
+        Action::SwapSpeaker => match app.swap_speaker() {
+            Some(name) => app.set_status(format!("Back to {}.", name), Linger::Brief),
+            None => app.set_status("There is no road back — no other room to return to.", Linger::Brief),
+        },
+        Action::MuteAll => {
+            set_mute_state(app, client, Some("all"), command::MuteAction::Toggle).await;
+        }
+        Action::Solo => {
+            if app.solo_restore.is_some() {
+                for (id, muted) in app.end_solo() {
+                    let _ = client.set_mute(&id, muted).await;
+                }
+                app.set_status("The chorus returns.", Linger::Brief);
+            } else if let Some(name) = app.speaker_name().filter(|_| require(app, "mute")) {
+                for (target, muted) in app.begin_solo() {
+                    let _ = client.set_mute(&target, muted).await;
+                }
+                app.set_status(format!("Only {} sings — the others hold their breath.", name), Linger::Normal);
             }
         }
 
-        KeyCode::Char('n') => {
+        Action::Next => {
             if let Some(id) = app.speaker_id() {
                 match client.next(&id).await {
-                    Ok(()) => app.set_status("Onward, into shadow.", 2),
-                    Err(_) => app.set_status("The road goes ever on — but not to the next track.", 3),
+                    Ok(()) => app.set_status("Onward, into shadow.", Linger::Brief),
+                    Err(_) => app.set_status("The road goes ever on — but not to the next track.", Linger::Error),
                 }
             }
         }
-        KeyCode::Char('p') => {
+        Action::Prev => {
             if let Some(id) = app.speaker_id() {
                 match client.previous(&id).await {
-                    Ok(()) => app.set_status("Back to the beginning.", 2),
-                    Err(_) => app.set_status("The road goes ever on — but not to the previous track.", 3),
+                    Ok(()) => app.set_status("Back to the beginning.", Linger::Brief),
+                    Err(_) => app.set_status("The road goes ever on — but not to the previous track.", Linger::Error),
                 }
             }
         }
 
-        KeyCode::Char('f') | KeyCode::Right => {
-            if app.is_podcast_playing() {
-                if let Some(id) = app.speaker_id() {
-                    let _ = client.skip(&id, app.skip_forward).await;
-                }
+        Action::SkipForward if app.is_podcast_playing() => {
+            if let Some(id) = app.speaker_id() {
+                let _ = client.skip(&id, app.skip_forward).await;
             }
         }
-        KeyCode::Char('b') | KeyCode::Left => {
-            if app.is_podcast_playing() {
-                if let Some(id) = app.speaker_id() {
-                    let _ = client.skip(&id, -app.skip_back).await;
-                }
+        Action::SkipBack if app.is_podcast_playing() => {
+            if let Some(id) = app.speaker_id() {
+                let _ = client.skip(&id, -app.skip_back).await;
             }
         }
 
-        KeyCode::Char('g') => {
-            if app.is_grouped() {
+        // A partial grouping is finished first; only a full one is broken up
+        Action::Group => {
+            if app.grouping_state() == app::GroupingState::Full {
                 let _ = client.ungroup_all().await;
+                app.set_status("The company is scattered to the winds.", Linger::Normal);
             } else {
-                let _ = client.group_all().await;
+                group_all(app, client).await;
+            }
+        }
+
+        Action::JoinLeave => join_or_leave(app, client).await,
+
+        Action::EditGroup => {
+            let opened = app.open_group_edit();
+            if !opened {
+                app.set_status("Focus Now Playing to gather or release its company.", Linger::Normal);
             }
         }
 
-        KeyCode::Char('v') => {
+        Action::VolumeInput => {
             app.volume_input = Some(String::new());
         }
 
-        KeyCode::Char('s') => {
+        Action::Refresh => {
+            refresh(app, client).await;
+        }
+
+        Action::DoNotDisturb => {
+            if let (Some(dnd), Some(name)) = (app.toggle_do_not_disturb(), app.speaker_name()) {
+                let list = app.do_not_disturb.clone();
+                prefs::update(|p| p.do_not_disturb = Some(list));
+                if dnd {
+                    app.set_status(format!("{} is warded — no party shall disturb it.", name), Linger::Normal);
+                } else {
+                    app.set_status(format!("{} rejoins the company.", name), Linger::Normal);
+                }
+            }
+        }
+
+        Action::Stop => {
+            set_play_state(app, client, None, Transport::Stop).await;
+        }
+
+        Action::ToggleSource => {
             app.toggle_source();
         }
 
-        KeyCode::Char(':') => {
+        Action::Dashboard => {
+            app.toggle_dashboard();
+            let dashboard_mode = app.dashboard_mode;
+            prefs::update(|p| p.dashboard_mode = dashboard_mode);
+        }
+        Action::Detail => app.detail = app.speaker_id(),
+        Action::Zen => app.zen = true,
+        Action::Follow => set_follow(app, !app.follow_mode),
+        Action::Like => rate_track(app, client, api::Thumb::Up).await,
+        Action::Dislike => rate_track(app, client, api::Thumb::Down).await,
+        Action::HelpBar => {
+            app.help_bar = app.help_bar.next();
+            let help_bar = app.help_bar;
+            prefs::update(|p| p.help_bar = help_bar);
+        }
+
+        Action::Command => {
             app.command_input = Some(String::new());
             app.volume_input = None; // mutually exclusive
         }
-        KeyCode::Char('?') => {
+        Action::Help => {
             app.help_open = !app.help_open;
         }
-        KeyCode::Char('e') => {
+        Action::Episode
             if app.source_mode == crate::app::SourceMode::Podcasts
                 && app.podcast_drill
                 && app.active_panel == crate::app::Panel::Playlists
-                && app.selected_episode().is_some()
-            {
-                app.episode_popup = !app.episode_popup;
-            }
+                && app.selected_episode().is_some() =>
+        {
+            app.episode_popup = !app.episode_popup;
         }
-        KeyCode::Esc => {
+        Action::Back => {
             if app.episode_popup {
                 app.episode_popup = false;
             } else if app.help_open {
                 app.help_open = false;
+            } else if app.expanded_now_playing {
+                app.expanded_now_playing = false;
             } else if app.podcast_drill {
                 app.podcast_drill = false;
             }
@@ -502,3 +1859,994 @@ async fn handle_key(app: &mut App, client: &ApiClient, key: KeyEvent) -> Result<
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Config, MockApi, Playlist, PlaylistSource};
+
+    fn speaker(name: &str, state: &str) -> Speaker {
+        Speaker {
+            uid: format!("RINCON_{}", name),
+            coordinator_uid: None,
+            name: name.to_string(),
+            alias: None,
+            ip: "10.0.0.1".to_string(),
+            volume: 20,
+            muted: false,
+            state: state.to_string(),
+            group_coordinator: None,
+            track: None,
+            bass: None,
+            treble: None,
+            loudness: None,
+            play_mode: None,
+            cross_fade: None,
+            model: None,
+            bonded_to: None,
+            bond: None,
+        }
+    }
+
+    fn app_with(names: &[&str]) -> App {
+        let mut app = App::new();
+        app.set_speakers(names.iter().map(|n| speaker(n, "STOPPED")).collect());
+        app
+    }
+
+    #[tokio::test]
+    async fn test_vol_all_sets_every_speaker() {
+        let mut app = app_with(&["Den", "Kitchen", "Office"]);
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "vol all 30", false).await.unwrap();
+        assert_eq!(api.calls(), vec![
+            "set_volume RINCON_Den 30",
+            "set_volume RINCON_Kitchen 30",
+            "set_volume RINCON_Office 30",
+        ]);
+        assert!(app.speakers.iter().all(|s| s.volume == 30));
+    }
+
+    #[tokio::test]
+    async fn test_vol_on_a_zone_sets_or_steps_its_whole_group() {
+        let mut app = app_with(&["Den", "Kitchen", "Office"]);
+        for sp in app.speakers.iter_mut().filter(|s| s.name != "Office") {
+            sp.coordinator_uid = Some("RINCON_Den".into());
+        }
+        app.zones = std::collections::HashMap::from([
+            ("upstairs".to_string(), vec!["Kitchen".to_string()]),
+            ("office".to_string(), vec!["Den".to_string()]),
+        ]);
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "vol upstairs 30", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["set_volume RINCON_Den 30", "set_volume RINCON_Kitchen 30"]);
+        assert_eq!(app.active_status(), "Volume set to 30 on 2 speakers in upstairs.");
+
+        app.speakers.iter_mut().find(|s| s.name == "Kitchen").unwrap().volume = 97;
+        execute_command(&mut app, &api, "vol office +5", false).await.unwrap();
+        assert_eq!(&api.calls()[2..], ["set_volume RINCON_Den 35", "set_volume RINCON_Kitchen 100"]);
+        assert_eq!(app.active_status(), "Volume up 5 on 2 speakers in office (the zone, not the room of that name).");
+
+        execute_command(&mut app, &api, "vol Office -5", false).await.unwrap();
+        assert_eq!(api.calls().len(), 6, "the zone still wins over the room");
+        execute_command(&mut app, &api, "vol nowhere +5", false).await.unwrap();
+        assert_eq!(api.calls().len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_vol_without_target_sets_selected_speaker() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.speaker_index = 1;
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "vol 45", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["set_volume RINCON_Kitchen 45"]);
+    }
+
+    #[tokio::test]
+    async fn test_reload_fetches_playlists_and_config() {
+        let mut app = app_with(&["Den"]);
+        let api = MockApi {
+            playlists: vec![Playlist { alias: "chill".to_string(), favorite_name: "Chill Vibes".to_string(), source: PlaylistSource::Configured }],
+            config: Config { wrap_navigation: false, ..Config::default() },
+            ..MockApi::default()
+        };
+        execute_command(&mut app, &api, "reload", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["reload"]);
+        assert_eq!(app.playlists.len(), 1);
+        assert!(!app.wrap_navigation);
+    }
+
+    #[tokio::test]
+    async fn test_resync_rediscovers_and_logs_what_drifted() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        let mut porch = speaker("Porch", "STOPPED");
+        porch.coordinator_uid = Some("RINCON_Den".into());
+        let api = MockApi { speakers: vec![speaker("Den", "STOPPED"), porch], ..MockApi::default() };
+        execute_command(&mut app, &api, "resync", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["resync"]);
+        assert_eq!(app.active_status(), "Resynced: 2 things had drifted — see the log (L).");
+        let log: Vec<&str> = app.event_log.iter().map(|e| e.text.as_str()).filter(|t| t.starts_with("Resync:")).collect();
+        assert_eq!(log, vec!["Resync: Porch answers the call.", "Resync: Kitchen has passed into the West."]);
+
+        // A daemon without /resync still gets its config reloaded
+        let api = MockApi { speakers: app.speakers.clone(), missing: vec!["resync"], ..MockApi::default() };
+        execute_command(&mut app, &api, "resync", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["resync", "reload"]);
+        assert_eq!(app.active_status(), "All is as it was — 2 rooms, nothing had drifted.");
+    }
+
+    #[tokio::test]
+    async fn test_pause_by_name_routes_to_coordinator() {
+        let mut app = App::new();
+        let mut den = speaker("Den", "PLAYING");
+        den.coordinator_uid = Some("RINCON_Den".to_string());
+        let mut kitchen = speaker("Kitchen", "PLAYING");
+        kitchen.coordinator_uid = Some("RINCON_Den".to_string());
+        app.set_speakers(vec![den, kitchen]);
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "pause kitch", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["pause RINCON_Den"]);
+        assert!(app.speakers.iter().all(|s| s.state == "PAUSED_PLAYBACK"));
+    }
+
+    #[tokio::test]
+    async fn test_resume_without_name_uses_selection_and_unknown_name_is_ignored() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.speaker_index = 1;
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "resume", false).await.unwrap();
+        execute_command(&mut app, &api, "resume attic", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["resume RINCON_Kitchen"]);
+    }
+
+    #[tokio::test]
+    async fn test_toggle_flips_selected_speaker() {
+        let mut app = App::new();
+        app.set_speakers(vec![speaker("Den", "PLAYING")]);
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "toggle", false).await.unwrap();
+        assert_eq!(app.speakers[0].state, "PAUSED_PLAYBACK");
+        execute_command(&mut app, &api, "toggle", false).await.unwrap();
+        assert_eq!(app.speakers[0].state, "PLAYING");
+        assert_eq!(api.calls(), vec!["pause RINCON_Den", "resume RINCON_Den"]);
+    }
+
+    #[tokio::test]
+    async fn test_toggle_without_speakers_sends_nothing() {
+        let mut app = App::new();
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "toggle", false).await.unwrap();
+        assert!(api.calls().is_empty());
+        assert!(!app.active_status().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_play_into_a_named_zone_targets_its_coordinator() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.speakers[0].coordinator_uid = Some("RINCON_Kitchen".into());
+        app.speakers[1].coordinator_uid = Some("RINCON_Kitchen".into());
+        app.zones = std::collections::HashMap::from([("upstairs".to_string(), vec!["Den".to_string()])]);
+        app.playlists = vec![Playlist { alias: "zen".to_string(), favorite_name: "Zen".to_string(), source: PlaylistSource::Configured }];
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "play Upstairs zen", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["play RINCON_Kitchen zen"]);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_and_questions_report_the_resolution_without_sending() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.playlists = vec![Playlist { alias: "jazz".to_string(), favorite_name: "Jazz Classics".to_string(), source: PlaylistSource::Configured }];
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "play kitchen jzcl; vol all 30", true).await.unwrap();
+        assert!(api.calls().is_empty());
+        assert_eq!(app.active_status(), "Would play jazz on Kitchen; then set Den, Kitchen to volume 30.");
+
+        execute_command(&mut app, &api, "play? polka", false).await.unwrap();
+        assert!(api.calls().is_empty());
+        assert_eq!(app.active_status(), "Would play nothing — no playlist answers to \"polka\".");
+
+        // A question mixes with steps that do run
+        execute_command(&mut app, &api, "p? jazz; vol 10", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["set_volume RINCON_Den 10"]);
+    }
+
+    #[tokio::test]
+    async fn test_play_by_index_uses_displayed_order() {
+        let mut app = app_with(&["Den"]);
+        app.playlists = vec![
+            Playlist { alias: "zen".to_string(), favorite_name: "Zen".to_string(), source: PlaylistSource::Configured },
+            Playlist { alias: "altwave".to_string(), favorite_name: "Alt Wave".to_string(), source: PlaylistSource::Configured },
+        ];
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "play #1", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["play RINCON_Den zen"]);
+
+        execute_command(&mut app, &api, "play #99", false).await.unwrap();
+        assert_eq!(api.calls().len(), 1);
+        assert_eq!(app.active_status(), "The library holds but 2 scrolls — there is no #99.");
+    }
+
+    #[tokio::test]
+    async fn test_save_track_names_the_track_or_explains_streams() {
+        let mut app = app_with(&["Den"]);
+        let mut den = speaker("Den", "PLAYING");
+        den.track = Some(api::Track {
+            title: "Elvis in the Freezer".into(), artist: "Ratboys".into(), album: "GN".into(),
+            duration: 231, position: 83, source: "".into(), quality: "".into(), rating: None,
+        });
+        let api = MockApi { speakers: vec![den.clone()], ..MockApi::default() };
+        execute_command(&mut app, &api, "save-track Keepers", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["add_track_to_playlist RINCON_Den Keepers"]);
+        assert_eq!(app.active_status(), "Inscribed in Keepers: Elvis in the Freezer");
+
+        // A radio stream reports no duration: nothing to keep
+        den.track.as_mut().unwrap().duration = 0;
+        let api = MockApi { speakers: vec![den], ..MockApi::default() };
+        execute_command(&mut app, &api, "save-track Keepers", false).await.unwrap();
+        assert!(app.active_status().contains("no single track"));
+
+        let api = MockApi { missing: vec!["playlist_add"], ..MockApi::default() };
+        execute_command(&mut app, &api, "save-track Keepers", false).await.unwrap();
+        assert_eq!(app.active_status(), TOO_OLD);
+        assert!(!app.supports("playlist_add"));
+    }
+
+    #[tokio::test]
+    async fn test_any_key_leaves_zen_without_acting() {
+        let mut app = app_with(&["Den"]);
+        let api = MockApi::default();
+        handle_key(&mut app, &api, KeyEvent::new(KeyCode::Char('Z'), KeyModifiers::SHIFT)).await.unwrap();
+        assert!(app.zen);
+        handle_key(&mut app, &api, KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE)).await.unwrap();
+        assert!(!app.zen);
+        assert!(api.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_scrolls_with_arrows_and_closes_on_any_other_key() {
+        let mut app = app_with(&["Den", "Kitchen", "Office"]);
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "list", false).await.unwrap();
+        assert_eq!(app.roster, Some(0));
+        for _ in 0..5 {
+            handle_key(&mut app, &api, KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)).await.unwrap();
+        }
+        assert_eq!(app.roster, Some(2), "stops at the last row");
+        handle_key(&mut app, &api, KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE)).await.unwrap();
+        assert_eq!(app.roster, None);
+        assert!(api.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_like_and_dislike_rate_the_selected_track() {
+        let mut den = speaker("Den", "PLAYING");
+        den.track = Some(api::Track {
+            title: "Elvis in the Freezer".into(), artist: "Ratboys".into(), album: "GN".into(),
+            duration: 231, position: 83, source: "".into(), quality: "".into(), rating: None,
+        });
+        let mut app = App::new();
+        app.set_speakers(vec![den.clone()]);
+        let api = MockApi { speakers: vec![den.clone()], ..MockApi::default() };
+        handle_key(&mut app, &api, KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE)).await.unwrap();
+        assert_eq!(api.calls(), vec!["rate RINCON_Den up"]);
+        assert_eq!(app.active_status(), "A song worth singing: Elvis in the Freezer");
+        assert_eq!(app.speakers[0].track.as_ref().unwrap().rating.as_deref(), Some("up"));
+        execute_command(&mut app, &api, "dislike", false).await.unwrap();
+        assert_eq!(app.speakers[0].track.as_ref().unwrap().rating.as_deref(), Some("down"));
+
+        // Radio and friends take no ratings
+        den.track.as_mut().unwrap().duration = 0;
+        let api = MockApi { speakers: vec![den], ..MockApi::default() };
+        execute_command(&mut app, &api, "like", false).await.unwrap();
+        assert_eq!(app.active_status(), "This realm knows no judgment of song.");
+
+        // A daemon without /rate can't judge either, and isn't asked again
+        let api = MockApi { missing: vec!["rate"], ..MockApi::default() };
+        execute_command(&mut app, &api, "like", false).await.unwrap();
+        assert_eq!(app.active_status(), "This realm knows no judgment of song.");
+        execute_command(&mut app, &api, "dislike", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["rate RINCON_Den up"]);
+        assert_eq!(app.active_status(), "This realm knows no judgment of song.");
+    }
+
+    #[tokio::test]
+    async fn test_play_failure_shows_daemon_error() {
+        let mut app = app_with(&["Den"]);
+        app.playlists = vec![Playlist { alias: "chill".to_string(), favorite_name: "Chill Vibes".to_string(), source: PlaylistSource::Configured }];
+        let api = MockApi {
+            play_error: Some("Favorite 'Chill Vibes' not found".to_string()),
+            ..MockApi::default()
+        };
+        execute_command(&mut app, &api, "play chill", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["play RINCON_Den chill"]);
+        let status = app.active_status();
+        assert!(status.contains("Favorite 'Chill Vibes' not found"));
+        assert!(status.contains(":reload"));
+    }
+
+    #[tokio::test]
+    async fn test_play_is_confirmed_by_a_later_poll() {
+        for (state, expected) in [
+            ("PLAYING", "Playing chill on Den"),
+            ("STOPPED", "chill didn't start on Den — is the source available?"),
+        ] {
+            let clock = std::rc::Rc::new(crate::clock::MockClock::new(1_772_000_000));
+            let mut app = App::with_clock(clock.clone());
+            app.set_speakers(vec![speaker("Den", "STOPPED")]);
+            app.playlists = vec![Playlist { alias: "chill".to_string(), favorite_name: "Chill Vibes".to_string(), source: PlaylistSource::Configured }];
+            let api = MockApi::default();
+            execute_command(&mut app, &api, "play chill", false).await.unwrap();
+            assert_eq!(app.active_status(), "Starting chill on Den…");
+
+            // Too soon to call it idle
+            let now = app.clock.now();
+            apply_poll(&mut app, &api, now, Ok(vec![speaker("Den", "STOPPED")])).await;
+            assert_eq!(app.active_status(), "Starting chill on Den…");
+
+            clock.advance(app::PLAY_CONFIRM_DELAY);
+            let now = app.clock.now();
+            apply_poll(&mut app, &api, now, Ok(vec![speaker("Den", state)])).await;
+            assert_eq!(app.active_status(), expected);
+            assert!(app.pending_start.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plus_raises_volume_and_shows_osd() {
+        let mut app = app_with(&["Den"]);
+        let api = MockApi::default();
+        let key = KeyEvent::new(KeyCode::Char('+'), KeyModifiers::NONE);
+        handle_key(&mut app, &api, key).await.unwrap();
+        handle_key(&mut app, &api, key).await.unwrap();
+        assert_eq!(api.calls(), vec!["set_volume RINCON_Den 25", "set_volume RINCON_Den 30"]);
+        assert_eq!(app.osd.map(|(kind, v, _)| (kind, v)), Some((OsdKind::Volume, 30)));
+    }
+
+    #[tokio::test]
+    async fn test_group_all_uses_configured_subset() {
+        let mut app = app_with(&["Bedroom", "Den", "Kitchen"]);
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "group all", false).await.unwrap();
+        app.group_set = vec!["Kitchen".to_string(), "Den".to_string()];
+        execute_command(&mut app, &api, "group all", false).await.unwrap();
+        app.group_set = vec!["Kitchen".to_string(), "Attic".to_string()];
+        execute_command(&mut app, &api, "group all", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["group_all", "group RINCON_Kitchen RINCON_Den"]);
+    }
+
+    #[tokio::test]
+    async fn test_group_all_skips_an_assembled_fellowship_and_completes_a_partial_one() {
+        let mut app = app_with(&["Den", "Kitchen", "Office"]);
+        for sp in &mut app.speakers {
+            sp.coordinator_uid = Some("RINCON_Den".to_string());
+        }
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "group all", false).await.unwrap();
+        assert!(api.calls().is_empty());
+        assert!(app.active_status().starts_with("Already assembled"));
+        app.speakers[2].coordinator_uid = Some("RINCON_Office".to_string());
+        execute_command(&mut app, &api, "group all", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["group_all"]);
+    }
+
+    #[tokio::test]
+    async fn test_group_key_completes_a_partial_grouping_before_ungrouping() {
+        let mut app = app_with(&["Den", "Kitchen", "Office"]);
+        app.speakers[1].coordinator_uid = Some("RINCON_Den".to_string());
+        let api = MockApi::default();
+        let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+        handle_key(&mut app, &api, g).await.unwrap();
+        assert!(app.active_status().starts_with("The stragglers are gathered"));
+        for sp in &mut app.speakers {
+            sp.coordinator_uid = Some("RINCON_Den".to_string());
+        }
+        handle_key(&mut app, &api, g).await.unwrap();
+        assert_eq!(api.calls(), vec!["group_all", "ungroup_all"]);
+        assert_eq!(app.active_status(), "The company is scattered to the winds.");
+    }
+
+    #[tokio::test]
+    async fn test_o_moves_only_the_selected_room_in_or_out_of_a_group() {
+        let mut app = app_with(&["Den", "Kitchen", "Office", "Attic"]);
+        app.speakers.iter_mut().find(|s| s.name == "Kitchen").unwrap().coordinator_uid = Some("RINCON_Den".to_string());
+        let api = MockApi::default();
+        let o = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE);
+
+        app.select_speaker_named("Office");
+        handle_key(&mut app, &api, o).await.unwrap();
+        assert_eq!(app.active_status(), "Office joins Den's fellowship.");
+        app.select_speaker_named("Kitchen");
+        handle_key(&mut app, &api, o).await.unwrap();
+        assert_eq!(app.active_status(), "Kitchen departs from Den's fellowship and walks alone.");
+        app.select_speaker_named("Den");
+        handle_key(&mut app, &api, o).await.unwrap();
+        assert_eq!(app.active_status(), "Den leads this fellowship and cannot leave it.");
+        assert_eq!(api.calls(), vec!["group RINCON_Den RINCON_Office", "ungroup RINCON_Kitchen"]);
+
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.select_speaker_named("Den");
+        handle_key(&mut app, &api, o).await.unwrap();
+        assert_eq!(app.active_status(), "There is no fellowship for Den to join — g gathers everyone.");
+        app.speakers.iter_mut().find(|s| s.name == "Kitchen").unwrap().state = "PLAYING".to_string();
+        handle_key(&mut app, &api, o).await.unwrap();
+        assert_eq!(app.active_status(), "Den joins Kitchen's fellowship. The fellowship stands as one.");
+    }
+
+    #[tokio::test]
+    async fn test_bring_joins_the_playing_group_or_copies_a_solo_playlist() {
+        let mut app = app_with(&["Den", "Kitchen", "Office"]);
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "bring kitchen", false).await.unwrap();
+        assert!(app.active_status().starts_with("No music stirs"));
+
+        // Den plays altwave alone: Kitchen starts the same playlist
+        app.speakers[0].state = "PLAYING".to_string();
+        app.begin_listening("RINCON_Den", "altwave");
+        execute_command(&mut app, &api, "bring kitchen", false).await.unwrap();
+        assert_eq!(app.active_status(), "Kitchen takes up altwave beside Den.");
+
+        // Once Den leads a group, Office joins it
+        app.speakers[1].coordinator_uid = Some("RINCON_Den".to_string());
+        execute_command(&mut app, &api, "bring office", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["play RINCON_Kitchen altwave", "group RINCON_Den RINCON_Office"]);
+        assert!(app.in_group_of(&app.speakers[2], &app.speakers[0]));
+        execute_command(&mut app, &api, "bring office", false).await.unwrap();
+        assert_eq!(app.active_status(), "Office already hears Den's song.");
+    }
+
+    #[tokio::test]
+    async fn test_stop_key_stops_group_via_coordinator() {
+        let mut app = App::new();
+        let mut den = speaker("Den", "PLAYING");
+        den.coordinator_uid = Some("RINCON_Den".to_string());
+        let mut kitchen = speaker("Kitchen", "PLAYING");
+        kitchen.coordinator_uid = Some("RINCON_Den".to_string());
+        app.set_speakers(vec![den, kitchen]);
+        app.speaker_index = 1;
+        let api = MockApi::default();
+        let key = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+        handle_key(&mut app, &api, key).await.unwrap();
+        assert_eq!(api.calls(), vec!["stop RINCON_Den"]);
+        assert!(app.speakers.iter().all(|s| s.state == "STOPPED"));
+    }
+
+    #[tokio::test]
+    async fn test_stop_on_old_daemon_says_so_and_stops_offering_it() {
+        let mut app = app_with(&["Den"]);
+        app.speakers[0].state = "PLAYING".to_string();
+        let api = MockApi { missing: vec!["stop"], ..MockApi::default() };
+        let key = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+        handle_key(&mut app, &api, key).await.unwrap();
+        assert_eq!(app.status_message.as_deref(), Some(TOO_OLD));
+        assert_eq!(app.speakers[0].state, "PLAYING");
+        assert!(!app.supports("stop"));
+        // The second press never reaches the daemon
+        handle_key(&mut app, &api, key).await.unwrap();
+        assert_eq!(api.calls(), vec!["stop RINCON_Den"]);
+    }
+
+    #[tokio::test]
+    async fn test_mute_skipped_when_capabilities_lack_it() {
+        let mut app = app_with(&["Den"]);
+        let api = MockApi { missing: vec!["mute"], ..MockApi::default() };
+        app.capabilities = api.capabilities().await.ok();
+        execute_command(&mut app, &api, "mute den", false).await.unwrap();
+        assert!(api.calls().is_empty());
+        assert!(!app.speakers[0].muted);
+        assert_eq!(app.status_message.as_deref(), Some(TOO_OLD));
+    }
+
+    #[tokio::test]
+    async fn test_volume_prompt_steps_and_mutes() {
+        let mut app = app_with(&["Den"]);
+        let api = MockApi::default();
+        let type_in = async |app: &mut App, keys: &str| {
+            handle_key(app, &api, KeyEvent::new(KeyCode::Char('v'), KeyModifiers::NONE)).await.unwrap();
+            for c in keys.chars() {
+                handle_key(app, &api, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)).await.unwrap();
+            }
+            handle_key(app, &api, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)).await.unwrap();
+        };
+        type_in(&mut app, "+15").await;
+        assert_eq!(app.speakers[0].volume, 35);
+        type_in(&mut app, "-").await;
+        assert_eq!(app.speakers[0].volume, 30);
+        type_in(&mut app, "m5").await;
+        assert!(app.speakers[0].muted);
+        type_in(&mut app, "4+2").await;
+        assert_eq!(app.speakers[0].volume, 42);
+        assert_eq!(api.calls(), vec!["set_volume RINCON_Den 35", "set_volume RINCON_Den 30", "set_mute RINCON_Den true", "set_volume RINCON_Den 42"]);
+    }
+
+    #[test]
+    fn test_paste_fills_only_an_open_prompt() {
+        let mut app = app_with(&["Den"]);
+        handle_paste(&mut app, "play chill");
+        assert!(app.command_input.is_none());
+        app.command_input = Some("play ".into());
+        handle_paste(&mut app, "Chill\r\nVibes\n");
+        assert_eq!(app.command_input.as_deref(), Some("play Chill Vibes"));
+        app.command_input = None;
+        app.volume_input = Some("4".into());
+        handle_paste(&mut app, " 25%\n");
+        assert_eq!(app.volume_input.as_deref(), Some("425"));
+    }
+
+    #[tokio::test]
+    async fn test_ctrl_r_searches_history_and_esc_restores_the_prompt() {
+        let api = MockApi::default();
+        let mut app = app_with(&["Den"]);
+        app.remember_command("play altwave");
+        app.remember_command("vol 30");
+        app.command_input = Some("sl".into());
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        handle_key(&mut app, &api, ctrl_r).await.unwrap();
+        for c in "alt".chars() {
+            handle_key(&mut app, &api, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)).await.unwrap();
+        }
+        assert_eq!(app.history_match(), Some("play altwave"));
+        handle_key(&mut app, &api, KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)).await.unwrap();
+        assert!(app.history_search.is_none());
+        assert_eq!(app.command_input.as_deref(), Some("sl"));
+
+        handle_key(&mut app, &api, ctrl_r).await.unwrap();
+        handle_paste(&mut app, "vol");
+        handle_key(&mut app, &api, KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)).await.unwrap();
+        assert!(app.history_search.is_none());
+        assert_eq!(app.command_input.as_deref(), Some("vol 30"));
+    }
+
+    #[test]
+    fn test_parse_mini_takes_the_next_argument() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>().into_iter();
+        assert_eq!(parse_mini(args(&["--mini", "Living Room"])).unwrap().as_deref(), Some("Living Room"));
+        assert_eq!(parse_mini(args(&[])).unwrap(), None);
+        assert!(parse_mini(args(&["--mini"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mini_mode_ignores_keys_outside_its_set() {
+        let mut app = App::new();
+        app.mini = Some("kitchen".to_string());
+        app.set_speakers(vec![speaker("Den", "PLAYING"), speaker("Kitchen", "PLAYING")]);
+        let api = MockApi::default();
+        for c in ['x', 'g', 'm', ' '] {
+            handle_key(&mut app, &api, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)).await.unwrap();
+        }
+        assert_eq!(api.calls(), vec!["pause RINCON_Kitchen"]);
+    }
+
+    #[test]
+    fn test_unreachable_status_calls_out_rejected_credentials() {
+        let denied: anyhow::Error = api::Unauthorized.into();
+        assert!(unreachable_status(&denied).contains("credentials"));
+        let down = anyhow::anyhow!("connection refused");
+        assert!(unreachable_status(&down).contains("Start sonosd"));
+    }
+
+    #[tokio::test]
+    async fn test_startup_failure_tells_nothing_listening_from_an_unwell_daemon() {
+        let down = anyhow::anyhow!("connection refused");
+        assert_eq!(
+            startup_failure_status(&down, Some(("127.0.0.1:9271", false))),
+            "Nothing is listening on 127.0.0.1:9271 — is sonosd running? Start it with `sonosd`."
+        );
+        let garbled = anyhow::anyhow!("error decoding response body");
+        assert!(startup_failure_status(&garbled, Some(("127.0.0.1:9271", true))).ends_with("not as sonosd should: error decoding response body"));
+        assert!(startup_failure_status(&down, None).contains("Start sonosd"));
+        let denied: anyhow::Error = api::Unauthorized.into();
+        assert!(startup_failure_status(&denied, Some(("127.0.0.1:9271", true))).contains("credentials"));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        assert!(listening(&addr).await);
+        drop(listener);
+        assert!(!listening(&addr).await);
+    }
+
+    #[tokio::test]
+    async fn test_click_on_progress_bar_seeks() {
+        let mut app = app_with(&["Den"]);
+        app.seek_targets.borrow_mut().push(app::SeekTarget {
+            area: Rect { x: 4, y: 10, width: 40, height: 1 },
+            speaker: "RINCON_Den".into(),
+            duration: 240,
+        });
+        let api = MockApi::default();
+        let click = |kind, column| MouseEvent { kind, column, row: 10, modifiers: KeyModifiers::NONE };
+        handle_mouse(&mut app, &api, click(MouseEventKind::Moved, 14)).await;
+        handle_mouse(&mut app, &api, click(MouseEventKind::Down(MouseButton::Left), 14)).await;
+        assert_eq!(api.calls(), vec!["seek RINCON_Den 60"]);
+    }
+
+    #[tokio::test]
+    async fn test_wheel_turns_the_room_under_the_pointer_or_scrolls_playlists() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.playlists = vec![
+            Playlist { alias: "zen".to_string(), favorite_name: "Zen".to_string(), source: PlaylistSource::Configured },
+            Playlist { alias: "altwave".to_string(), favorite_name: "Alt Wave".to_string(), source: PlaylistSource::Configured },
+        ];
+        app.room_rows.borrow_mut().push(app::RoomRow { area: Rect { x: 1, y: 4, width: 30, height: 2 }, speaker: "RINCON_Kitchen".into() });
+        app.playlists_area.set(Rect { x: 40, y: 1, width: 30, height: 10 });
+        let api = MockApi::default();
+        let wheel = |kind, column, row| MouseEvent { kind, column, row, modifiers: KeyModifiers::NONE };
+        handle_mouse(&mut app, &api, wheel(MouseEventKind::ScrollUp, 10, 5)).await;
+        assert_eq!(api.calls(), vec!["set_volume RINCON_Kitchen 22"]);
+        assert_eq!(app.speaker_index, 0, "the selection stays put");
+
+        handle_mouse(&mut app, &api, wheel(MouseEventKind::ScrollDown, 45, 3)).await;
+        assert_eq!(app.playlist_index, 1);
+        handle_mouse(&mut app, &api, wheel(MouseEventKind::ScrollDown, 80, 3)).await;
+        assert_eq!((api.calls().len(), app.playlist_index), (1, 1), "the wheel elsewhere does nothing");
+    }
+
+    #[tokio::test]
+    async fn test_mute_commands_chain() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "mute all; unmute kit", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["set_mute all true", "set_mute RINCON_Kitchen false"]);
+        let muted: Vec<bool> = app.speakers.iter().map(|s| s.muted).collect();
+        assert_eq!(muted, vec![true, false]);
+    }
+
+    #[tokio::test]
+    async fn test_mute_unknown_speaker_sends_nothing() {
+        let mut app = app_with(&["Den"]);
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "mute mordor", false).await.unwrap();
+        assert!(api.calls().is_empty());
+        assert_eq!(app.active_status(), "Not all those who wander are found in this network.");
+    }
+
+    #[tokio::test]
+    async fn test_undo_resumes_only_what_sleep_paused() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.sleep_paused = vec!["RINCON_Kitchen".into()];
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "undo", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["resume RINCON_Kitchen"]);
+        assert!(app.sleep_paused.is_empty());
+
+        execute_command(&mut app, &api, "undo", false).await.unwrap();
+        assert_eq!(api.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_panic_stop_works_mid_prompt_and_undo_restores() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.speakers[0].state = "PLAYING".into();
+        app.speakers[1].muted = true;
+        app.command_input = Some("vol 8".into());
+        let api = MockApi::default();
+        handle_key(&mut app, &api, KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL)).await.unwrap();
+        assert_eq!(api.calls(), vec![
+            "pause RINCON_Den", "pause RINCON_Kitchen",
+            "set_mute RINCON_Den true", "set_mute RINCON_Kitchen true",
+        ]);
+        assert_eq!(app.command_input.as_deref(), Some("vol 8"));
+        assert!(app.speakers.iter().all(|s| s.muted && s.state == "PAUSED_PLAYBACK"));
+        assert!(app.active_status().starts_with("Everything silenced."));
+
+        app.command_input = None;
+        execute_command(&mut app, &api, "undo", false).await.unwrap();
+        assert_eq!(&api.calls()[4..], ["set_mute RINCON_Den false", "resume RINCON_Den"]);
+        assert!(app.silenced.is_none());
+        assert!(!app.speakers[0].muted && app.speakers[1].muted);
+        assert_eq!(app.speakers[0].state, "PLAYING");
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_sleep_acknowledges_auto_pause() {
+        let mut app = app_with(&["Den"]);
+        app.sleep_paused = vec!["RINCON_Den".into()];
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "resume", false).await.unwrap();
+        assert_eq!(app.active_status(), "Rousing Den from the Palantir's slumber.");
+    }
+
+    #[tokio::test]
+    async fn test_all_actions_skip_do_not_disturb_speakers() {
+        let mut app = app_with(&["Den", "Nursery"]);
+        app.do_not_disturb = vec!["Nursery".into()];
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "vol all 40; pause all; mute all; group all", false).await.unwrap();
+        assert_eq!(api.calls(), vec![
+            "set_volume RINCON_Den 40",
+            "pause RINCON_Den",
+            "set_mute RINCON_Den true",
+        ]);
+        assert_eq!(app.speakers[1].volume, 20);
+        assert!(!app.speakers[1].muted);
+        assert_eq!(app.active_status(), "Too few of the company answer the call to form a fellowship.");
+    }
+
+    #[tokio::test]
+    async fn test_startup_command_runs_chained_steps() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        let api = MockApi::default();
+        run_startup(&mut app, &api, "vol all 15; pause kitchen").await;
+        assert_eq!(api.calls(), vec![
+            "set_volume RINCON_Den 15",
+            "set_volume RINCON_Kitchen 15",
+            "pause RINCON_Kitchen",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_default_speaker_is_the_startup_target() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        let api = MockApi::default();
+        select_default_speaker(&mut app, "kitchen");
+        assert_eq!(app.active_status(), "Kitchen awaits your command.");
+        run_startup(&mut app, &api, "vol 15").await;
+        assert_eq!(api.calls(), vec!["set_volume RINCON_Kitchen 15"]);
+        // Offline: keep the current selection and say who answers instead
+        select_default_speaker(&mut app, "Attic");
+        assert_eq!(app.speaker_name().as_deref(), Some("Kitchen"));
+        assert_eq!(app.active_status(), "Attic is not found in this network — Kitchen answers instead.");
+    }
+
+    #[tokio::test]
+    async fn test_poller_reports_an_outage_then_reconnects() {
+        let mut app = app_with(&["Den"]);
+        let api = MockApi {
+            speakers: vec![speaker("Den", "PLAYING")],
+            playlists: vec![Playlist { alias: "chill".to_string(), favorite_name: "Chill Vibes".to_string(), source: PlaylistSource::Configured }],
+            ..MockApi::default()
+        };
+        let down = || Err(anyhow::anyhow!("connection refused"));
+        let now = Instant::now();
+        // One blip is not an outage
+        apply_poll(&mut app, &api, now, down()).await;
+        assert_eq!(app.active_status(), "");
+        apply_poll(&mut app, &api, now, down()).await;
+        assert_eq!(app.active_status(), "The gates of Moria are sealed. Start sonosd.");
+        apply_poll(&mut app, &api, now, down()).await;
+        assert!(app.playlists.is_empty());
+
+        apply_poll(&mut app, &api, now, Ok(api.speakers.clone())).await;
+        assert_eq!(app.active_status(), "The gates stand open again — sonosd answers.");
+        assert_eq!(app.playlists.len(), 1);
+        assert_eq!(app.speakers[0].state, "PLAYING");
+        // Back to normal: a later single failure stays quiet again
+        app.status_message = None;
+        apply_poll(&mut app, &api, now, down()).await;
+        assert_eq!(app.active_status(), "");
+    }
+
+    #[tokio::test]
+    async fn test_startup_command_waits_for_speakers() {
+        let mut app = App::new();
+        let api = MockApi::default();
+        run_startup(&mut app, &api, "vol all 15").await;
+        assert!(api.calls().is_empty());
+        assert_eq!(app.active_status(), "The startup rite found no speakers to stir.");
+    }
+
+    #[tokio::test]
+    async fn test_play_group_gathers_the_online_rooms_then_plays_on_the_leader() {
+        let mut app = app_with(&["Den", "Kitchen", "Office"]);
+        app.playlists = vec![Playlist { alias: "zen".to_string(), favorite_name: "Zen".to_string(), source: PlaylistSource::Configured }];
+        app.play_group = vec!["Kitchen".into(), "Attic".into(), "Den".into()];
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "play zen", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["group RINCON_Kitchen RINCON_Den", "play RINCON_Kitchen zen"]);
+        assert!(app.event_log.iter().any(|e| e.text.contains("The company gathers — 2 rooms")));
+
+        // Already standing together: no second group call
+        app.last_play.clear();
+        app.select_speaker_named("Office");
+        apply_action(&mut app, &api, Action::Select).await.unwrap();
+        assert_eq!(&api.calls()[2..], ["play RINCON_Kitchen zen"]);
+    }
+
+    #[tokio::test]
+    async fn test_play_cooldown_starts_only_once_a_playlist_resolves() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.playlists = vec![Playlist { alias: "zen".to_string(), favorite_name: "Zen".to_string(), source: PlaylistSource::Configured }];
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "play zne", false).await.unwrap();
+        execute_command(&mut app, &api, "play zen", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["play RINCON_Den zen"]);
+
+        // A repeat inside the cooldown gathers nobody
+        app.play_group = vec!["Kitchen".into(), "Den".into()];
+        app.last_play.clear();
+        execute_command(&mut app, &api, "play zen", false).await.unwrap();
+        app.set_membership("RINCON_Den", None);
+        execute_command(&mut app, &api, "play zen", false).await.unwrap();
+        assert_eq!(&api.calls()[1..], ["group RINCON_Kitchen RINCON_Den", "play RINCON_Kitchen zen"]);
+    }
+
+    #[tokio::test]
+    async fn test_solo_and_named_rooms_skip_the_play_group() {
+        let mut app = app_with(&["Den", "Kitchen", "Office"]);
+        app.playlists = vec![Playlist { alias: "zen".to_string(), favorite_name: "Zen".to_string(), source: PlaylistSource::Configured }];
+        app.play_group = vec!["Kitchen".into(), "Den".into()];
+        app.select_speaker_named("Office");
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "solo zen", false).await.unwrap();
+        execute_command(&mut app, &api, "play Den zen", false).await.unwrap();
+        assert_eq!(api.calls(), vec!["play RINCON_Office zen", "play RINCON_Den zen"]);
+
+        // With only one of the set online, it plays there ungrouped
+        app.play_group = vec!["Attic".into(), "Den".into()];
+        app.last_play.clear();
+        execute_command(&mut app, &api, "play #1", false).await.unwrap();
+        assert_eq!(&api.calls()[2..], ["play RINCON_Den zen"]);
+    }
+
+    #[tokio::test]
+    async fn test_party_groups_plays_and_sets_volume_then_off_restores() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.party = api::PartyConfig {
+            speakers: vec!["Kitchen".into(), "Den".into()],
+            volume: Some(40),
+            playlist: Some("altwave".into()),
+        };
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "party", false).await.unwrap();
+        assert_eq!(api.calls(), vec![
+            "group RINCON_Kitchen RINCON_Den",
+            "play RINCON_Kitchen altwave",
+            "set_volume RINCON_Kitchen 40",
+            "set_volume RINCON_Den 40",
+        ]);
+        execute_command(&mut app, &api, "party off", false).await.unwrap();
+        assert_eq!(&api.calls()[4..], [
+            "ungroup_all",
+            "set_volume RINCON_Den 20",
+            "set_volume RINCON_Kitchen 20",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_actions_apply_without_a_key() {
+        let mut app = app_with(&["Den"]);
+        app.speakers[0].state = "PLAYING".to_string();
+        let api = MockApi::default();
+        apply_action(&mut app, &api, Action::PlayPause).await.unwrap();
+        apply_action(&mut app, &api, Action::VolumeUp).await.unwrap();
+        apply_action(&mut app, &api, Action::Stop).await.unwrap();
+        assert_eq!(api.calls(), vec!["pause RINCON_Den", "set_volume RINCON_Den 25", "stop RINCON_Den"]);
+        apply_action(&mut app, &api, Action::Command).await.unwrap();
+        assert_eq!(app.command_input.as_deref(), Some(""));
+    }
+
+    #[tokio::test]
+    async fn test_remapped_keys_dispatch_through_the_keymap() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        let config = Config {
+            keybindings: [("down".to_string(), vec!["J".to_string()]), ("bogus".to_string(), vec![])].into(),
+            ..Config::default()
+        };
+        app.apply_config(&config);
+        assert!(app.status_message.as_deref().unwrap().contains("unknown action \"bogus\""));
+        let api = MockApi::default();
+        handle_key(&mut app, &api, KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE)).await.unwrap();
+        assert_eq!(app.speaker_index, 0);
+        handle_key(&mut app, &api, KeyEvent::new(KeyCode::Char('J'), KeyModifiers::SHIFT)).await.unwrap();
+        assert_eq!(app.speaker_index, 1);
+    }
+
+    #[tokio::test]
+    async fn test_balance_key_evens_out_the_group() {
+        let mut app = app_with(&["Den", "Kitchen", "Office"]);
+        for sp in &mut app.speakers[..2] {
+            sp.coordinator_uid = Some("RINCON_Den".into());
+        }
+        app.speakers[0].volume = 10;
+        app.speakers[1].volume = 40;
+        let api = MockApi::default();
+        let key = KeyEvent::new(KeyCode::Char('B'), KeyModifiers::SHIFT);
+        handle_key(&mut app, &api, key).await.unwrap();
+        assert_eq!(api.calls(), vec!["set_volume RINCON_Den 25", "set_volume RINCON_Kitchen 25"]);
+        assert!(app.speakers[..2].iter().all(|s| s.volume == 25));
+        // A lone speaker has nothing to balance
+        execute_command(&mut app, &api, "balance office", false).await.unwrap();
+        assert_eq!(api.calls().len(), 2);
+        assert!(app.status_message.as_deref().unwrap().contains("stands alone"));
+    }
+
+    #[tokio::test]
+    async fn test_group_editor_adds_and_drops_rooms() {
+        let mut app = app_with(&["Den", "Kitchen", "Office"]);
+        for sp in &mut app.speakers[..2] {
+            sp.coordinator_uid = Some("RINCON_Den".into());
+        }
+        let api = MockApi::default();
+        let press = |c| KeyEvent::new(c, KeyModifiers::NONE);
+        // Only Now Playing has a group to edit
+        handle_key(&mut app, &api, press(KeyCode::Char('G'))).await.unwrap();
+        assert!(app.group_edit.is_none());
+        app.active_panel = app::Panel::NowPlaying;
+        handle_key(&mut app, &api, press(KeyCode::Char('G'))).await.unwrap();
+        for code in [KeyCode::Down, KeyCode::Down, KeyCode::Char(' '), KeyCode::Up, KeyCode::Char(' ')] {
+            handle_key(&mut app, &api, press(code)).await.unwrap();
+        }
+        assert_eq!(api.calls(), vec!["group RINCON_Den RINCON_Office", "ungroup RINCON_Kitchen"]);
+        assert_eq!(app.speakers[2].coordinator_uid.as_deref(), Some("RINCON_Den"));
+        assert_eq!(app.speakers[1].coordinator_uid.as_deref(), Some("RINCON_Kitchen"));
+        // The leader can't be dropped; + still means volume, and esc closes
+        app.move_group_edit(-5);
+        handle_key(&mut app, &api, press(KeyCode::Char(' '))).await.unwrap();
+        assert_eq!(api.calls().len(), 2);
+        handle_key(&mut app, &api, press(KeyCode::Esc)).await.unwrap();
+        assert!(app.group_edit.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_plus_scales_focused_group() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        for sp in &mut app.speakers {
+            sp.coordinator_uid = Some("RINCON_Den".into());
+        }
+        app.active_panel = app::Panel::NowPlaying;
+        let api = MockApi::default();
+        let key = KeyEvent::new(KeyCode::Char('+'), KeyModifiers::NONE);
+        handle_key(&mut app, &api, key).await.unwrap();
+        assert_eq!(api.calls(), vec!["set_group_volume RINCON_Den 25"]);
+        assert!(app.speakers.iter().all(|s| s.volume == 25));
+    }
+
+    #[tokio::test]
+    async fn test_shift_r_refreshes_speakers_now() {
+        let mut app = app_with(&["Den"]);
+        let api = MockApi {
+            speakers: vec![speaker("Den", "PLAYING"), speaker("Kitchen", "PAUSED_PLAYBACK")],
+            ..MockApi::default()
+        };
+        let key = KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT);
+        handle_key(&mut app, &api, key).await.unwrap();
+        assert_eq!(app.speakers.len(), 2);
+        assert!(app.refreshed_at.is_some());
+        assert_eq!(app.status_message.as_deref(), Some("Refreshed."));
+    }
+
+    #[tokio::test]
+    async fn test_sleep_here_targets_selected_group() {
+        let mut app = app_with(&["Den", "Kitchen"]);
+        app.speaker_index = 1;
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "sleep 20 here fade", false).await.unwrap();
+        assert_eq!(app.sleep_timers.len(), 1);
+        assert_eq!(app.sleep_timers[0].target.as_deref(), Some("RINCON_Kitchen"));
+        assert!(app.sleep_timers[0].fade);
+        assert!(app.sleep_until.is_none());
+        execute_command(&mut app, &api, "sleep cancel", false).await.unwrap();
+        assert!(app.sleep_timers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sleep_at_sets_countdown_to_wall_clock_time() {
+        // MockClock runs in UTC: 1_772_000_000 is 06:13:20
+        let clock = std::rc::Rc::new(crate::clock::MockClock::new(1_772_000_000));
+        let mut app = App::with_clock(clock);
+        let api = MockApi::default();
+        execute_command(&mut app, &api, "sleep at 7:05", false).await.unwrap();
+        let remaining = app.sleep_until.unwrap() - app.clock.now();
+        assert_eq!(remaining.as_secs(), 51 * 60 + 40);
+    }
+
+    #[tokio::test]
+    async fn test_space_pauses_playing_speaker() {
+        let mut app = App::new();
+        app.set_speakers(vec![speaker("Den", "PLAYING")]);
+        let api = MockApi::default();
+        let key = KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE);
+        handle_key(&mut app, &api, key).await.unwrap();
+        assert_eq!(api.calls(), vec!["pause RINCON_Den"]);
+    }
+
+    #[tokio::test]
+    async fn test_ctrl_c_quits_even_in_command_mode() {
+        let mut app = app_with(&["Den"]);
+        app.command_input = Some("vol 3".to_string());
+        let api = MockApi::default();
+        let key = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        handle_key(&mut app, &api, key).await.unwrap();
+        assert!(app.should_quit);
+        assert!(api.calls().is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+/// UI preferences toggled at runtime and remembered across launches.
+/// Unknown or missing keys fall back to defaults so old files keep loading.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Prefs {
+    pub dashboard_mode: bool,
+    pub help_bar: crate::app::HelpBar,
+    /// Playlist aliases in curated order, used when `playlist_sort: manual`.
+    pub playlist_order: Vec<String>,
+    /// Do-not-disturb speakers as last toggled with `z`; None defers to config.
+    pub do_not_disturb: Option<Vec<String>>,
+    /// Playlist order last picked with `sort`; None follows the daemon's `playlist_sort`.
+    pub playlist_sort: Option<String>,
+    /// Commands run from the `:` prompt, oldest first, for Ctrl-R search.
+    pub command_history: Vec<String>,
+}
+
+/// Where everything the TUI remembers lives (created on first use):
+/// `SONOS_PALANTIR_CONFIG_DIR` if set, else `$XDG_CONFIG_HOME/sonos-palantir`,
+/// else `~/.config/sonos-palantir`.
+pub fn config_dir() -> PathBuf {
+    // Tests that play or save settings write to a pid-scoped scratch directory,
+    // never the developer's own.
+    #[cfg(test)]
+    let dir = std::env::temp_dir().join(format!("palantir-config-{}", std::process::id()));
+    #[cfg(not(test))]
+    let dir = config_dir_from(|name| std::env::var(name).ok());
+    fs::create_dir_all(&dir).ok();
+    dir
+}
+
+/// `config_dir`'s choice, reading variables through `var`; empty ones count as unset.
+fn config_dir_from(var: impl Fn(&str) -> Option<String>) -> PathBuf {
+    let var = |name| var(name).filter(|v| !v.is_empty());
+    if let Some(dir) = var("SONOS_PALANTIR_CONFIG_DIR") {
+        return PathBuf::from(dir);
+    }
+    let base = var("XDG_CONFIG_HOME").map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(var("HOME").unwrap_or_else(|| ".".to_string())).join(".config")
+    });
+    base.join("sonos-palantir")
+}
+
+/// Where one window left off, restored at its next launch. Each profile
+/// (see `session_profile`) keeps its own, so instances don't trade places.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Session {
+    pub panel: Option<crate::app::Panel>,
+    /// UID of the selected speaker.
+    pub speaker: Option<String>,
+    /// Alias of the selected playlist.
+    pub playlist: Option<String>,
+}
+
+/// Variables naming the terminal pane or window we run in, most specific first.
+const TERMINAL_IDS: [&str; 5] = ["TMUX_PANE", "WEZTERM_PANE", "KITTY_WINDOW_ID", "TERM_SESSION_ID", "WT_SESSION"];
+
+/// Which session this window keeps: `--profile <name>` if given, else one
+/// per terminal pane (a hash of its id), else the shared "default".
+pub fn session_profile(flag: Option<&str>, var: impl Fn(&str) -> Option<String>) -> String {
+    if let Some(name) = flag {
+        // Used as a file name: keep it to one harmless path component
+        let name: String = name.chars().filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')).collect();
+        if !name.is_empty() {
+            return name;
+        }
+    }
+    let id = TERMINAL_IDS.iter().find_map(|name| var(name).filter(|v| !v.is_empty()).map(|v| format!("{}={}", name, v)));
+    match id {
+        Some(id) => format!("term-{:016x}", fnv1a(&id)),
+        None => "default".to_string(),
+    }
+}
+
+/// FNV-1a: stable across builds, unlike `std`'s hasher, so a pane keeps its file.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+fn session_path_in(dir: &Path, profile: &str) -> PathBuf {
+    dir.join("sessions").join(format!("{}.json", profile))
+}
+
+pub fn load_session(profile: &str) -> Session {
+    fs::read_to_string(session_path_in(&config_dir(), profile))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_session(profile: &str, session: &Session) {
+    let path = session_path_in(&config_dir(), profile);
+    if let (Some(dir), Ok(json)) = (path.parent(), serde_json::to_string_pretty(session)) {
+        fs::create_dir_all(dir).ok();
+        fs::write(path, json).ok();
+    }
+}
+
+pub fn prefs_path() -> PathBuf {
+    config_dir().join("prefs.json")
+}
+
+pub fn load() -> Prefs {
+    fs::read_to_string(prefs_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Load, modify, and write back, so one toggle doesn't clobber the others.
+pub fn update(change: impl FnOnce(&mut Prefs)) {
+    let mut prefs = load();
+    change(&mut prefs);
+    if let Ok(json) = serde_json::to_string_pretty(&prefs) {
+        fs::write(prefs_path(), json).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefs_missing_keys_use_defaults() {
+        let prefs: Prefs = serde_json::from_str("{}").unwrap();
+        assert_eq!(prefs, Prefs::default());
+    }
+
+    #[test]
+    fn test_prefs_ignore_unknown_keys() {
+        let prefs: Prefs = serde_json::from_str(r#"{"dashboard_mode": true, "future": 1}"#).unwrap();
+        assert!(prefs.dashboard_mode);
+    }
+
+    #[test]
+    fn test_config_dir_prefers_override_then_xdg_then_home() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        let all = env(&[("SONOS_PALANTIR_CONFIG_DIR", "/tmp/palantir-a"), ("XDG_CONFIG_HOME", "/xdg"), ("HOME", "/home/frodo")]);
+        assert_eq!(config_dir_from(all), PathBuf::from("/tmp/palantir-a"));
+        let xdg = env(&[("SONOS_PALANTIR_CONFIG_DIR", ""), ("XDG_CONFIG_HOME", "/xdg"), ("HOME", "/home/frodo")]);
+        assert_eq!(config_dir_from(xdg), PathBuf::from("/xdg/sonos-palantir"));
+        let home = env(&[("HOME", "/home/frodo")]);
+        assert_eq!(config_dir_from(home), PathBuf::from("/home/frodo/.config/sonos-palantir"));
+    }
+
+    #[test]
+    fn test_session_profile_is_the_flag_then_the_terminal_pane_then_default() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        let tmux = env(&[("TMUX_PANE", "%3"), ("TERM_SESSION_ID", "w0t0p0")]);
+        assert_eq!(session_profile(Some("kitchen-pi"), tmux), "kitchen-pi");
+        assert_eq!(session_profile(Some("../../etc"), tmux), "etc");
+
+        let pane = session_profile(None, tmux);
+        assert!(pane.starts_with("term-") && pane.len() == 21, "{}", pane);
+        assert_eq!(pane, session_profile(None, tmux), "the same pane keeps its file");
+        assert_ne!(pane, session_profile(None, env(&[("TMUX_PANE", "%4")])));
+        assert_ne!(session_profile(None, env(&[("TERM_SESSION_ID", "w0t0p0")])), pane);
+
+        assert_eq!(session_profile(None, env(&[("TMUX_PANE", "")])), "default");
+        assert_eq!(session_profile(Some("///"), env(&[])), "default");
+    }
+
+    #[test]
+    fn test_session_paths_are_keyed_by_profile() {
+        let dir = Path::new("/cfg/sonos-palantir");
+        assert_eq!(session_path_in(dir, "default"), PathBuf::from("/cfg/sonos-palantir/sessions/default.json"));
+        assert_ne!(session_path_in(dir, "term-0000000000000001"), session_path_in(dir, "term-0000000000000002"));
+    }
+}
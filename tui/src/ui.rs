@@ -5,7 +5,8 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph},
     Frame,
 };
-use crate::app::{App, Panel};
+use crate::api::{PlaylistSource, QueueItem};
+use crate::app::{App, GroupingState, HelpBar, OsdKind, Panel};
 use crate::command;
 
 const BG: Color = Color::Rgb(20, 20, 30);
@@ -17,6 +18,14 @@ const DIM: Color = Color::Rgb(80, 80, 100);
 const HIGHLIGHT_BG: Color = Color::Rgb(40, 45, 65);
 const BORDER_ACTIVE: Color = ACCENT;
 const BORDER_INACTIVE: Color = Color::Rgb(50, 50, 70);
+/// Group members notably louder / quieter than the group average.
+const LOUDER: Color = Color::Rgb(240, 150, 90);
+const QUIETER: Color = Color::Rgb(110, 170, 230);
+// Volume numbers by level (`volume_color_levels`)
+const SOFT: Color = Color::Rgb(100, 150, 200);
+const LOUD: Color = Color::Rgb(230, 90, 90);
+/// Everything's text under the `dim` screensaver.
+const SAVER_FG: Color = Color::Rgb(35, 35, 45);
 
 pub fn draw_splash(f: &mut Frame) {
     let area = f.area();
@@ -64,28 +73,158 @@ pub fn draw_splash(f: &mut Frame) {
 }
 
 pub fn draw(f: &mut Frame, app: &App) {
+    // A terminal shrunk to nothing has no cells to paint
+    if f.area().is_empty() {
+        return;
+    }
+    app.seek_targets.borrow_mut().clear();
+    app.room_rows.borrow_mut().clear();
+    app.playlists_area.set(Rect::default());
+    if app.screensaver_active() {
+        draw_screensaver(f, app);
+        return;
+    }
+    if app.zen {
+        draw_zen(f, app);
+        return;
+    }
+    draw_screen(f, app);
+    if app.log_open {
+        draw_log_overlay(f, app);
+    }
+    if let Some(entries) = &app.stats {
+        draw_stats_overlay(f, app, entries);
+    }
+    if let Some(entries) = &app.recap {
+        draw_recap_overlay(f, app, entries);
+    }
+    if let Some(scroll) = app.roster {
+        draw_roster_overlay(f, app, scroll);
+    }
+    if let Some((alias, entries)) = &app.why {
+        draw_why_overlay(f, app, alias, entries);
+    }
+    if let Some(id) = &app.detail {
+        draw_detail_overlay(f, app, id);
+    }
+    if app.group_edit.is_some() {
+        draw_group_overlay(f, app);
+    }
+    if let Some((kind, value, _)) = app.osd {
+        draw_osd(f, kind, value);
+    }
+}
+
+/// Idle, nothing-playing kiosk view. `clock`: the time alone, hopping to a
+/// new spot each minute so no pixel stays lit; `dim`: the usual screen, darkened.
+fn draw_screensaver(f: &mut Frame, app: &App) {
+    let area = f.area();
+    if app.screensaver_style == "dim" {
+        draw_screen(f, app);
+        for cell in f.buffer_mut().content.iter_mut() {
+            cell.set_fg(SAVER_FG).set_bg(Color::Black);
+        }
+        return;
+    }
+    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
+    let secs = app.clock.local_seconds_of_day();
+    let text = format_clock(secs, app.clock_12h);
+    let w = text.chars().count() as u16;
+    if w > area.width {
+        return;
+    }
+    // A cheap scramble of the minute picks the spot
+    let minute = secs / 60;
+    let x = area.x + (minute * 37 % (area.width - w + 1) as u64) as u16;
+    let y = area.y + (minute * 13 % area.height as u64) as u16;
+    f.render_widget(
+        Paragraph::new(Span::styled(text, Style::default().fg(DIM))),
+        Rect::new(x, y, w, 1),
+    );
+}
+
+/// `Z`: the selected speaker's title, artist and progress, centered, with no
+/// panels or borders — for a spare monitor. Idle shows a lone dash.
+fn draw_zen(f: &mut Frame, app: &App) {
+    let area = f.area();
+    f.render_widget(Block::default().style(Style::default().bg(BG)), area);
+    let center = ratatui::layout::Alignment::Center;
+    let Some(track) = app.selected_speaker().and_then(|sp| sp.track.as_ref()) else {
+        let y = area.y + area.height / 2;
+        f.render_widget(
+            Paragraph::new(Span::styled("—", Style::default().fg(DIM))).alignment(center),
+            Rect::new(area.x, y, area.width, 1),
+        );
+        return;
+    };
+    let width = (area.width as usize).saturating_sub(8);
+    // Letter-spaced like the expanded view when it fits
+    let spaced: String = track.title.chars().map(|c| c.to_string()).collect::<Vec<_>>().join(" ");
+    let title = if spaced.chars().count() <= width { spaced } else { truncate(&track.title, width) };
+    let mut lines = vec![
+        Line::from(Span::styled(title, Style::default().fg(FG).add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(Span::styled(truncate(&track.artist, width), Style::default().fg(ACCENT))),
+    ];
+    if track.duration > 0 {
+        lines.push(Line::from(""));
+        lines.push(Line::from(""));
+        lines.push(segmented_progress(track.position, track.duration, width.min(60)));
+        lines.push(Line::from(Span::styled(
+            format!("{} / {}", format_time(track.position), format_time(track.duration)),
+            Style::default().fg(DIM),
+        )));
+    }
+    let h = (lines.len() as u16).min(area.height);
+    let y = area.y + (area.height - h) / 2;
+    f.render_widget(Paragraph::new(lines).alignment(center), Rect::new(area.x, y, area.width, h));
+}
+
+fn draw_screen(f: &mut Frame, app: &App) {
+    if app.mini.is_some() {
+        draw_mini(f, app);
+        return;
+    }
+    if app.expanded_now_playing {
+        draw_expanded_now_playing(f, app);
+        if app.help_open {
+            draw_help_overlay(f);
+        }
+        return;
+    }
+
     let outer = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(1),   // top status bar
             Constraint::Min(1),     // main panels
             Constraint::Length(1),   // status line
-            Constraint::Length(3),   // help bar / command input
+            Constraint::Length(help_bar_height(app)), // help bar / command input
         ])
         .split(f.area());
 
     draw_top_bar(f, app, outer[0]);
 
+    if app.dashboard_mode {
+        draw_dashboard(f, app, outer[1]);
+        draw_status_line(f, app, outer[2]);
+        draw_help_bar(f, app, outer[3]);
+        if app.help_open {
+            draw_help_overlay(f);
+        }
+        return;
+    }
+
     let main = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
         .split(outer[1]);
 
     // Dynamic left column: Rooms takes what it needs, Playlists gets the rest
-    let speaker_rows = if app.is_grouped() {
+    let speaker_rows = if app.show_topology() {
         let mut rows: u16 = 0;
         for coord in app.coordinators() {
-            let members = app.group_members_of(&coord.name);
+            let members = app.group_members_of(coord);
             rows += 1 + (members.len() as u16 * 2) + 1; // header + members*2 + blank
         }
         for _solo in app.solo_speakers() {
@@ -119,6 +258,19 @@ pub fn draw(f: &mut Frame, app: &App) {
     }
 }
 
+/// `--mini`: the speaker's track block fills the pane, with the status line beneath.
+fn draw_mini(f: &mut Frame, app: &App) {
+    let [body, status] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(f.area());
+    match app.mini_speaker() {
+        Some(sp) => track_block(f, app, sp, body),
+        None => f.render_widget(
+            Paragraph::new(Span::styled("  Not all those who wander are found in this network.", Style::default().fg(DIM))),
+            body,
+        ),
+    }
+    draw_status_line(f, app, status);
+}
+
 const TOP_BAR_BG: Color = Color::Rgb(30, 30, 45);
 
 fn draw_top_bar(f: &mut Frame, app: &App, area: Rect) {
@@ -133,7 +285,7 @@ fn draw_top_bar(f: &mut Frame, app: &App, area: Rect) {
 
     // Speaker name
     let speaker_name = selected
-        .map(|s| s.alias.as_deref().unwrap_or(&s.name).to_string())
+        .map(|s| s.display_name().to_string())
         .unwrap_or_else(|| "—".to_string());
 
     // Track info
@@ -194,7 +346,7 @@ fn panel_block(title: &str, active: bool) -> Block<'_> {
 }
 
 /// Returns a color for the volume bar: green (0-50), yellow (51-80), red (81-100).
-fn volume_color(vol: u8) -> Color {
+fn bar_color(vol: u8) -> Color {
     if vol <= 50 {
         Color::Rgb(120, 220, 140)
     } else if vol <= 80 {
@@ -204,9 +356,19 @@ fn volume_color(vol: u8) -> Color {
     }
 }
 
+/// A volume number's colour: cool at or below the quiet level, red at or
+/// above the loud one, otherwise `neutral` (always, when `levels` is None).
+fn volume_color(vol: u8, levels: Option<(u8, u8)>, neutral: Color) -> Color {
+    match levels {
+        Some((_, loud)) if vol >= loud => LOUD,
+        Some((quiet, _)) if vol <= quiet => SOFT,
+        _ => neutral,
+    }
+}
+
 /// Render a volume bar string using block characters.
 fn volume_bar(vol: u8, width: usize) -> (String, Color) {
-    let color = volume_color(vol);
+    let color = bar_color(vol);
     let filled = (vol as usize * width) / 100;
     let remainder = (vol as usize * width) % 100;
     let partial = if filled < width && remainder > 0 {
@@ -219,55 +381,208 @@ fn volume_bar(vol: u8, width: usize) -> (String, Color) {
     (bar, color)
 }
 
+/// A `Vol` gauge for the expanded view. A muted speaker's gauge is dimmed and
+/// struck through, with a "(muted)" tag, so its volume doesn't read as audible.
+fn volume_gauge(label: &'static str, sp: &crate::api::Speaker, width: usize, levels: Option<(u8, u8)>) -> Line<'static> {
+    let (bar, color) = volume_bar(sp.volume, width);
+    let (bar_style, num_style) = if sp.muted {
+        let struck = Style::default().fg(DIM).add_modifier(Modifier::CROSSED_OUT);
+        (struck, struck)
+    } else {
+        (Style::default().fg(color), Style::default().fg(volume_color(sp.volume, levels, FG)))
+    };
+    let mut spans = vec![
+        Span::styled(label, Style::default().fg(DIM)),
+        Span::styled(bar, bar_style),
+        Span::styled(format!(" {:>3}", sp.volume), num_style),
+    ];
+    if sp.muted {
+        spans.push(Span::styled(" (muted)", Style::default().fg(PAUSED)));
+    }
+    Line::from(spans)
+}
+
 fn draw_speakers(f: &mut Frame, app: &App, area: Rect) {
     let active = app.active_panel == Panel::Speakers;
-    let block = panel_block("Rooms", active);
+    // ◐ some rooms grouped, ● everyone `g` gathers is in one group
+    let title = match app.grouping_state() {
+        GroupingState::None => "Rooms",
+        GroupingState::Partial => "Rooms ◐",
+        GroupingState::Full => "Rooms ●",
+    };
+    let block = panel_block(title, active);
     let inner = block.inner(area);
     f.render_widget(block, area);
 
+    if inner.is_empty() {
+        return;
+    }
+
     let mut lines: Vec<Line> = vec![];
     let bar_width = (inner.width as usize).saturating_sub(6);
+    // Where the selected speaker's row lands, to keep it in view
+    let mut selected_line = 0;
+    // Where every speaker's row starts, for the mouse wheel
+    let mut rows: Vec<(usize, String)> = vec![];
 
-    if app.is_grouped() {
+    if app.show_topology() {
         for coord in app.coordinators() {
-            let members = app.group_members_of(&coord.name);
-            let member_names: Vec<&str> = members.iter()
-                .map(|m| m.alias.as_deref().unwrap_or(&m.name))
+            let members = app.group_members_of(coord);
+            let member_names: Vec<String> = members.iter()
+                .map(|m| m.label())
                 .collect();
+            // The zone's play mode rides at the end; names give way to fit it
+            let modes = play_mode_glyphs(coord);
+            let names_room = (inner.width as usize)
+                .saturating_sub(9)
+                .saturating_sub(if modes.is_empty() { 0 } else { modes.chars().count() + 1 });
             lines.push(Line::from(vec![
                 Span::styled(" GROUPED ", Style::default().fg(DIM)),
-                Span::styled(member_names.join(" + "), Style::default().fg(ACCENT)),
+                Span::styled(truncate(&member_names.join(" + "), names_room), Style::default().fg(ACCENT)),
+                Span::styled(if modes.is_empty() { String::new() } else { format!(" {}", modes) }, Style::default().fg(DIM)),
             ]));
+            // Zone loudness: one gauge for the whole group (average of members)
+            let average = app.group_volume(&coord.id());
+            if let Some(vol) = average {
+                let (bar, _) = volume_bar(vol, bar_width.saturating_sub(4));
+                lines.push(Line::from(vec![
+                    Span::raw("   "),
+                    Span::styled(bar, Style::default().fg(DIM)),
+                    Span::styled(format!(" {:>3}", vol), Style::default().fg(DIM)),
+                ]));
+            }
             for m in &members {
-                let sp_index = app.speakers.iter().position(|s| s.name == m.name);
+                let sp_index = app.index_of(m);
                 let is_selected = active && sp_index == Some(app.speaker_index);
-                render_speaker_row(&mut lines, m, is_selected, bar_width);
+                let hint = match average {
+                    Some(avg) if members.len() > 1 => GroupHint::Member(avg),
+                    _ => GroupHint::None,
+                };
+                if sp_index == Some(app.speaker_index) {
+                    selected_line = lines.len();
+                }
+                rows.push((lines.len(), m.id()));
+                render_speaker_row(&mut lines, app, m, sp_index, is_selected, bar_width, hint);
             }
         }
         for sp in app.solo_speakers() {
-            let sp_index = app.speakers.iter().position(|s| s.name == sp.name);
+            let sp_index = app.index_of(sp);
             let is_selected = active && sp_index == Some(app.speaker_index);
-            render_speaker_row(&mut lines, sp, is_selected, bar_width);
+            if sp_index == Some(app.speaker_index) {
+                selected_line = lines.len();
+            }
+            rows.push((lines.len(), sp.id()));
+            render_speaker_row(&mut lines, app, sp, sp_index, is_selected, bar_width, GroupHint::None);
         }
     } else {
         for (i, sp) in app.speakers.iter().enumerate() {
             let is_selected = active && i == app.speaker_index;
-            render_speaker_row(&mut lines, sp, is_selected, bar_width);
+            if i == app.speaker_index {
+                selected_line = lines.len();
+            }
+            // `rooms_layout: flat` keeps this list while grouped: followers are
+            // indented, and the coordinator carries the group's average volume
+            let coord = app.coordinator_of(sp);
+            let average = app.group_volume(&coord.id()).filter(|_| app.group_members_of(coord).len() > 1);
+            let hint = match average {
+                Some(avg) if coord.id() == sp.id() => GroupHint::Leads(avg),
+                Some(avg) => GroupHint::Follows(avg),
+                None => GroupHint::None,
+            };
+            rows.push((lines.len(), sp.id()));
+            render_speaker_row(&mut lines, app, sp, Some(i), is_selected, bar_width, hint);
         }
     }
 
-    let para = Paragraph::new(lines);
+    let offset = rooms_offset(app.rooms_offset.get(), selected_line, lines.len(), inner.height as usize, app.center_rooms.take());
+    app.rooms_offset.set(offset);
+    let mut room_rows = app.room_rows.borrow_mut();
+    for (line, speaker) in rows {
+        // A row scrolled out of view (even half of one) can't be pointed at
+        let Some(y) = line.checked_sub(offset).filter(|y| y + 2 <= inner.height as usize) else { continue };
+        let area = Rect { x: inner.x, y: inner.y + y as u16, width: inner.width, height: 2 };
+        room_rows.push(crate::app::RoomRow { area, speaker });
+    }
+    let para = Paragraph::new(lines).scroll((offset as u16, 0));
     f.render_widget(para, inner);
 }
 
-fn render_speaker_row(lines: &mut Vec<Line>, sp: &crate::api::Speaker, selected: bool, bar_width: usize) {
-    let name = sp.alias.as_deref().unwrap_or(&sp.name);
-    let marker = if selected { "▸" } else { " " };
-    let (state_icon, state_color) = match sp.state.as_str() {
+/// First visible Rooms line: the previous `offset`, nudged just enough to keep
+/// the selected speaker's two-line row (at `selected`) on screen — or, when
+/// `center` is asked for, placing it mid-panel. Never scrolls past the end.
+fn rooms_offset(offset: usize, selected: usize, total: usize, height: usize, center: bool) -> usize {
+    let max = total.saturating_sub(height);
+    let offset = if center {
+        (selected + 1).saturating_sub(height / 2)
+    } else if selected < offset {
+        selected
+    } else if selected + 2 > offset + height {
+        (selected + 2).saturating_sub(height)
+    } else {
+        offset
+    };
+    offset.min(max)
+}
+
+/// Icon and colour for a Sonos transport state. STOPPED gets its own square so
+/// a real stop is distinguishable from pause and from idle/unknown states.
+fn state_glyph(state: &str) -> (&'static str, Color) {
+    match state {
         "PLAYING" => ("▶", PLAYING),
         "PAUSED_PLAYBACK" => ("‖", PAUSED),
+        "STOPPED" => ("■", DIM),
         _ => ("·", DIM),
+    }
+}
+
+/// Faint quick-jump digit for the speaker at `index` (keys `1`–`9`), blank beyond.
+fn jump_digit(index: Option<usize>) -> String {
+    match index {
+        Some(i) if i < 9 => (i + 1).to_string(),
+        _ => " ".to_string(),
+    }
+}
+
+/// A group member's volume number: dim near the group `average`, warm when
+/// `threshold` or more above it, cool when that far below.
+fn balance_color(volume: u8, average: u8, threshold: u8) -> Color {
+    let diff = volume as i16 - average as i16;
+    if threshold == 0 || diff.abs() < threshold as i16 {
+        DIM
+    } else if diff > 0 {
+        LOUDER
+    } else {
+        QUIETER
+    }
+}
+
+/// A room's place in a group of two or more, with the group's average volume
+/// (which its own volume is coloured against). The flat Rooms list also
+/// indents followers and tags the coordinator with the average.
+#[derive(Clone, Copy)]
+enum GroupHint {
+    None,
+    /// A member in the grouped view, which shows the average itself.
+    Member(u8),
+    Leads(u8),
+    Follows(u8),
+}
+
+/// One room's two lines: name, state and volume (then ☾ if do-not-disturb,
+/// ⏾ if a sleep timer will pause it), and its volume bar.
+fn render_speaker_row(lines: &mut Vec<Line>, app: &App, sp: &crate::api::Speaker, index: Option<usize>, selected: bool, bar_width: usize, hint: GroupHint) {
+    let name = sp.label();
+    let vol_color = match hint {
+        GroupHint::Member(avg) | GroupHint::Leads(avg) | GroupHint::Follows(avg) => balance_color(sp.volume, avg, app.balance_threshold),
+        GroupHint::None => DIM,
     };
+    // Off-balance members keep their warm/cool; the rest colour by level
+    let vol_color = if vol_color == DIM { volume_color(sp.volume, app.volume_levels, DIM) } else { vol_color };
+    // Followers shift right; the name column narrows so volumes stay aligned
+    let indent = if matches!(hint, GroupHint::Follows(_)) { 2 } else { 0 };
+    let dnd = app.excludes(sp);
+    let marker = if selected { "▸" } else { " " };
+    let (state_icon, state_color) = state_glyph(&sp.state);
     let name_style = if selected {
         Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)
     } else {
@@ -275,23 +590,75 @@ fn render_speaker_row(lines: &mut Vec<Line>, sp: &crate::api::Speaker, selected:
     };
 
     let name_line = Line::from(vec![
-        Span::styled(format!(" {} ", marker), if selected { Style::default().fg(ACCENT) } else { Style::default().fg(DIM) }),
-        Span::styled(format!("{:<12}", name), name_style),
+        Span::styled(jump_digit(index), Style::default().fg(DIM).add_modifier(Modifier::DIM)),
+        Span::styled(format!("{} ", marker), if selected { Style::default().fg(ACCENT) } else { Style::default().fg(DIM) }),
+        Span::styled(format!("{}{:<w$}", " ".repeat(indent), name, w = 12 - indent), name_style),
         Span::styled(format!(" {} ", state_icon), Style::default().fg(state_color)),
-        Span::styled(format!("{:>3}", sp.volume), Style::default().fg(DIM)),
+        Span::styled(format!("{:>3}", sp.volume), Style::default().fg(vol_color)),
+        Span::styled(match hint { GroupHint::Leads(avg) => format!(" ⊕{}", avg), _ => String::new() }, Style::default().fg(DIM)),
+        Span::styled(if dnd { " ☾" } else { "" }, Style::default().fg(DIM)),
+        Span::styled(if app.sleep_pending_for(sp) { " ⏾" } else { "" }, Style::default().fg(PAUSED)),
     ]);
     lines.push(name_line);
 
-    // Volume bar below speaker name
-    let (bar, color) = volume_bar(sp.volume, bar_width);
+    // Volume bar below speaker name, dimmed and struck through while muted
+    let (bar, color) = volume_bar(sp.volume, bar_width.saturating_sub(indent));
+    let bar_style = if sp.muted {
+        Style::default().fg(DIM).add_modifier(Modifier::CROSSED_OUT)
+    } else {
+        Style::default().fg(color)
+    };
     lines.push(Line::from(vec![
-        Span::raw("   "),
-        Span::styled(bar, Style::default().fg(color)),
+        Span::raw(" ".repeat(3 + indent)),
+        Span::styled(bar, bar_style),
     ]));
 }
 
+/// Dashboard mode: one dense line per speaker — name, state, volume, track.
+fn draw_dashboard(f: &mut Frame, app: &App, area: Rect) {
+    let block = panel_block("Dashboard — d to exit", true);
+    let inner_width = area.width.saturating_sub(2) as usize;
+
+    let items: Vec<ListItem> = app.speakers.iter().enumerate().map(|(i, sp)| {
+        let selected = i == app.speaker_index;
+        let (state_icon, state_color) = state_glyph(&sp.state);
+        let name_style = if selected {
+            Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(FG)
+        };
+        let track = sp.track.as_ref()
+            .map(|t| format!("{} — {}", t.title, t.artist))
+            .unwrap_or_default();
+        // marker(3) + name(14) + state(3) + volume(4) + gap(2)
+        let track = truncate(&track, inner_width.saturating_sub(26));
+
+        let line = Line::from(vec![
+            Span::styled(jump_digit(Some(i)), Style::default().fg(DIM).add_modifier(Modifier::DIM)),
+            Span::styled(format!("{} ", if selected { "▸" } else { " " }), Style::default().fg(if selected { ACCENT } else { DIM })),
+            Span::styled(format!("{:<14}", truncate(&sp.label(), 14)), name_style),
+            Span::styled(format!(" {} ", state_icon), Style::default().fg(state_color)),
+            Span::styled(format!("{:>3} ", sp.volume), Style::default().fg(volume_color(sp.volume, app.volume_levels, DIM))),
+            Span::styled(format!(" {}", track), Style::default().fg(FG)),
+        ]);
+        let mut item = ListItem::new(line);
+        if selected {
+            item = item.style(Style::default().bg(HIGHLIGHT_BG));
+        }
+        item
+    }).collect();
+
+    let list = List::new(items).block(block).highlight_style(Style::default());
+    let mut state = ListState::default();
+    if !app.speakers.is_empty() {
+        state.select(Some(app.speaker_index));
+    }
+    f.render_stateful_widget(list, area, &mut state);
+}
+
 fn draw_playlists(f: &mut Frame, app: &App, area: Rect) {
     let active = app.active_panel == Panel::Playlists;
+    app.playlists_area.set(area);
     if app.source_mode == crate::app::SourceMode::Podcasts {
         draw_podcasts_panel(f, app, area, active);
         return;
@@ -308,12 +675,18 @@ fn draw_playlists(f: &mut Frame, app: &App, area: Rect) {
         };
 
         let marker = if selected { "▸" } else { " " };
-        let display = truncate(&pl.alias, inner_width.saturating_sub(4));
+        // Auto-imported Favorites get a dim ☆ so they're distinguishable from config aliases
+        let is_favorite = pl.source == PlaylistSource::Favorite;
+        let display = truncate(&pl.alias, inner_width.saturating_sub(if is_favorite { 6 } else { 4 }));
 
-        let line = Line::from(vec![
+        let mut spans = vec![
             Span::styled(format!(" {} ", marker), if selected { Style::default().fg(ACCENT) } else { Style::default().fg(DIM) }),
             Span::styled(display, style),
-        ]);
+        ];
+        if is_favorite {
+            spans.push(Span::styled(" ☆", Style::default().fg(DIM)));
+        }
+        let line = Line::from(spans);
 
         let mut item = ListItem::new(line);
         if selected && active {
@@ -440,6 +813,10 @@ fn draw_now_playing(f: &mut Frame, app: &App, area: Rect) {
     let inner = block.inner(area);
     f.render_widget(block, area);
 
+    if inner.is_empty() {
+        return;
+    }
+
     let entities = app.playing_entities();
 
     if entities.is_empty() {
@@ -452,7 +829,7 @@ fn draw_now_playing(f: &mut Frame, app: &App, area: Rect) {
     }
 
     if entities.len() == 1 {
-        draw_track_block(f, entities[0], inner, true);
+        track_block(f, app, entities[0], inner);
         return;
     }
 
@@ -460,7 +837,7 @@ fn draw_now_playing(f: &mut Frame, app: &App, area: Rect) {
     let chunk_h = inner.height / entities.len() as u16;
     if chunk_h == 0 {
         // Terminal too small to stack — render only the first entity
-        draw_track_block(f, entities[0], inner, false);
+        track_block(f, app, entities[0], inner);
         return;
     }
     for (i, sp) in entities.iter().enumerate() {
@@ -475,7 +852,20 @@ fn draw_now_playing(f: &mut Frame, app: &App, area: Rect) {
             height,
             ..inner
         };
-        draw_track_block(f, sp, chunk, false);
+        track_block(f, app, sp, chunk);
+    }
+}
+
+/// Draw one track block and remember its progress bar as a click-to-seek
+/// target, if the daemon can seek.
+fn track_block(f: &mut Frame, app: &App, sp: &crate::api::Speaker, area: Rect) {
+    let Some(bar) = draw_track_block(f, sp, app.next_up_for(sp), area) else { return };
+    if let Some(track) = sp.track.as_ref().filter(|_| app.supports("seek")) {
+        app.seek_targets.borrow_mut().push(crate::app::SeekTarget {
+            area: bar,
+            speaker: sp.id(),
+            duration: track.duration,
+        });
     }
 }
 
@@ -498,15 +888,16 @@ fn segmented_progress(position: u64, duration: u64, width: usize) -> Line<'stati
     ])
 }
 
-fn draw_track_block(f: &mut Frame, sp: &crate::api::Speaker, area: Rect, _show_vol: bool) {
-    if area.height == 0 {
-        return;
+/// Returns where the progress bar landed, if one was drawn.
+fn draw_track_block(f: &mut Frame, sp: &crate::api::Speaker, next: Option<&QueueItem>, area: Rect) -> Option<Rect> {
+    if area.is_empty() {
+        return None;
     }
     // Speaker label
     let label_area = Rect { y: area.y, height: 1, ..area };
     let label = Paragraph::new(Line::from(vec![
         Span::styled(
-            format!("  {} ", sp.alias.as_deref().unwrap_or(&sp.name)),
+            format!("  {} ", sp.display_name()),
             Style::default().fg(DIM),
         ),
     ]));
@@ -517,6 +908,10 @@ fn draw_track_block(f: &mut Frame, sp: &crate::api::Speaker, area: Rect, _show_v
         height: area.height.saturating_sub(1),
         ..area
     };
+    if content_area.is_empty() {
+        return None;
+    }
+    let mut seek_bar = None;
 
     if let Some(track) = &sp.track {
         let chunks = Layout::default()
@@ -538,6 +933,11 @@ fn draw_track_block(f: &mut Frame, sp: &crate::api::Speaker, area: Rect, _show_v
             Paragraph::new(Line::from(vec![
                 Span::styled("  ♫ ", Style::default().fg(PLAYING)),
                 Span::styled(&track.title, Style::default().fg(FG).add_modifier(Modifier::BOLD)),
+                match track.rating.as_deref() {
+                    Some("up") => Span::styled(" ▲", Style::default().fg(PLAYING)),
+                    Some("down") => Span::styled(" ▼", Style::default().fg(LOUDER)),
+                    _ => Span::raw(""),
+                },
             ])),
             chunks[0],
         );
@@ -567,15 +967,19 @@ fn draw_track_block(f: &mut Frame, sp: &crate::api::Speaker, area: Rect, _show_v
         };
         f.render_widget(Paragraph::new(source_line), chunks[3]);
 
-        // Segmented progress bar
-        let bar_width = chunks[5].width.saturating_sub(8) as usize;
-        let progress = segmented_progress(track.position, track.duration, bar_width);
+        // Segmented progress bar (inset 4 columns each side; skipped when that leaves nothing)
         let bar_area = Rect {
             x: chunks[5].x + 4,
             width: chunks[5].width.saturating_sub(8),
             ..chunks[5]
         };
-        f.render_widget(Paragraph::new(progress), bar_area);
+        if !bar_area.is_empty() {
+            let progress = segmented_progress(track.position, track.duration, bar_area.width as usize);
+            if !progress.spans.is_empty() {
+                seek_bar = Some(bar_area);
+            }
+            f.render_widget(Paragraph::new(progress), bar_area);
+        }
 
         // Time display
         f.render_widget(
@@ -585,16 +989,206 @@ fn draw_track_block(f: &mut Frame, sp: &crate::api::Speaker, area: Rect, _show_v
             )),
             chunks[6],
         );
+
+        // Coming up — only set in the last few seconds of a queued track
+        if let Some(next) = next {
+            f.render_widget(
+                Paragraph::new(Span::styled(
+                    format!("    Next: {} — {}", next.title, next.artist),
+                    Style::default().fg(DIM),
+                )),
+                chunks[7],
+            );
+        }
     } else {
         f.render_widget(
             Paragraph::new(Span::styled("  Nothing playing", Style::default().fg(DIM))),
             content_area,
         );
     }
+    seek_bar
+}
+
+/// Full-screen focus view for the selected speaker: large title, wide progress
+/// bar, volume, EQ and play-mode icons.
+fn draw_expanded_now_playing(f: &mut Frame, app: &App) {
+    let area = f.area();
+    let sp = match app.selected_speaker() {
+        Some(sp) => sp,
+        None => {
+            let block = panel_block("Now Playing — Esc to return", true);
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+            f.render_widget(
+                Paragraph::new(Span::styled("  Nothing playing", Style::default().fg(DIM))),
+                inner,
+            );
+            return;
+        }
+    };
+
+    let name = sp.display_name();
+    let title = format!("{} — Esc to return", name);
+    let block = panel_block(&title, true);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let (state_icon, state_color) = state_glyph(&sp.state);
+
+    let mut lines: Vec<Line> = vec![Line::from("")];
+
+    if let Some(track) = &sp.track {
+        // Letter-space the title like the splash screen when there is room
+        let spaced: String = track.title.chars()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let width = inner.width.saturating_sub(8) as usize;
+        let title = if spaced.chars().count() <= width {
+            spaced
+        } else {
+            truncate(&track.title, width)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", state_icon), Style::default().fg(state_color)),
+            Span::styled(title, Style::default().fg(FG).add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("    Artist  ", Style::default().fg(DIM)),
+            Span::styled(&track.artist, Style::default().fg(ACCENT)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("    Album   ", Style::default().fg(DIM)),
+            Span::styled(&track.album, Style::default().fg(FG)),
+        ]));
+        if !track.source.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("    Source  ", Style::default().fg(DIM)),
+                Span::styled(&track.source, Style::default().fg(FG)),
+            ]));
+        }
+        lines.push(Line::from(""));
+        let bar_width = inner.width.saturating_sub(8) as usize;
+        let mut progress = segmented_progress(track.position, track.duration, bar_width);
+        progress.spans.insert(0, Span::raw("    "));
+        lines.push(progress);
+        lines.push(Line::from(Span::styled(
+            format!("    {} / {}", format_time(track.position), format_time(track.duration)),
+            Style::default().fg(DIM),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled("  Nothing playing", Style::default().fg(DIM))));
+    }
+
+    lines.push(Line::from(""));
+
+    // Volume gauge
+    let vol_width = (inner.width as usize).saturating_sub(20).min(40);
+    lines.push(volume_gauge("    Vol     ", sp, vol_width, app.volume_levels));
+
+    // EQ
+    let mut eq = vec![];
+    if let Some(bass) = sp.bass {
+        eq.push(format!("Bass {:+}", bass));
+    }
+    if let Some(treble) = sp.treble {
+        eq.push(format!("Treble {:+}", treble));
+    }
+    if let Some(loudness) = sp.loudness {
+        eq.push(format!("Loudness {}", if loudness { "on" } else { "off" }));
+    }
+    if !eq.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("    EQ      ", Style::default().fg(DIM)),
+            Span::styled(eq.join("  "), Style::default().fg(FG)),
+        ]));
+    }
+
+    // Play mode
+    let modes = play_mode_icons(sp.play_mode.as_deref(), sp.cross_fade.unwrap_or(false));
+    if !modes.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("    Mode    ", Style::default().fg(DIM)),
+            Span::styled(modes.join("  "), Style::default().fg(ACCENT)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("    {} · {}", sp.name, sp.ip),
+        Style::default().fg(DIM),
+    )));
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Icons for the active Sonos play mode flags (shuffle / repeat / crossfade).
+fn play_mode_icons(play_mode: Option<&str>, cross_fade: bool) -> Vec<&'static str> {
+    let mut icons = vec![];
+    match play_mode.unwrap_or("NORMAL") {
+        "SHUFFLE" => icons.extend(["⤮ Shuffle", "↻ Repeat"]),
+        "SHUFFLE_NOREPEAT" => icons.push("⤮ Shuffle"),
+        "SHUFFLE_REPEAT_ONE" => icons.extend(["⤮ Shuffle", "↻1 Repeat one"]),
+        "REPEAT_ALL" => icons.push("↻ Repeat"),
+        "REPEAT_ONE" => icons.push("↻1 Repeat one"),
+        _ => {}
+    }
+    if cross_fade {
+        icons.push("≈ Crossfade");
+    }
+    icons
+}
+
+/// `play_mode_icons` as bare glyphs (`⤮ ↻ ≈`) for a Rooms group header;
+/// empty when the daemon doesn't report modes or none is on.
+fn play_mode_glyphs(sp: &crate::api::Speaker) -> String {
+    play_mode_icons(sp.play_mode.as_deref(), sp.cross_fade.unwrap_or(false))
+        .iter()
+        .filter_map(|icon| icon.split(' ').next())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Columns reserved at the right of the status line for the clock, wide
+/// enough for `12:59 PM` so messages never slide under it.
+const CLOCK_SLOT: u16 = 10;
+
+/// `23:05` or `11:05 PM` from seconds since local midnight.
+fn format_clock(secs_of_day: u64, twelve_hour: bool) -> String {
+    let (h, m) = ((secs_of_day / 3600) % 24, (secs_of_day / 60) % 60);
+    if !twelve_hour {
+        return format!("{:02}:{:02}", h, m);
+    }
+    let suffix = if h < 12 { "AM" } else { "PM" };
+    let h12 = if h % 12 == 0 { 12 } else { h % 12 };
+    format!("{}:{:02} {}", h12, m, suffix)
 }
 
 fn draw_status_line(f: &mut Frame, app: &App, area: Rect) {
+    let area = if app.show_clock && area.width > CLOCK_SLOT * 2 {
+        let [rest, slot] = Layout::horizontal([Constraint::Min(0), Constraint::Length(CLOCK_SLOT)]).areas(area);
+        let time = format_clock(app.clock.local_seconds_of_day(), app.clock_12h);
+        f.render_widget(
+            Paragraph::new(format!("{} ", time)).alignment(ratatui::layout::Alignment::Right).style(Style::default().fg(DIM).bg(BG)),
+            slot,
+        );
+        rest
+    } else {
+        area
+    };
     let msg = app.active_status();
+    if msg.is_empty() {
+        if let Some(ticker) = app.ticker() {
+            let width = area.width.saturating_sub(2) as usize;
+            // One character a second, on the app's clock.
+            let offset = app.clock.unix_now() as usize;
+            let para = Paragraph::new(format!(" {}", ticker_window(&ticker, width, offset)))
+                .style(Style::default().fg(DIM).bg(BG));
+            f.render_widget(para, area);
+            return;
+        }
+    }
     let style = if msg.is_empty() {
         Style::default().fg(DIM).bg(BG)
     } else {
@@ -604,17 +1198,66 @@ fn draw_status_line(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(para, area);
 }
 
+const TICKER_GAP: &str = "   ·   ";
+
+/// The visible slice of a marquee: static when `text` fits, otherwise scrolled
+/// `offset` characters with a gap between repetitions.
+fn ticker_window(text: &str, width: usize, offset: usize) -> String {
+    let len = text.chars().count();
+    if len <= width {
+        return text.to_string();
+    }
+    let looped: Vec<char> = text.chars().chain(TICKER_GAP.chars()).collect();
+    let start = offset % looped.len();
+    looped.iter().cycle().skip(start).take(width).collect()
+}
+
+/// Rows for the help bar: as `help_bar` asks, but always room for a prompt.
+fn help_bar_height(app: &App) -> u16 {
+    if app.command_input.is_some() || app.volume_input.is_some() {
+        return 3;
+    }
+    match app.help_bar {
+        HelpBar::Full => 3,
+        HelpBar::Compact => 1,
+        HelpBar::Hidden => 0,
+    }
+}
+
 fn draw_help_bar(f: &mut Frame, app: &App, area: Rect) {
+    if area.is_empty() {
+        return;
+    }
+    if let Some(search) = &app.history_search {
+        let found = app.history_match();
+        let mut spans = vec![
+            Span::styled("  (reverse-i-search)`", Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)),
+            Span::styled(search.query.clone(), Style::default().fg(FG)),
+            Span::styled("': ", Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)),
+        ];
+        match found {
+            Some(line) => spans.push(Span::styled(line.to_string(), Style::default().fg(FG))),
+            None if !search.query.is_empty() => {
+                spans.push(Span::styled("no such words in the chronicle", Style::default().fg(DIM)))
+            }
+            None => {}
+        }
+        spans.push(Span::styled("▌", Style::default().fg(ACCENT)));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(ACCENT))
+            .style(Style::default().bg(BG));
+        f.render_widget(Paragraph::new(Line::from(spans)).block(block), area);
+        return;
+    }
+
     if let Some(input) = &app.command_input {
-        let playlist_names: Vec<String> = app.playlists
-            .iter()
-            .map(|p| p.favorite_name.clone())
-            .collect();
         let speaker_names: Vec<String> = app.speakers
             .iter()
-            .map(|s| s.alias.as_deref().unwrap_or(&s.name).to_string())
+            .map(|s| s.display_name().to_string())
             .collect();
-        let ghost = command::autocomplete(input, &playlist_names, &speaker_names);
+        let ghost = command::autocomplete(input, &app.playlists, &speaker_names);
 
         let mut spans = vec![
             Span::styled("  :", Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)),
@@ -641,7 +1284,7 @@ fn draw_help_bar(f: &mut Frame, app: &App, area: Rect) {
                 format!("[{}▌]", input),
                 Style::default().fg(FG).add_modifier(Modifier::BOLD),
             ),
-            Span::styled("   Enter confirm   Esc cancel", Style::default().fg(DIM)),
+            Span::styled("   40 set · +5/-5 step · m mute   Enter confirm   Esc cancel", Style::default().fg(DIM)),
         ]);
         let block = Block::default()
             .borders(Borders::ALL)
@@ -687,6 +1330,10 @@ fn draw_help_bar(f: &mut Frame, app: &App, area: Rect) {
     ]);
 
     let help = Line::from(help_spans);
+    if app.help_bar == HelpBar::Compact {
+        f.render_widget(Paragraph::new(help).style(Style::default().bg(BG)), area);
+        return;
+    }
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -714,18 +1361,41 @@ fn draw_help_overlay(f: &mut Frame) {
         Line::from(vec![Span::styled("  Tab        ", Style::default().fg(ACCENT)), Span::styled("Cycle panels — as the Fellowship moved between realms", Style::default().fg(FG))]),
         Line::from(vec![Span::styled("  ↑ / k      ", Style::default().fg(ACCENT)), Span::styled("Move up", Style::default().fg(FG))]),
         Line::from(vec![Span::styled("  ↓ / j      ", Style::default().fg(ACCENT)), Span::styled("Move down", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  Shift+↑/↓  ", Style::default().fg(ACCENT)), Span::styled("Move playlist (playlist_sort: manual)", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  x          ", Style::default().fg(ACCENT)), Span::styled("Stop (releases radio / line-in, unlike pause)", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  z          ", Style::default().fg(ACCENT)), Span::styled("Do not disturb — \"all\" actions skip this speaker (☾)", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  R / F5     ", Style::default().fg(ACCENT)), Span::styled("Refresh speakers & playlists now", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  1–9        ", Style::default().fg(ACCENT)), Span::styled("Jump to speaker by its faint number", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  d          ", Style::default().fg(ACCENT)), Span::styled("Dashboard — every room on one line each", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  h          ", Style::default().fg(ACCENT)), Span::styled("Help bar: full, one line, hidden", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  i          ", Style::default().fg(ACCENT)), Span::styled("Speaker detail — IP, model, UID, EQ", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  Z          ", Style::default().fg(ACCENT)), Span::styled("Zen — only the track, full screen (any key leaves)", Style::default().fg(FG))]),
         Line::from(vec![Span::styled("  Enter      ", Style::default().fg(ACCENT)), Span::styled("Play selected playlist on selected speaker", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  Enter      ", Style::default().fg(ACCENT)), Span::styled("On Now Playing: expand the focused room — gaze into the Palantir", Style::default().fg(FG))]),
         Line::from(""),
         Line::from(vec![Span::styled("  PLAYBACK", Style::default().fg(ACCENT).add_modifier(Modifier::BOLD))]),
         Line::from(vec![Span::styled("  Space      ", Style::default().fg(ACCENT)), Span::styled("Pause / resume — even hobbits need rest", Style::default().fg(FG))]),
         Line::from(vec![Span::styled("  n          ", Style::default().fg(ACCENT)), Span::styled("Next track — onwards, to Rivendell", Style::default().fg(FG))]),
         Line::from(vec![Span::styled("  p          ", Style::default().fg(ACCENT)), Span::styled("Previous track — back to the Shire", Style::default().fg(FG))]),
-        Line::from(vec![Span::styled("  + / =      ", Style::default().fg(ACCENT)), Span::styled("Volume up 5", Style::default().fg(FG))]),
-        Line::from(vec![Span::styled("  -          ", Style::default().fg(ACCENT)), Span::styled("Volume down 5", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  + / =      ", Style::default().fg(ACCENT)), Span::styled("Volume up 5 (whole group from Now Playing)", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  -          ", Style::default().fg(ACCENT)), Span::styled("Volume down 5 (whole group from Now Playing)", Style::default().fg(FG))]),
         Line::from(vec![Span::styled("  v          ", Style::default().fg(ACCENT)), Span::styled("Set exact volume — speak your will", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  m          ", Style::default().fg(ACCENT)), Span::styled("Mute / unmute selected speaker", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  M          ", Style::default().fg(ACCENT)), Span::styled("Mute / unmute all speakers", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  S          ", Style::default().fg(ACCENT)), Span::styled("Solo — mute all but selected; again to restore", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  B          ", Style::default().fg(ACCENT)), Span::styled("Balance — set the group to its average volume", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  L          ", Style::default().fg(ACCENT)), Span::styled("Event log — recent actions and messages", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  c          ", Style::default().fg(ACCENT)), Span::styled("Center the selected speaker in Rooms", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  P          ", Style::default().fg(ACCENT)), Span::styled("Jump to the next playing group's coordinator", Style::default().fg(FG))]),
         Line::from(""),
         Line::from(vec![Span::styled("  GROUPS", Style::default().fg(ACCENT).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("  g          ", Style::default().fg(ACCENT)), Span::styled("Toggle group all speakers — assemble the Fellowship", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  g          ", Style::default().fg(ACCENT)), Span::styled("Group all (finishing a partial grouping); once all are one, ungroup", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  G          ", Style::default().fg(ACCENT)), Span::styled("Add or drop rooms in the Now Playing group", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  o          ", Style::default().fg(ACCENT)), Span::styled("Selected room: leave its group, or join the biggest one", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  F          ", Style::default().fg(ACCENT)), Span::styled("Follow mode — select whatever starts playing", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  t / T      ", Style::default().fg(ACCENT)), Span::styled("Like / dislike the track (services that take ratings)", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  `          ", Style::default().fg(ACCENT)), Span::styled("Swap to the previously selected room", Style::default().fg(FG))]),
+        Line::from(vec![Span::styled("  Ctrl-X     ", Style::default().fg(ACCENT)), Span::styled("Panic stop: pause and mute everything (:undo restores)", Style::default().fg(FG))]),
         Line::from(""),
         Line::from(vec![Span::styled("  PODCASTS", Style::default().fg(ACCENT).add_modifier(Modifier::BOLD))]),
         Line::from(vec![Span::styled("  s          ", Style::default().fg(ACCENT)), Span::styled("Toggle source — Playlists / Podcasts", Style::default().fg(FG))]),
@@ -753,6 +1423,450 @@ fn draw_help_overlay(f: &mut Frame) {
     f.render_widget(para, inner);
 }
 
+/// Small centered gauge, OSD-style, shown briefly after a volume change.
+fn draw_osd(f: &mut Frame, kind: OsdKind, value: u8) {
+    let area = f.area();
+    let popup_w = 34.min(area.width);
+    let popup_h = 3.min(area.height);
+    if popup_w < 3 || popup_h < 3 {
+        return; // no room inside the border for the bar
+    }
+    let x = area.x + (area.width.saturating_sub(popup_w)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_h)) / 2;
+    let popup_area = Rect::new(x, y, popup_w, popup_h);
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let label = match kind {
+        OsdKind::Volume => "Volume",
+    };
+    let block = Block::default()
+        .title(format!(" {} ", label))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(ACCENT))
+        .style(Style::default().bg(BG));
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let (bar, color) = volume_bar(value, (inner.width as usize).saturating_sub(6));
+    let line = Line::from(vec![
+        Span::styled(format!(" {}", bar), Style::default().fg(color)),
+        Span::styled(format!(" {:>3}", value), Style::default().fg(FG).add_modifier(Modifier::BOLD)),
+    ]);
+    f.render_widget(Paragraph::new(line), inner);
+}
+
+/// The event log: newest entry at the bottom, `log_scroll` lines back in time.
+fn draw_log_overlay(f: &mut Frame, app: &App) {
+    let area = f.area();
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default()
+        .title(" The Chronicle — ↑/↓ scroll, Esc to close ")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(ACCENT))
+        .style(Style::default().bg(BG));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if app.event_log.is_empty() {
+        f.render_widget(Paragraph::new(Span::styled("  Nothing has yet come to pass.", Style::default().fg(DIM))), inner);
+        return;
+    }
+    let end = app.event_log.len().saturating_sub(app.log_scroll);
+    let start = end.saturating_sub(inner.height as usize);
+    let lines: Vec<Line> = app.event_log.range(start..end).map(|e| Line::from(vec![
+        Span::styled(format!("  {}  ", crate::clock::format_hms(e.at)), Style::default().fg(DIM)),
+        Span::styled(e.text.as_str(), Style::default().fg(FG)),
+    ])).collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Narrowest track column worth showing in the `list` table.
+const MIN_TRACK_COLUMN: usize = 12;
+
+/// `list`: a header and one plain-text row per speaker — room, state, volume,
+/// mute, then its group's track cut to fit `width`. Too narrow for all of
+/// that, mute goes first, then state, then the track.
+fn speaker_table(app: &App, width: usize) -> Vec<String> {
+    // Room and volume always stay; the others cost their width plus a gap
+    let mut fixed = 14 + 1 + 3;
+    let state = fixed + 8 + 1 + MIN_TRACK_COLUMN <= width;
+    if state {
+        fixed += 8;
+    }
+    let mute = fixed + 6 + 1 + MIN_TRACK_COLUMN <= width;
+    if mute {
+        fixed += 6;
+    }
+    let track_room = width.saturating_sub(fixed + 1);
+    let row = |cells: [&str; 5]| {
+        let mut line = format!("{:<14} ", truncate(cells[0], 14));
+        if state {
+            line += &format!("{:<7} ", cells[1]);
+        }
+        line += &format!("{:>3}", cells[2]);
+        if mute {
+            line += &format!(" {:<5}", cells[3]);
+        }
+        if track_room >= MIN_TRACK_COLUMN {
+            line += &format!(" {}", truncate(cells[4], track_room));
+        }
+        line.trim_end().to_string()
+    };
+    let mut lines = vec![row(["Room", "State", "Vol", "Mute", "Track"])];
+    for sp in &app.speakers {
+        let state = match sp.state.as_str() {
+            "PLAYING" => "playing",
+            "PAUSED_PLAYBACK" => "paused",
+            "STOPPED" => "stopped",
+            _ => "—",
+        };
+        let track = app.coordinator_of(sp).track.as_ref()
+            .map(|t| if t.artist.is_empty() { t.title.clone() } else { format!("{} — {}", t.title, t.artist) })
+            .unwrap_or_default();
+        lines.push(row([&sp.label(), state, &sp.volume.to_string(), if sp.muted { "muted" } else { "" }, &track]));
+    }
+    lines
+}
+
+/// The `list` table over the whole screen, its rows scrolled by `scroll`.
+fn draw_roster_overlay(f: &mut Frame, app: &App, scroll: usize) {
+    let area = f.area();
+    f.render_widget(ratatui::widgets::Clear, area);
+    let block = Block::default()
+        .title(" Every Hall — ↑/↓ scroll, any other key closes ")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(ACCENT))
+        .style(Style::default().bg(BG));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let mut table = speaker_table(app, (inner.width as usize).saturating_sub(2)).into_iter();
+    let header = table.next().unwrap_or_default();
+    let mut lines = vec![Line::from(Span::styled(format!(" {}", header), Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)))];
+    lines.extend(table.skip(scroll).map(|row| Line::from(Span::styled(format!(" {}", row), Style::default().fg(FG)))));
+    if app.speakers.is_empty() {
+        lines.push(Line::from(Span::styled(" No halls answer — is sonosd running?", Style::default().fg(DIM))));
+    }
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// `42m`, `5h 07m` — listening totals don't need seconds.
+fn format_listening(d: std::time::Duration) -> String {
+    let mins = d.as_secs() / 60;
+    if mins < 60 {
+        format!("{}m", mins)
+    } else {
+        format!("{}h {:02}m", mins / 60, mins % 60)
+    }
+}
+
+/// Headline numbers, then listening time overall and per playlist, from the
+/// history loaded on `stats`.
+fn draw_stats_overlay(f: &mut Frame, app: &App, entries: &[crate::history::PlayEntry]) {
+    use crate::history;
+    let by_playlist = history::listening_by_playlist(entries);
+    let untimed = entries.iter().filter(|e| e.duration.is_none()).count();
+    let now = app.clock.unix_now();
+    let plural = |n: usize, one: &str, many: &str| format!("{} {}", n, if n == 1 { one } else { many });
+    let plays = format!("  {} · {} this week", plural(entries.len(), "play", "plays"), history::plays_this_week(entries, now));
+    let reach = format!(
+        "  {} · longest streak {}",
+        plural(history::distinct_playlists(entries), "playlist", "playlists"),
+        plural(history::listening_streak(entries, now) as usize, "day", "days"),
+    );
+
+    let area = f.area();
+    let popup_w = (area.width * 60 / 100).max(36).min(area.width);
+    let popup_h = (by_playlist.len() as u16 + 8).min(area.height);
+    let x = area.x + (area.width.saturating_sub(popup_w)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_h)) / 2;
+    let popup_area = Rect::new(x, y, popup_w, popup_h);
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Listening — any key to close ")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(ACCENT))
+        .style(Style::default().bg(BG));
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let name_w = (inner.width as usize).saturating_sub(24).max(8);
+    let mut total = vec![
+        Span::styled(format!("  {:<w$}", "All playlists", w = name_w), Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)),
+        Span::styled(format!("{:>8}", format_listening(crate::history::total_listening_time(entries))), Style::default().fg(FG)),
+    ];
+    if untimed > 0 {
+        total.push(Span::styled(format!("  +{} untimed", untimed), Style::default().fg(DIM)));
+    }
+    let mut lines = vec![
+        Line::from(Span::styled(plays, Style::default().fg(FG))),
+        Line::from(Span::styled(reach, Style::default().fg(FG))),
+        Line::from(""),
+        Line::from(total),
+        Line::from(""),
+    ];
+    for (alias, listened, plays) in &by_playlist {
+        let name = app.playlists.iter()
+            .find(|p| &p.alias == alias)
+            .map(|p| p.favorite_name.as_str())
+            .unwrap_or(alias);
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<w$}", truncate(name, name_w), w = name_w), Style::default().fg(FG)),
+            Span::styled(format!("{:>8}", format_listening(*listened)), Style::default().fg(FG)),
+            Span::styled(format!("  {} play{}", plays, if *plays == 1 { "" } else { "s" }), Style::default().fg(DIM)),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// `3m`, `5h 07m`, `2d 4h` before now; under a minute is `just now`.
+fn format_ago(secs: u64) -> String {
+    let mins = secs / 60;
+    match mins {
+        0 => "just now".to_string(),
+        1..60 => format!("{}m ago", mins),
+        60..1440 => format!("{}h {:02}m ago", mins / 60, mins % 60),
+        _ => format!("{}d {}h ago", mins / 1440, mins % 1440 / 60),
+    }
+}
+
+/// `why`: the plays that make up `alias`'s seven-day popularity count, each
+/// with how long ago it was, from the history loaded on `why`.
+fn draw_why_overlay(f: &mut Frame, app: &App, alias: &str, entries: &[crate::history::PlayEntry]) {
+    use crate::history;
+    let now = app.clock.unix_now();
+    let plays = history::popularity_plays(entries, alias, now);
+    let name = app.playlists.iter()
+        .find(|p| p.alias == alias)
+        .map(|p| p.favorite_name.as_str())
+        .unwrap_or(alias);
+
+    let area = f.area();
+    let popup_w = 44.min(area.width);
+    let popup_h = (plays.len() as u16 + 7).min(area.height);
+    let x = area.x + (area.width.saturating_sub(popup_w)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_h)) / 2;
+    let popup_area = Rect::new(x, y, popup_w, popup_h);
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Why here? — any key to close ")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(ACCENT))
+        .style(Style::default().bg(BG));
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let count = match plays.len() {
+        0 => "  No plays in the last 7 days".to_string(),
+        1 => "  1 play in the last 7 days".to_string(),
+        n => format!("  {} plays in the last 7 days", n),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(format!("  {}", truncate(name, (inner.width as usize).saturating_sub(4))), Style::default().fg(ACCENT).add_modifier(Modifier::BOLD))),
+        Line::from(Span::styled(count, Style::default().fg(FG))),
+    ];
+    if app.active_sort() != "popularity" {
+        lines.push(Line::from(Span::styled(format!("  (sorted {} for now, not by popularity)", app.active_sort()), Style::default().fg(DIM))));
+    }
+    lines.push(Line::from(""));
+    for at in &plays {
+        let ago = now.saturating_sub(*at);
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<14}", format_ago(ago)), Style::default().fg(FG)),
+            Span::styled(history::weekday_name(*at, 0), Style::default().fg(DIM)),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Block characters for a sparkline, lowest to highest.
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One block per count, scaled so the largest gets the full block; zero is a dot.
+fn sparkline(counts: &[usize]) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    counts.iter()
+        .map(|&c| if c == 0 { '·' } else { SPARKS[(c * (SPARKS.len() - 1)).div_ceil(max)] })
+        .collect()
+}
+
+/// The past seven (UTC) days: plays, the top three playlists, the busiest day
+/// and a plays-per-day sparkline, from the history loaded on `recap`.
+fn draw_recap_overlay(f: &mut Frame, app: &App, entries: &[crate::history::PlayEntry]) {
+    use crate::history;
+    let now = app.clock.unix_now();
+    let per_day = history::plays_per_day(entries, now, 7);
+    let week_start = (now - now % (24 * 3600)).saturating_sub(6 * 24 * 3600);
+    let week: Vec<history::PlayEntry> = entries.iter().filter(|e| e.played_at <= now).cloned().collect();
+    let top = history::top_playlists_since(&week, week_start, 3);
+    let total: usize = per_day.iter().sum();
+
+    let area = f.area();
+    let popup_w = 44.min(area.width);
+    let popup_h = (top.len() as u16 + 9).min(area.height);
+    let x = area.x + (area.width.saturating_sub(popup_w)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_h)) / 2;
+    let popup_area = Rect::new(x, y, popup_w, popup_h);
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let block = Block::default()
+        .title(" The week in song — any key to close ")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(ACCENT))
+        .style(Style::default().bg(BG));
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    if total == 0 {
+        let quiet = Line::from(Span::styled("  No tales were sung these seven days.", Style::default().fg(DIM)));
+        f.render_widget(Paragraph::new(quiet), inner);
+        return;
+    }
+    let name_w = (inner.width as usize).saturating_sub(14).max(8);
+    let mut lines = vec![Line::from(vec![
+        Span::styled(format!("  {} play{}", total, if total == 1 { "" } else { "s" }), Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)),
+        Span::styled("  ", Style::default()),
+        Span::styled(sparkline(&per_day), Style::default().fg(PLAYING)),
+    ])];
+    // Weekday initials under the sparkline, oldest first
+    let initials: String = (0..7).rev().map(|ago| history::weekday_name(now, ago).chars().next().unwrap_or(' ')).collect();
+    let pad = format!("  {} play{}  ", total, if total == 1 { "" } else { "s" }).chars().count();
+    lines.push(Line::from(Span::styled(format!("{}{}", " ".repeat(pad), initials), Style::default().fg(DIM))));
+    lines.push(Line::from(""));
+    for (alias, plays) in &top {
+        let name = app.playlists.iter()
+            .find(|p| &p.alias == alias)
+            .map(|p| p.favorite_name.as_str())
+            .unwrap_or(alias);
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<w$}", truncate(name, name_w), w = name_w), Style::default().fg(FG)),
+            Span::styled(format!("  {} play{}", plays, if *plays == 1 { "" } else { "s" }), Style::default().fg(DIM)),
+        ]));
+    }
+    // Busiest day: the most plays, the most recent on a tie
+    let (busiest, most) = per_day.iter().enumerate().max_by_key(|(_, c)| **c).map(|(i, c)| (i, *c)).unwrap_or((6, 0));
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("  Busiest: ", Style::default().fg(DIM)),
+        Span::styled(format!("{} ({} play{})", history::weekday_name(now, 6 - busiest as u64), most, if most == 1 { "" } else { "s" }), Style::default().fg(FG)),
+    ]));
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Everything known about one speaker, for matching it to a router's device list.
+fn draw_detail_overlay(f: &mut Frame, app: &App, id: &str) {
+    let sp = app.speakers.iter().find(|s| s.id() == id);
+    let on_off = |b: bool| if b { "on" } else { "off" }.to_string();
+    let rows: Vec<(&str, String)> = match sp {
+        None => vec![("", "This speaker has left the network.".to_string())],
+        Some(sp) => {
+            let coordinator = app.speakers.iter()
+                .find(|c| sp.coordinator_key() == Some(c.group_key()))
+                .map(|c| c.display_name().to_string());
+            let mut rows = vec![
+                ("Room", sp.name.clone()),
+                ("Alias", sp.alias.clone().unwrap_or_else(|| "—".to_string())),
+                ("IP", sp.ip.clone()),
+                ("Model", sp.model.clone().unwrap_or_else(|| "unknown".to_string())),
+                ("UID", if sp.uid.is_empty() { "—".to_string() } else { sp.uid.clone() }),
+                ("Group", match coordinator {
+                    Some(_) if sp.is_coordinator() => "coordinator".to_string(),
+                    Some(c) => format!("with {}", c),
+                    None => "solo".to_string(),
+                }),
+                ("State", sp.state.clone()),
+                ("Volume", format!("{}{}", sp.volume, if sp.muted { " (muted)" } else { "" })),
+            ];
+            if let (Some(bass), Some(treble)) = (sp.bass, sp.treble) {
+                rows.push(("EQ", format!("bass {:+} · treble {:+}", bass, treble)));
+            }
+            if let Some(loudness) = sp.loudness {
+                rows.push(("Loudness", on_off(loudness)));
+            }
+            if let Some(mode) = &sp.play_mode {
+                rows.push(("Play mode", mode.clone()));
+            }
+            if let Some(cross_fade) = sp.cross_fade {
+                rows.push(("Crossfade", on_off(cross_fade)));
+            }
+            rows
+        }
+    };
+
+    let area = f.area();
+    let popup_w = 56.min(area.width);
+    let popup_h = (rows.len() as u16 + 2).min(area.height);
+    let x = area.x + (area.width.saturating_sub(popup_w)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_h)) / 2;
+    let popup_area = Rect::new(x, y, popup_w, popup_h);
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let title = format!(" {} — any key to close ", sp.map(|s| s.display_name()).unwrap_or("Speaker"));
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(ACCENT))
+        .style(Style::default().bg(BG));
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let lines: Vec<Line> = rows.into_iter()
+        .map(|(label, value)| Line::from(vec![
+            Span::styled(format!("  {:<11}", label), Style::default().fg(DIM)),
+            Span::styled(value, Style::default().fg(FG)),
+        ]))
+        .collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// The `G` picker: every room, checked if it's in the edited group.
+fn draw_group_overlay(f: &mut Frame, app: &App) {
+    let Some((coordinator, picked)) = app.group_edit_pick() else { return };
+    let area = f.area();
+    let popup_w = 44.min(area.width);
+    let popup_h = (app.speakers.len() as u16 + 2).min(area.height);
+    let x = area.x + (area.width.saturating_sub(popup_w)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_h)) / 2;
+    let popup_area = Rect::new(x, y, popup_w, popup_h);
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    let title = format!(" {}'s group — space toggles, esc closes ", coordinator.display_name());
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(ACCENT))
+        .style(Style::default().bg(BG));
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let lines: Vec<Line> = app.speakers.iter()
+        .map(|sp| {
+            let member = app.in_group_of(sp, coordinator);
+            let mark = if member { "[x]" } else { "[ ]" };
+            let mut style = Style::default().fg(if member { PLAYING } else { FG });
+            if sp.id() == picked.id() {
+                style = style.bg(HIGHLIGHT_BG).add_modifier(Modifier::BOLD);
+            }
+            let mut spans = vec![Span::styled(format!("  {} {}", mark, sp.display_name()), style)];
+            if sp.id() == coordinator.id() {
+                spans.push(Span::styled("  (leads)", Style::default().fg(DIM)));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
 fn draw_episode_popup(f: &mut Frame, app: &App) {
     let ep = match app.selected_episode() {
         Some(ep) => ep,
@@ -781,6 +1895,7 @@ fn draw_episode_popup(f: &mut Frame, app: &App) {
 
     let duration_str = format_time(ep.duration);
     let played = if ep.played == 1 { " (played)" } else { "" };
+    let published = ep.published.get(..10).unwrap_or(&ep.published);
 
     let lines = vec![
         Line::from(vec![
@@ -788,7 +1903,7 @@ fn draw_episode_popup(f: &mut Frame, app: &App) {
             Span::styled(&ep.title, Style::default().fg(FG).add_modifier(Modifier::BOLD)),
         ]),
         Line::from(vec![
-            Span::styled(format!("    {}  {}", duration_str, played), Style::default().fg(DIM)),
+            Span::styled(format!("    {}  {}{}", published, duration_str, played), Style::default().fg(DIM)),
         ]),
     ];
 
@@ -796,7 +1911,7 @@ fn draw_episode_popup(f: &mut Frame, app: &App) {
     f.render_widget(wrap, inner);
 }
 
-fn format_time(seconds: u64) -> String {
+pub fn format_time(seconds: u64) -> String {
     format!("{}:{:02}", seconds / 60, seconds % 60)
 }
 
@@ -809,3 +1924,492 @@ fn truncate(s: &str, max: usize) -> String {
         s.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    /// Two grouped speakers plus a solo one, all with a track — exercises every panel.
+    fn busy_app() -> App {
+        let mut app = App::new();
+        let speaker = |uid: &str, name: &str, coordinator: Option<&str>| -> crate::api::Speaker {
+            serde_json::from_value(serde_json::json!({
+                "uid": uid, "coordinator_uid": coordinator, "name": name, "alias": null,
+                "ip": "10.0.0.1", "volume": 40, "muted": false, "state": "PLAYING",
+                "group_coordinator": null,
+                "track": {"title": "Elvis in the Freezer", "artist": "Ratboys", "album": "GN",
+                          "duration": 231, "position": 83},
+            })).unwrap()
+        };
+        app.speakers = vec![
+            speaker("RINCON_a", "Den", Some("RINCON_a")),
+            speaker("RINCON_b", "Kitchen", Some("RINCON_a")),
+            speaker("RINCON_c", "Office", None),
+        ];
+        app
+    }
+
+    #[test]
+    fn test_balance_color_flags_members_far_from_the_average() {
+        assert_eq!(balance_color(40, 30, 15), DIM);
+        assert_eq!(balance_color(45, 30, 15), LOUDER);
+        assert_eq!(balance_color(10, 30, 15), QUIETER);
+        assert_eq!(balance_color(90, 30, 0), DIM);
+    }
+
+    #[test]
+    fn test_state_glyph_distinguishes_stopped() {
+        assert_eq!(state_glyph("STOPPED").0, "■");
+        assert_eq!(state_glyph("PAUSED_PLAYBACK").0, "‖");
+        assert_eq!(state_glyph("TRANSITIONING").0, "·");
+    }
+
+    #[test]
+    fn test_jump_digit_only_for_first_nine() {
+        assert_eq!(jump_digit(Some(0)), "1");
+        assert_eq!(jump_digit(Some(8)), "9");
+        assert_eq!(jump_digit(Some(9)), " ");
+        assert_eq!(jump_digit(None), " ");
+    }
+
+    #[test]
+    fn test_draw_records_progress_bars_as_seek_targets() {
+        let app = busy_app();
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let targets = app.seek_targets.borrow();
+        assert_eq!(targets.len(), 2, "one per playing group");
+        assert!(targets.iter().all(|t| t.duration == 231 && t.area.width > 0));
+    }
+
+    #[test]
+    fn test_speaker_table_drops_columns_as_the_width_shrinks() {
+        let mut app = busy_app();
+        app.speakers[2].muted = true;
+        app.speakers[2].state = "PAUSED_PLAYBACK".into();
+        app.speakers[2].track = None;
+        let wide = speaker_table(&app, 80);
+        assert_eq!(wide[0], "Room           State   Vol Mute  Track");
+        assert_eq!(wide[1], "Den            playing  40       Elvis in the Freezer — Ratboys");
+        assert_eq!(wide[2], "Kitchen        playing  40       Elvis in the Freezer — Ratboys", "followers show the group's track");
+        assert_eq!(wide[3], "Office         paused   40 muted");
+
+        let snug = speaker_table(&app, 44);
+        assert_eq!(snug[0], "Room           State   Vol Track");
+        assert_eq!(snug[1], "Den            playing  40 Elvis in the Fre…");
+        assert_eq!(speaker_table(&app, 34)[1], "Den             40 Elvis in the F…");
+        assert_eq!(speaker_table(&app, 20)[3], "Office          40");
+    }
+
+    #[test]
+    fn test_group_header_shows_the_zone_play_mode_and_fits_it() {
+        let mut app = busy_app();
+        app.speakers[1].alias = Some("The Kitchen Hall".into());
+        let header = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+            terminal.draw(|f| draw(f, app)).unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            (0..buffer.area.height)
+                .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+                .find(|row| row.contains("GROUPED"))
+                .unwrap()
+        };
+        let row = header(&app);
+        assert!(row.contains("Den + The Kitchen Hall ") && !row.contains('⤮'), "no modes reported, nothing shown: {}", row);
+
+        app.speakers[0].play_mode = Some("SHUFFLE_NOREPEAT".into());
+        app.speakers[0].cross_fade = Some(true);
+        let row = header(&app);
+        assert!(row.contains("Den + The Kitchen Ha… ⤮ ≈"), "{}", row);
+        assert_eq!(play_mode_glyphs(&app.speakers[2]), "");
+    }
+
+    #[test]
+    fn test_draw_records_room_rows_and_the_playlists_panel() {
+        let app = busy_app();
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let rows = app.room_rows.borrow();
+        assert_eq!(rows.len(), 3);
+        for row in rows.iter() {
+            let name = app.speakers.iter().find(|s| s.id() == row.speaker).unwrap().label();
+            let line = terminal.backend().buffer().content()
+                .iter()
+                .skip((row.area.y * 120 + row.area.x) as usize)
+                .take(row.area.width as usize)
+                .map(|c| c.symbol())
+                .collect::<String>();
+            assert!(line.contains(&name), "{:?} not on its row: {:?}", name, line);
+        }
+        assert!(app.playlists_area.get().area() > 0);
+    }
+
+    #[test]
+    fn test_rooms_mark_speakers_a_sleep_timer_will_pause() {
+        let mut app = busy_app();
+        let moons = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+            terminal.draw(|f| draw(f, app)).unwrap();
+            terminal.backend().buffer().content().iter().filter(|c| c.symbol() == "⏾").count()
+        };
+        assert_eq!(moons(&app), 0);
+        app.sleep_timers.push(crate::app::SleepTimer {
+            target: Some("RINCON_a".into()),
+            label: "Den".into(),
+            until: app.clock.now() + std::time::Duration::from_secs(600),
+            fade: false,
+            faded_from: vec![],
+        });
+        assert_eq!(moons(&app), 2, "Den's group, not Office");
+        app.sleep_until = Some(app.clock.now() + std::time::Duration::from_secs(600));
+        assert_eq!(moons(&app), 3, "the whole-house timer reaches everyone");
+        app.do_not_disturb = vec!["Office".into()];
+        assert_eq!(moons(&app), 2, "the whole-house timer spares do-not-disturb rooms");
+        app.sleep_until = None;
+        app.sleep_timers[0].target = None;
+        assert_eq!(moons(&app), 2, "so does an everyone-scoped timer");
+        app.sleep_timers[0].target = Some("RINCON_c".into());
+        assert_eq!(moons(&app), 1, "a timer aimed at the room still reaches it");
+    }
+
+    #[test]
+    fn test_flat_rooms_layout_keeps_the_list_with_group_hints() {
+        let mut app = busy_app();
+        app.speakers[1].volume = 60;
+        let rooms = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+            terminal.draw(|f| draw(f, app)).unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..buffer.area.height)
+                .map(|y| (0..36).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        assert!(rooms(&app).iter().any(|l| l.contains("GROUPED")));
+        app.flat_rooms = true;
+        let lines = rooms(&app);
+        assert!(!lines.iter().any(|l| l.contains("GROUPED")));
+        assert!(lines.iter().any(|l| l.contains("Den") && l.contains("⊕50")), "coordinator carries the average");
+        assert!(lines.iter().any(|l| l.contains("    Kitchen")), "follower is indented");
+        assert!(lines.iter().any(|l| l.contains("Office") && !l.contains('⊕')));
+    }
+
+    #[test]
+    fn test_format_clock_12_and_24_hour() {
+        let t = 23 * 3600 + 5 * 60;
+        assert_eq!(format_clock(t, false), "23:05");
+        assert_eq!(format_clock(t, true), "11:05 PM");
+        assert_eq!(format_clock(30 * 60, true), "12:30 AM");
+        assert_eq!(format_clock(7 * 3600, false), "07:00");
+    }
+
+    #[test]
+    fn test_clock_sits_right_of_status_message() {
+        let mut app = busy_app();
+        app.show_clock = true;
+        app.clock = std::rc::Rc::new(crate::clock::MockClock::new(1_772_000_000)); // 06:13 UTC
+        app.set_status("The Fellowship rests.", crate::app::Linger::Sticky);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let buf = terminal.backend().buffer();
+        let line: String = buf.content().chunks(80)
+            .map(|row| row.iter().map(|c| c.symbol()).collect::<String>())
+            .find(|l| l.contains("The Fellowship rests."))
+            .unwrap();
+        assert!(line.trim_end().ends_with("06:13"));
+    }
+
+    #[test]
+    fn test_ticker_scrolls_with_the_app_clock() {
+        let mut app = busy_app();
+        let clock = std::rc::Rc::new(crate::clock::MockClock::new(1_772_000_000));
+        app.clock = clock.clone();
+        let status_row = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(20, 1)).unwrap();
+            terminal.draw(|f| draw_status_line(f, app, f.area())).unwrap();
+            terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect::<String>()
+        };
+        let before = status_row(&app);
+        assert_eq!(status_row(&app), before, "a frozen clock holds the ticker still");
+        clock.advance(std::time::Duration::from_secs(1));
+        let after = status_row(&app);
+        assert_eq!(after.chars().nth(1), before.chars().nth(2), "one character a second");
+    }
+
+    #[test]
+    fn test_volume_color_by_level_and_off_without_levels() {
+        let levels = Some((25, 80));
+        assert_eq!(volume_color(10, levels, DIM), SOFT);
+        assert_eq!(volume_color(25, levels, DIM), SOFT);
+        assert_eq!(volume_color(50, levels, DIM), DIM);
+        assert_eq!(volume_color(80, levels, FG), LOUD);
+        assert_eq!(volume_color(100, None, FG), FG);
+
+        let mut app = busy_app();
+        app.speakers[2].volume = 95;
+        let gauge = volume_gauge("Vol ", &app.speakers[2], 10, app.volume_levels);
+        assert_eq!(gauge.spans[2].style.fg, Some(LOUD));
+        app.volume_levels = None;
+        let gauge = volume_gauge("Vol ", &app.speakers[2], 10, app.volume_levels);
+        assert_eq!(gauge.spans[2].style.fg, Some(FG));
+    }
+
+    #[test]
+    fn test_muted_rooms_strike_their_volume_bar() {
+        let mut app = busy_app();
+        let mut lines = vec![];
+        render_speaker_row(&mut lines, &app, &app.speakers[2], None, false, 10, GroupHint::None);
+        assert_eq!(lines.len(), 2, "a name line and a bar, as before");
+        assert!(!lines[1].spans[1].style.add_modifier.contains(Modifier::CROSSED_OUT));
+        app.speakers[2].muted = true;
+        let mut lines = vec![];
+        render_speaker_row(&mut lines, &app, &app.speakers[2], None, false, 10, GroupHint::None);
+        assert!(lines[1].spans[1].style.add_modifier.contains(Modifier::CROSSED_OUT));
+    }
+
+    #[test]
+    fn test_volume_gauge_marks_muted_speakers() {
+        let mut app = busy_app();
+        let sp = &mut app.speakers[2];
+        let text = |l: &Line| l.spans.iter().map(|s| s.content.as_ref()).collect::<String>();
+        let audible = volume_gauge("Vol ", sp, 10, None);
+        assert!(!text(&audible).contains("(muted)"));
+        assert!(!audible.spans[1].style.add_modifier.contains(Modifier::CROSSED_OUT));
+        sp.muted = true;
+        let muted = volume_gauge("Vol ", sp, 10, None);
+        assert!(text(&muted).ends_with(" 40 (muted)"));
+        assert!(muted.spans[1].style.add_modifier.contains(Modifier::CROSSED_OUT));
+    }
+
+    #[test]
+    fn test_help_bar_collapses_but_prompts_stay() {
+        let mut app = busy_app();
+        let screen = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+            terminal.draw(|f| draw(f, app)).unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            (0..30).map(|y| (0..120).map(|x| buffer[(x, y)].symbol()).collect::<String>()).collect::<Vec<_>>()
+        };
+        assert!(screen(&app)[28].contains(": cmd  ?"), "full bar sits inside its border");
+        app.help_bar = HelpBar::Compact;
+        assert!(screen(&app)[29].contains(": cmd  ?"));
+        app.help_bar = HelpBar::Hidden;
+        assert!(!screen(&app).iter().any(|row| row.contains(": cmd  ?")));
+        app.command_input = Some("pla".to_string());
+        assert!(screen(&app)[28].contains(":pla"));
+    }
+
+    #[test]
+    fn test_detail_overlay_shows_ip_only_when_asked() {
+        let mut app = busy_app();
+        app.speakers[2].model = Some("Sonos One".to_string());
+        let text = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+            terminal.draw(|f| draw(f, app)).unwrap();
+            terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect::<String>()
+        };
+        assert!(!text(&app).contains("10.0.0.1"));
+        app.detail = Some("RINCON_c".to_string());
+        let shown = text(&app);
+        assert!(shown.contains("10.0.0.1") && shown.contains("Sonos One") && shown.contains("RINCON_c"));
+        app.speakers.pop();
+        assert!(text(&app).contains("left the network"));
+    }
+
+    #[test]
+    fn test_rooms_title_marks_partial_and_full_grouping() {
+        let title = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+            terminal.draw(|f| draw(f, app)).unwrap();
+            terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect::<String>()
+        };
+        let mut app = busy_app();
+        assert!(title(&app).contains("Rooms ◐"));
+        app.speakers[2].coordinator_uid = Some("RINCON_a".into());
+        assert!(title(&app).contains("Rooms ●"));
+        for sp in &mut app.speakers {
+            sp.coordinator_uid = None;
+        }
+        let text = title(&app);
+        assert!(text.contains("Rooms") && !text.contains("Rooms ◐") && !text.contains("Rooms ●"));
+    }
+
+    #[test]
+    fn test_zen_shows_only_the_track_without_borders() {
+        let mut app = busy_app();
+        app.zen = true;
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("E l v i s") && text.contains("Ratboys") && text.contains("1:23 / 3:51"));
+        assert!(!text.contains("Rooms") && !text.contains('╭'));
+
+        for sp in &mut app.speakers {
+            sp.track = None;
+        }
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert_eq!(text.trim(), "—");
+    }
+
+    #[test]
+    fn test_screensaver_clock_replaces_the_ui() {
+        let mut app = busy_app();
+        for sp in &mut app.speakers {
+            sp.state = "STOPPED".to_string();
+        }
+        app.clock = std::rc::Rc::new(crate::clock::MockClock::new(1_772_000_000)); // 06:13:20 UTC
+        app.last_activity = app.clock.now();
+        app.screensaver_after = Some(std::time::Duration::ZERO);
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("06:13"));
+        assert!(!text.contains("Rooms"));
+
+        app.screensaver_style = "dim".to_string();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert!(buffer.content().iter().map(|c| c.symbol()).collect::<String>().contains("Rooms"));
+        assert!(buffer.content().iter().all(|c| c.fg == SAVER_FG));
+    }
+
+    #[test]
+    fn test_mini_pane_shows_only_its_speaker() {
+        let mut app = busy_app();
+        app.mini = Some("office".to_string());
+        let mut terminal = Terminal::new(TestBackend::new(40, 9)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Office"));
+        assert!(text.contains("Elvis in the Freezer"));
+        assert!(!text.contains("Kitchen") && !text.contains("Rooms"));
+    }
+
+    #[test]
+    fn test_log_overlay_shows_newest_entries_that_fit() {
+        let mut app = busy_app();
+        app.clock = std::rc::Rc::new(crate::clock::MockClock::new(1_772_000_000)); // 06:13:20 UTC
+        for i in 0..30 {
+            app.log_event(format!("event {}", i));
+        }
+        app.log_open = true;
+        let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("06:13:20  event 29"));
+        assert!(!text.contains("event 19 "));
+        app.scroll_log(5);
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("event 24") && !text.contains("event 25"));
+    }
+
+    #[test]
+    fn test_stats_overlay_totals_listening_time() {
+        use crate::history::PlayEntry;
+        let mut app = busy_app();
+        let entry = |playlist: &str, duration| PlayEntry { playlist: playlist.to_string(), played_at: 1, duration };
+        app.stats = Some(vec![entry("altwave", Some(3 * 3600 + 420)), entry("jazz", Some(1500)), entry("jazz", None)]);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("3h 32m") && text.contains("+1 untimed"));
+        assert!(text.contains("25m  2 plays"));
+        assert!(text.contains("3 plays · 0 this week") && text.contains("2 playlists · longest streak 1 day"));
+        assert_eq!(format_listening(std::time::Duration::from_secs(59)), "0m");
+    }
+
+    #[test]
+    fn test_why_overlay_lists_the_plays_behind_the_rank() {
+        use crate::history::PlayEntry;
+        let mut app = busy_app();
+        let now = 1_772_000_000; // Wed 06:13:20 UTC
+        app.clock = std::rc::Rc::new(crate::clock::MockClock::new(now));
+        app.playlist_sort = "popularity".to_string();
+        let entry = |playlist: &str, played_at| PlayEntry { playlist: playlist.to_string(), played_at, duration: None };
+        app.why = Some(("jazz".to_string(), vec![entry("jazz", now - 90 * 60), entry("jazz", now - 2 * 86_400 - 3600), entry("jazz", now - 9 * 86_400)]));
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("2 plays in the last 7 days") && !text.contains("not by popularity"));
+        assert!(text.contains("1h 30m ago") && text.contains("2d 1h ago     Mon"));
+        assert_eq!(format_ago(30), "just now");
+        assert_eq!(format_ago(59 * 60), "59m ago");
+    }
+
+    #[test]
+    fn test_recap_overlay_summarises_the_week() {
+        use crate::history::PlayEntry;
+        let mut app = busy_app();
+        let now = 1_772_000_000; // Wed 06:13:20 UTC
+        app.clock = std::rc::Rc::new(crate::clock::MockClock::new(now));
+        let entry = |playlist: &str, played_at| PlayEntry { playlist: playlist.to_string(), played_at, duration: None };
+        app.recap = Some(vec![entry("jazz", now - 100), entry("jazz", now - 200), entry("altwave", now - 2 * 86_400), entry("jazz", now - 30 * 86_400)]);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("3 plays") && text.contains("Busiest: Wed (2 plays)"));
+        assert!(text.contains("TFSSMTW"));
+        assert!(text.contains("2 plays") && text.contains("1 play"));
+        assert_eq!(sparkline(&[0, 1, 2, 4]), "·▃▅█");
+
+        app.recap = Some(vec![entry("jazz", now - 30 * 86_400)]);
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("No tales were sung"));
+
+        // Monday and Tuesday tie: the later one wins
+        app.recap = Some(vec![entry("jazz", now - 2 * 86_400), entry("altwave", now - 86_400)]);
+        terminal.draw(|f| draw(f, &app)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Busiest: Tue (1 play)"));
+    }
+
+    #[test]
+    fn test_rooms_offset_follows_selection_and_centers_on_request() {
+        // 40 lines in a 10-line panel
+        assert_eq!(rooms_offset(0, 4, 40, 10, false), 0);
+        assert_eq!(rooms_offset(0, 12, 40, 10, false), 4);
+        assert_eq!(rooms_offset(10, 6, 40, 10, false), 6);
+        assert_eq!(rooms_offset(0, 20, 40, 10, true), 16);
+        assert_eq!(rooms_offset(0, 38, 40, 10, true), 30);
+        assert_eq!(rooms_offset(5, 2, 6, 10, true), 0);
+    }
+
+    #[test]
+    fn test_draw_survives_rapid_resizes() {
+        let app = busy_app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        for (w, h) in [(80, 24), (0, 0), (120, 40), (1, 50), (50, 1), (13, 7), (0, 24), (80, 0), (80, 24)] {
+            terminal.backend_mut().resize(w, h);
+            terminal.draw(|f| draw(f, &app)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_draw_tiny_terminals_do_not_panic() {
+        let mut app = busy_app();
+        app.show_osd(crate::app::OsdKind::Volume, 40);
+        app.set_status("Speak, friend, and enter.", crate::app::Linger::Long);
+        for (w, h) in [(1, 1), (3, 3)] {
+            for view in 0..4 {
+                app.dashboard_mode = view == 1;
+                app.expanded_now_playing = view == 2;
+                app.help_open = view == 3;
+                let mut terminal = Terminal::new(TestBackend::new(w, h)).unwrap();
+                terminal.draw(|f| draw(f, &app)).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_draw_now_playing_stacked_in_short_area() {
+        let app = busy_app();
+        for h in 0..6 {
+            let mut terminal = Terminal::new(TestBackend::new(40, h)).unwrap();
+            terminal.draw(|f| draw_now_playing(f, &app, f.area())).unwrap();
+        }
+    }
+}